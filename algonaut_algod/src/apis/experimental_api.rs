@@ -10,7 +10,7 @@
 
 use reqwest;
 
-use super::{configuration, Error};
+use super::{configuration, read_text, Error};
 use crate::apis::ResponseContent;
 
 /// struct for typed errors of method [`experimental_check`]
@@ -63,7 +63,7 @@ pub async fn experimental_check(
     let local_var_resp = local_var_client.execute(local_var_req).await?;
 
     let local_var_status = local_var_resp.status();
-    let local_var_content = local_var_resp.text().await?;
+    let local_var_content = read_text(local_var_configuration, local_var_resp).await?;
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         Ok(())
@@ -118,7 +118,7 @@ pub async fn get_ledger_state_delta(
     let local_var_resp = local_var_client.execute(local_var_req).await?;
 
     let local_var_status = local_var_resp.status();
-    let local_var_content = local_var_resp.text().await?;
+    let local_var_content = read_text(local_var_configuration, local_var_resp).await?;
 
     if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
         serde_json::from_str(&local_var_content).map_err(Error::from)
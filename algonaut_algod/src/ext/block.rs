@@ -1,6 +1,8 @@
+use super::block_header::BlockHeader;
 use super::transaction::TransactionHeader;
 use algonaut_crypto::HashDigest;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error, fmt};
 
 /// Block
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -19,8 +21,62 @@ pub struct BlockWithCertificateResponse {
 }
 
 impl BlockWithCertificateResponse {
-    pub fn hash(&self) -> HashDigest {
-        self.cert.prop.hash
+    /// The hash of the block, computed from its header and checked against the digest
+    /// the certificate's proposal commits to.
+    pub fn hash(&self) -> Result<HashDigest, BlockHashError> {
+        let hash = self
+            .block
+            .header()
+            .map_err(BlockHashError::Header)?
+            .hash()
+            .map_err(BlockHashError::Encoding)?;
+        if hash != self.cert.prop.hash {
+            return Err(BlockHashError::CertificateMismatch {
+                computed: hash,
+                certified: self.cert.prop.hash,
+            });
+        }
+        Ok(hash)
+    }
+}
+
+/// Why the hash of a [BlockWithCertificateResponse] couldn't be computed.
+#[derive(Debug)]
+pub enum BlockHashError {
+    Header(serde_json::Error),
+    Encoding(rmp_serde::encode::Error),
+    /// The header doesn't hash to the certified digest, e.g. because it has fields
+    /// [BlockHeader] doesn't know about.
+    CertificateMismatch {
+        computed: HashDigest,
+        certified: HashDigest,
+    },
+}
+
+impl fmt::Display for BlockHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockHashError::Header(e) => write!(f, "invalid block header: {}", e),
+            BlockHashError::Encoding(e) => write!(f, "couldn't encode block header: {}", e),
+            BlockHashError::CertificateMismatch {
+                computed,
+                certified,
+            } => write!(
+                f,
+                "block header hashes to {}, but the certificate is for {}",
+                computed, certified
+            ),
+        }
+    }
+}
+
+impl error::Error for BlockHashError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            BlockHashError::Header(e) => Some(e),
+            BlockHashError::Encoding(e) => Some(e),
+            BlockHashError::CertificateMismatch { .. } => None,
+        }
     }
 }
 
@@ -31,14 +87,20 @@ pub struct BlockCertificate {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BlockCertificateProp {
-    #[serde(rename = "dig")]
+    #[serde(rename = "dig", deserialize_with = "super::encoding::base64")]
     pub hash: HashDigest,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Block {
+    /// Bonus paid to the proposer on top of the collected fees, in microalgos.
+    #[serde(rename = "bi")]
+    pub bonus: Option<u64>,
     #[serde(rename = "earn")]
     pub rewards_level: Option<u64>,
+    /// Fees collected from the block's transactions, in microalgos.
+    #[serde(rename = "fc")]
+    pub fees_collected: Option<u64>,
     #[serde(rename = "fees")]
     pub fee_sink: Option<String>,
     #[serde(rename = "frac")]
@@ -47,10 +109,32 @@ pub struct Block {
     pub genesis_id: Option<String>,
     #[serde(rename = "gh")]
     pub genesis_hash: Option<String>,
+    #[serde(rename = "nextbefore")]
+    pub next_protocol_vote_before: Option<u64>,
+    #[serde(rename = "nextproto")]
+    pub next_protocol: Option<String>,
+    #[serde(rename = "nextswitch")]
+    pub next_protocol_switch_on: Option<u64>,
+    #[serde(rename = "nextyes")]
+    pub next_protocol_approvals: Option<u64>,
+    /// Online accounts marked offline by this block, because they're not proposing.
+    #[serde(rename = "partupdabs", default)]
+    pub absent_participation_accounts: Vec<String>,
+    /// Online accounts marked offline by this block, because their participation keys expired.
+    #[serde(rename = "partupdrmv", default)]
+    pub expired_participation_accounts: Vec<String>,
+    /// Amount paid to the proposer from the fee sink, in microalgos.
+    #[serde(rename = "pp")]
+    pub proposer_payout: Option<u64>,
     #[serde(rename = "prev")]
     pub branch: Option<String>,
+    #[serde(rename = "prev512")]
+    pub branch_sha512: Option<String>,
     #[serde(rename = "proto")]
     pub current_protocol: Option<String>,
+    /// The account that proposed this block. Set only on blocks with staking incentives.
+    #[serde(rename = "prp")]
+    pub proposer: Option<String>,
     #[serde(rename = "rate")]
     pub rewards_rate: Option<u64>,
     #[serde(rename = "rnd")]
@@ -61,10 +145,232 @@ pub struct Block {
     pub rewards_pool: Option<String>,
     #[serde(rename = "seed")]
     pub seed: Option<String>,
+    /// Keyed by state proof type.
+    #[serde(rename = "spt")]
+    pub state_proof_tracking: Option<HashMap<String, StateProofTracking>>,
+    /// Number of transactions committed before this block.
+    #[serde(rename = "tc")]
+    pub txn_counter: Option<u64>,
     #[serde(rename = "ts")]
     pub timestamp: Option<u64>,
+    /// Root of the SHA256 vector commitment of the block's transactions.
     #[serde(rename = "txn256")]
     pub txn_commitment: Option<String>,
+    /// Root of the SHA512/256 vector commitment of the block's transactions.
+    #[serde(rename = "txn")]
+    pub txn_commitment_sha512_256: Option<String>,
+    #[serde(rename = "txn512")]
+    pub txn_commitment_sha512: Option<String>,
     #[serde(rename = "txns")]
     pub txns: Option<Vec<TransactionHeader>>,
+    #[serde(rename = "upgradedelay")]
+    pub upgrade_delay: Option<u64>,
+    #[serde(rename = "upgradeprop")]
+    pub upgrade_propose: Option<String>,
+    #[serde(rename = "upgradeyes")]
+    pub upgrade_approve: Option<bool>,
+}
+
+impl Block {
+    /// The typed header of the block, e.g. to compute the block's hash.
+    pub fn header(&self) -> Result<BlockHeader, serde_json::Error> {
+        let mut value = serde_json::to_value(self)?;
+        if let serde_json::Value::Object(fields) = &mut value {
+            fields.retain(|name, field| !field.is_null() && name != "txns");
+        }
+        serde_json::from_value(value)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StateProofTracking {
+    /// Commitment to the voters of the next state proof.
+    #[serde(rename = "v", skip_serializing_if = "Option::is_none")]
+    pub voters_commitment: Option<String>,
+    #[serde(rename = "t", skip_serializing_if = "Option::is_none")]
+    pub online_total_weight: Option<u64>,
+    #[serde(rename = "n", skip_serializing_if = "Option::is_none")]
+    pub next_round: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::block_header::tests::{HEADER_JSON, NEXT_PREV};
+    use crate::ext::msgpack::to_json;
+    use data_encoding::{BASE32_NOPAD, BASE64, HEXLOWER};
+
+    fn block_with_certificate(dig: &[u8]) -> BlockWithCertificateResponse {
+        serde_json::from_str(&format!(
+            r#"{{"block": {HEADER_JSON}, "cert": {{"prop": {{"dig": "{}"}}}}}}"#,
+            BASE64.encode(dig)
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_certified_hash_is_computed_from_header() {
+        let hash = BASE32_NOPAD
+            .decode(NEXT_PREV.trim_start_matches("blk-").as_bytes())
+            .unwrap();
+
+        assert_eq!(hash, block_with_certificate(&hash).hash().unwrap().0);
+        assert!(matches!(
+            block_with_certificate(&[1; 32]).hash(),
+            Err(BlockHashError::CertificateMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decodes_incentive_block() {
+        // synthetic, with the fields of a mainnet block with staking incentives
+        let json = r#"{
+            "block": {
+                "bi": 9895000,
+                "earn": 218288,
+                "fc": 4000,
+                "fees": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                "frac": 6886250026,
+                "gen": "mainnet-v1.0",
+                "gh": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                "partupdabs": ["NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA"],
+                "partupdrmv": [
+                    "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                    "737777777777777777777777777777777777777777777777777UFEJ2CI"
+                ],
+                "pp": 9897000,
+                "prev": "blk-JZ6BWG7IT2JAHF2GHGGI5FQ3QXQEQ5MPJOVZU2D62TTKFD2V6CEA",
+                "proto": "https://github.com/algorandfoundation/specs/tree/236dcc18c9c507d794813ab768e467ea42d1b4d9",
+                "prp": "NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA",
+                "rnd": 46512890,
+                "rwcalr": 46500000,
+                "rwd": "737777777777777777777777777777777777777777777777777UFEJ2CI",
+                "seed": "4Wc2EpwiEdNcflxBRoUEaqJxGAjIOkAW2mBmbZX6Ptk=",
+                "spt": {
+                    "0": {
+                        "n": 46513152,
+                        "t": 1995433251471009,
+                        "v": "qDbgFxz6oT0K4jjx7gWa3nO9Nnn8/dHJ7i+Fe7bD4lXwl1XB0n0x8pfKjAyflwW3qQFRdhH1Pvi6H85+4T9P3w=="
+                    }
+                },
+                "tc": 2718346519,
+                "ts": 1736510450,
+                "txn": "Qx6ShPBPj/Jv3HbsQO5b0q6wxZqMYfPiDWOzHrQrHEY=",
+                "txn256": "O2+OcTdQ1xaV02zKP7OFGFjzRxzDBmwwLGXNBdBq1/Y=",
+                "txns": [{
+                    "hgi": true,
+                    "sig": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==",
+                    "txn": {
+                        "amt": 1000,
+                        "fee": 4000,
+                        "fv": 46512885,
+                        "lv": 46513885,
+                        "rcv": "NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA",
+                        "snd": "NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA",
+                        "type": "pay"
+                    }
+                }]
+            }
+        }"#;
+
+        let block = serde_json::from_str::<BlockResponse>(json).unwrap().block;
+
+        assert_eq!(Some(46512890), block.round);
+        assert_eq!(
+            Some("NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA".to_owned()),
+            block.proposer
+        );
+        assert_eq!(Some(4000), block.fees_collected);
+        assert_eq!(Some(9895000), block.bonus);
+        assert_eq!(Some(9897000), block.proposer_payout);
+        assert_eq!(1, block.absent_participation_accounts.len());
+        assert_eq!(2, block.expired_participation_accounts.len());
+        assert_eq!(Some(2718346519), block.txn_counter);
+        let tracking = &block.state_proof_tracking.as_ref().unwrap()["0"];
+        assert_eq!(Some(46513152), tracking.next_round);
+        assert_eq!(Some(1995433251471009), tracking.online_total_weight);
+        assert_eq!(46512890, block.header().unwrap().round);
+        assert_eq!(1, block.txns.unwrap().len());
+    }
+
+    /// algod's msgpack response for the block of [HEADER_JSON], encoded without this crate
+    /// with the canonical msgpack rules, as algod encodes blocks. Like the header, it's synthetic:
+    /// no block was captured from mainnet.
+    const INCENTIVE_BLOCK_MSGPACK: &str = concat!(
+        "81a5626c6f636bde0014a26269ce00989680a46561726ece000354b0a26663ce000f4240a4666565",
+        "73c420c7fccdb258f0d4189c2bf8b6d68ee697508642b0ad001f31fcb918c354ba859aa466726163",
+        "cf000000019a73d62aa367656eac6d61696e6e65742d76312e30a26768c420c061c4d8fc1dbdded2",
+        "d7604be4568e3f6d041987ac37bde4b620b5ab39248adfa27070ce004c4b40a470726576c4204e7c",
+        "1b1be89e92039746398c8e961b85e048758f4bab9a687ed4e6a28f55f088a570726f746fd9596874",
+        "7470733a2f2f6769746875622e636f6d2f616c676f72616e64666f756e646174696f6e2f73706563",
+        "732f747265652f323336646363313863396335303764373934383133616237363865343637656134",
+        "32643162346439a3707270c4206c719a94030a6c484bc6e29b04ac4c6d26b5fa504efe0dc439e9ae",
+        "4654421a90a3726e64ce02c5bafaa6727763616c72ce02cd29c0a3727764c420feffffffffffffff",
+        "ffffffffffffffffffffffffffffffffffffffffffffffffa473656564c42019b25856e1c150ca83",
+        "4cffc8b59b23adbd0ec0389e58eb22b3b64768098d002ba3737074810083a16ece02c5bc00a174cf",
+        "0006ed83c14fe000a176c44099aa2e93399a737dd6854a66e8c673b7112875bc0a0430360cc0ed33",
+        "33fa5e90898ea67d882c09c349d4ae9f27dbf62c5ffff4f4a5ccb62f4740c816019a4076a27463ce",
+        "a6e49c00a27473ce67810bf2a374786ec420fc44678719aab6149ed0469ec5336d56ebb6a4504d9c",
+        "28903b90d8f92b65a490a674786e323536c4202aca4d073326002e1964617ff19c6aacba878cd05f",
+        "1f67e6803df8937e734f2a",
+    );
+
+    #[test]
+    fn test_decodes_incentive_block_from_msgpack() {
+        let bytes = HEXLOWER.decode(INCENTIVE_BLOCK_MSGPACK.as_bytes()).unwrap();
+
+        let block = serde_json::from_value::<BlockResponse>(to_json(&bytes).unwrap())
+            .unwrap()
+            .block;
+
+        assert_eq!(
+            Some("NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA".to_owned()),
+            block.proposer
+        );
+        assert_eq!(Some(1000000), block.fees_collected);
+        assert_eq!(Some(10000000), block.bonus);
+        assert_eq!(Some(5000000), block.proposer_payout);
+        let tracking = &block.state_proof_tracking.as_ref().unwrap()["0"];
+        assert_eq!(Some(1950000000000000), tracking.online_total_weight);
+        assert_eq!(
+            NEXT_PREV.trim_start_matches("blk-"),
+            BASE32_NOPAD.encode(&block.header().unwrap().hash().unwrap().0)
+        );
+    }
+
+    #[test]
+    fn test_decodes_block_without_incentives() {
+        let json = r#"{
+            "block": {
+                "earn": 27521,
+                "fees": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                "frac": 2006530153,
+                "gen": "mainnet-v1.0",
+                "gh": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                "prev": "blk-WLQV4GN6ZQ6QJRPDYKQ33JV4XHLPKLW6ZN7KMLU3LTQMI7SMH3SQ",
+                "proto": "https://github.com/algorandfoundation/specs/tree/bc36005dbd776e6d1eaf0c560619bb183215645c",
+                "rate": 24999910,
+                "rnd": 20000000,
+                "rwcalr": 20500000,
+                "rwd": "737777777777777777777777777777777777777777777777777UFEJ2CI",
+                "seed": "iCx9Ao5Ev+2YCWBzWlgmXhHVSN0lWTeMjIIgUL/cl1Q=",
+                "tc": 735462861,
+                "ts": 1651137950,
+                "txn": "nQ3a5sXvL7vYpDVb2DfX7ZPg5gk8m1T8fb+oaeqYt9s="
+            }
+        }"#;
+
+        let block = serde_json::from_str::<BlockResponse>(json).unwrap().block;
+
+        assert_eq!(Some(20000000), block.round);
+        assert_eq!(None, block.proposer);
+        assert_eq!(None, block.fees_collected);
+        assert_eq!(None, block.bonus);
+        assert_eq!(None, block.proposer_payout);
+        assert!(block.absent_participation_accounts.is_empty());
+        assert!(block.expired_participation_accounts.is_empty());
+        assert!(block.state_proof_tracking.is_none());
+        assert_eq!(20000000, block.header().unwrap().round);
+        assert!(block.txns.is_none());
+    }
 }
@@ -0,0 +1,431 @@
+use super::encoding;
+use algonaut_core::{Address, MicroAlgos};
+use algonaut_crypto::HashDigest;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512_256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Domain separation prefix of block header hashes.
+const BLOCK_HEADER_PREFIX: &[u8] = b"BH";
+
+/// A typed block header, decoded from JSON or msgpack.
+///
+/// Fields omitted by algod (zero values) are set to their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct BlockHeader {
+    #[serde(rename = "rnd", default)]
+    pub round: u64,
+    /// Hash of the previous block.
+    #[serde(rename = "prev", default, deserialize_with = "encoding::block_hash")]
+    pub branch: HashDigest,
+    /// SHA512 hash of the previous block, set only once the consensus protocol enables it.
+    #[serde(rename = "prev512", default, deserialize_with = "encoding::bytes")]
+    pub branch_sha512: Vec<u8>,
+    #[serde(rename = "seed", default, deserialize_with = "encoding::base64")]
+    pub seed: HashDigest,
+    /// Root of the SHA512/256 vector commitment of the block's transactions.
+    #[serde(rename = "txn", default, deserialize_with = "encoding::base64")]
+    pub txn_commitment: HashDigest,
+    /// Root of the SHA256 vector commitment of the block's transactions.
+    #[serde(rename = "txn256", default, deserialize_with = "encoding::base64")]
+    pub txn_commitment_sha256: HashDigest,
+    /// Root of the SHA512 vector commitment of the block's transactions, set only once the
+    /// consensus protocol enables it.
+    #[serde(rename = "txn512", default, deserialize_with = "encoding::bytes")]
+    pub txn_commitment_sha512: Vec<u8>,
+    #[serde(rename = "ts", default)]
+    pub timestamp: i64,
+    #[serde(rename = "gen", default)]
+    pub genesis_id: String,
+    #[serde(rename = "gh", default, deserialize_with = "encoding::base64")]
+    pub genesis_hash: HashDigest,
+    /// The account that proposed this block. Set only on blocks with staking incentives.
+    #[serde(rename = "prp", default, deserialize_with = "encoding::opt_address")]
+    pub proposer: Option<Address>,
+    #[serde(rename = "fc", default)]
+    pub fees_collected: MicroAlgos,
+    #[serde(rename = "bi", default)]
+    pub bonus: MicroAlgos,
+    #[serde(rename = "pp", default)]
+    pub proposer_payout: MicroAlgos,
+    #[serde(rename = "fees", default, deserialize_with = "encoding::opt_address")]
+    pub fee_sink: Option<Address>,
+    #[serde(rename = "rwd", default, deserialize_with = "encoding::opt_address")]
+    pub rewards_pool: Option<Address>,
+    #[serde(rename = "earn", default)]
+    pub rewards_level: u64,
+    #[serde(rename = "rate", default)]
+    pub rewards_rate: u64,
+    #[serde(rename = "frac", default)]
+    pub rewards_residue: u64,
+    #[serde(rename = "rwcalr", default)]
+    pub rewards_recalculation_round: u64,
+    #[serde(rename = "proto", default)]
+    pub current_protocol: String,
+    #[serde(rename = "nextproto", default)]
+    pub next_protocol: String,
+    #[serde(rename = "nextyes", default)]
+    pub next_protocol_approvals: u64,
+    #[serde(rename = "nextbefore", default)]
+    pub next_protocol_vote_before: u64,
+    #[serde(rename = "nextswitch", default)]
+    pub next_protocol_switch_on: u64,
+    #[serde(rename = "upgradeprop", default)]
+    pub upgrade_propose: String,
+    #[serde(rename = "upgradedelay", default)]
+    pub upgrade_delay: u64,
+    #[serde(rename = "upgradeyes", default)]
+    pub upgrade_approve: bool,
+    /// Number of transactions committed before this block.
+    #[serde(rename = "tc", default)]
+    pub txn_counter: u64,
+    /// Keyed by state proof type.
+    #[serde(rename = "spt", default)]
+    pub state_proof_tracking: HashMap<u64, StateProofTrackingData>,
+    /// Online accounts marked offline by this block, because their participation keys expired.
+    #[serde(
+        rename = "partupdrmv",
+        default,
+        deserialize_with = "encoding::addresses"
+    )]
+    pub expired_participation_accounts: Vec<Address>,
+    /// Online accounts marked offline by this block, because they're not proposing.
+    #[serde(
+        rename = "partupdabs",
+        default,
+        deserialize_with = "encoding::addresses"
+    )]
+    pub absent_participation_accounts: Vec<Address>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct StateProofTrackingData {
+    /// Commitment to the voters of the next state proof.
+    #[serde(rename = "v", default, deserialize_with = "encoding::bytes")]
+    pub voters_commitment: Vec<u8>,
+    #[serde(rename = "t", default)]
+    pub online_total_weight: MicroAlgos,
+    #[serde(rename = "n", default)]
+    pub next_round: u64,
+}
+
+impl BlockHeader {
+    /// The hash of the block, which the next block references as `prev`.
+    ///
+    /// Hashes the canonical msgpack encoding of the header, so a header with fields
+    /// this model doesn't know about doesn't hash to the block's actual hash.
+    pub fn hash(&self) -> Result<HashDigest, rmp_serde::encode::Error> {
+        Ok(HashDigest(
+            Sha512_256::digest([BLOCK_HEADER_PREFIX, &self.canonical_msg_pack()?].concat()).into(),
+        ))
+    }
+
+    fn canonical_msg_pack(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        rmp_serde::to_vec_named(&CanonicalBlockHeader::from(self))
+    }
+}
+
+/// The header as algod encodes it for hashing: fields sorted by name, without zero values.
+#[derive(Serialize)]
+struct CanonicalBlockHeader<'a> {
+    #[serde(rename = "bi", skip_serializing_if = "is_zero")]
+    bonus: u64,
+    #[serde(rename = "earn", skip_serializing_if = "is_zero")]
+    rewards_level: u64,
+    #[serde(rename = "fc", skip_serializing_if = "is_zero")]
+    fees_collected: u64,
+    #[serde(rename = "fees", skip_serializing_if = "Option::is_none")]
+    fee_sink: Option<&'a Address>,
+    #[serde(rename = "frac", skip_serializing_if = "is_zero")]
+    rewards_residue: u64,
+    #[serde(rename = "gen", skip_serializing_if = "str::is_empty")]
+    genesis_id: &'a str,
+    #[serde(rename = "gh", skip_serializing_if = "is_zero_hash")]
+    genesis_hash: &'a HashDigest,
+    #[serde(rename = "nextbefore", skip_serializing_if = "is_zero")]
+    next_protocol_vote_before: u64,
+    #[serde(rename = "nextproto", skip_serializing_if = "str::is_empty")]
+    next_protocol: &'a str,
+    #[serde(rename = "nextswitch", skip_serializing_if = "is_zero")]
+    next_protocol_switch_on: u64,
+    #[serde(rename = "nextyes", skip_serializing_if = "is_zero")]
+    next_protocol_approvals: u64,
+    #[serde(rename = "partupdabs", skip_serializing_if = "<[_]>::is_empty")]
+    absent_participation_accounts: &'a [Address],
+    #[serde(rename = "partupdrmv", skip_serializing_if = "<[_]>::is_empty")]
+    expired_participation_accounts: &'a [Address],
+    #[serde(rename = "pp", skip_serializing_if = "is_zero")]
+    proposer_payout: u64,
+    #[serde(rename = "prev", skip_serializing_if = "is_zero_hash")]
+    branch: &'a HashDigest,
+    #[serde(
+        rename = "prev512",
+        with = "serde_bytes",
+        skip_serializing_if = "is_zero_bytes"
+    )]
+    branch_sha512: &'a [u8],
+    #[serde(rename = "proto", skip_serializing_if = "str::is_empty")]
+    current_protocol: &'a str,
+    #[serde(rename = "prp", skip_serializing_if = "Option::is_none")]
+    proposer: Option<&'a Address>,
+    #[serde(rename = "rate", skip_serializing_if = "is_zero")]
+    rewards_rate: u64,
+    #[serde(rename = "rnd", skip_serializing_if = "is_zero")]
+    round: u64,
+    #[serde(rename = "rwcalr", skip_serializing_if = "is_zero")]
+    rewards_recalculation_round: u64,
+    #[serde(rename = "rwd", skip_serializing_if = "Option::is_none")]
+    rewards_pool: Option<&'a Address>,
+    #[serde(rename = "seed", skip_serializing_if = "is_zero_hash")]
+    seed: &'a HashDigest,
+    #[serde(rename = "spt", skip_serializing_if = "BTreeMap::is_empty")]
+    state_proof_tracking: BTreeMap<u64, CanonicalStateProofTrackingData<'a>>,
+    #[serde(rename = "tc", skip_serializing_if = "is_zero")]
+    txn_counter: u64,
+    #[serde(rename = "ts", skip_serializing_if = "is_zero_i64")]
+    timestamp: i64,
+    #[serde(rename = "txn", skip_serializing_if = "is_zero_hash")]
+    txn_commitment: &'a HashDigest,
+    #[serde(rename = "txn256", skip_serializing_if = "is_zero_hash")]
+    txn_commitment_sha256: &'a HashDigest,
+    #[serde(
+        rename = "txn512",
+        with = "serde_bytes",
+        skip_serializing_if = "is_zero_bytes"
+    )]
+    txn_commitment_sha512: &'a [u8],
+    #[serde(rename = "upgradedelay", skip_serializing_if = "is_zero")]
+    upgrade_delay: u64,
+    #[serde(rename = "upgradeprop", skip_serializing_if = "str::is_empty")]
+    upgrade_propose: &'a str,
+    #[serde(rename = "upgradeyes", skip_serializing_if = "is_false")]
+    upgrade_approve: bool,
+}
+
+#[derive(Serialize)]
+struct CanonicalStateProofTrackingData<'a> {
+    #[serde(rename = "n", skip_serializing_if = "is_zero")]
+    next_round: u64,
+    #[serde(rename = "t", skip_serializing_if = "is_zero")]
+    online_total_weight: u64,
+    #[serde(
+        rename = "v",
+        with = "serde_bytes",
+        skip_serializing_if = "<[_]>::is_empty"
+    )]
+    voters_commitment: &'a [u8],
+}
+
+impl<'a> From<&'a BlockHeader> for CanonicalBlockHeader<'a> {
+    fn from(header: &'a BlockHeader) -> Self {
+        CanonicalBlockHeader {
+            bonus: header.bonus.0,
+            rewards_level: header.rewards_level,
+            fees_collected: header.fees_collected.0,
+            fee_sink: non_zero_address(&header.fee_sink),
+            rewards_residue: header.rewards_residue,
+            genesis_id: &header.genesis_id,
+            genesis_hash: &header.genesis_hash,
+            next_protocol_vote_before: header.next_protocol_vote_before,
+            next_protocol: &header.next_protocol,
+            next_protocol_switch_on: header.next_protocol_switch_on,
+            next_protocol_approvals: header.next_protocol_approvals,
+            absent_participation_accounts: &header.absent_participation_accounts,
+            expired_participation_accounts: &header.expired_participation_accounts,
+            proposer_payout: header.proposer_payout.0,
+            branch: &header.branch,
+            branch_sha512: &header.branch_sha512,
+            current_protocol: &header.current_protocol,
+            proposer: non_zero_address(&header.proposer),
+            rewards_rate: header.rewards_rate,
+            round: header.round,
+            rewards_recalculation_round: header.rewards_recalculation_round,
+            rewards_pool: non_zero_address(&header.rewards_pool),
+            seed: &header.seed,
+            state_proof_tracking: header
+                .state_proof_tracking
+                .iter()
+                .map(|(state_proof_type, data)| {
+                    (
+                        *state_proof_type,
+                        CanonicalStateProofTrackingData {
+                            next_round: data.next_round,
+                            online_total_weight: data.online_total_weight.0,
+                            voters_commitment: &data.voters_commitment,
+                        },
+                    )
+                })
+                .collect(),
+            txn_counter: header.txn_counter,
+            timestamp: header.timestamp,
+            txn_commitment: &header.txn_commitment,
+            txn_commitment_sha256: &header.txn_commitment_sha256,
+            txn_commitment_sha512: &header.txn_commitment_sha512,
+            upgrade_delay: header.upgrade_delay,
+            upgrade_propose: &header.upgrade_propose,
+            upgrade_approve: header.upgrade_approve,
+        }
+    }
+}
+
+fn non_zero_address(address: &Option<Address>) -> Option<&Address> {
+    address.as_ref().filter(|address| address.0 != [0; 32])
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+fn is_zero_i64(value: &i64) -> bool {
+    *value == 0
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_zero_hash(hash: &&HashDigest) -> bool {
+    hash.0 == [0; 32]
+}
+
+fn is_zero_bytes(bytes: &&[u8]) -> bool {
+    bytes.iter().all(|b| *b == 0)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_encoding_omits_zero_values() {
+        let header = BlockHeader {
+            round: 1,
+            genesis_id: "a".to_owned(),
+            upgrade_approve: true,
+            proposer: Some(Address([0; 32])),
+            ..BlockHeader::default()
+        };
+
+        let encoded = header.canonical_msg_pack().unwrap();
+
+        // {"gen": "a", "rnd": 1, "upgradeyes": true}
+        assert_eq!(
+            [
+                b"\x83\xa3gen\xa1a\xa3rnd\x01\xaaupgradeyes".as_slice(),
+                &[0xc3]
+            ]
+            .concat(),
+            encoded
+        );
+    }
+
+    #[test]
+    fn test_canonical_encoding_sorts_fields() {
+        let header: BlockHeader = serde_json::from_str(
+            r#"{
+                "earn": 218288,
+                "fees": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                "gh": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                "prev": "blk-JZ6BWG7IT2JAHF2GHGGI5FQ3QXQEQ5MPJOVZU2D62TTKFD2V6CEA",
+                "rnd": 46512890,
+                "spt": { "0": { "n": 46513152 } },
+                "ts": 1736510450
+            }"#,
+        )
+        .unwrap();
+
+        let keys = encoded_keys(&header.canonical_msg_pack().unwrap());
+
+        assert_eq!(vec!["earn", "fees", "gh", "prev", "rnd", "spt", "ts"], keys);
+        assert_ne!(
+            header.hash().unwrap(),
+            BlockHeader::default().hash().unwrap()
+        );
+    }
+
+    /// A synthetic header with the fields of a mainnet header with staking incentives,
+    /// as algod returns it as JSON.
+    ///
+    /// It's not a captured block: its `prev`, commitments and state proof tracking data are made
+    /// up, so it doesn't match mainnet's block of that round. It should be replaced by a captured
+    /// mainnet header and the `prev` of its next block.
+    pub(crate) const HEADER_JSON: &str = r#"{
+        "bi": 10000000,
+        "earn": 218288,
+        "fc": 1000000,
+        "fees": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+        "frac": 6886250026,
+        "gen": "mainnet-v1.0",
+        "gh": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+        "pp": 5000000,
+        "prev": "blk-JZ6BWG7IT2JAHF2GHGGI5FQ3QXQEQ5MPJOVZU2D62TTKFD2V6CEA",
+        "proto": "https://github.com/algorandfoundation/specs/tree/236dcc18c9c507d794813ab768e467ea42d1b4d9",
+        "prp": "NRYZVFADBJWEQS6G4KNQJLCMNUTLL6SQJ37A3RBZ5GXEMVCCDKID36MUPA",
+        "rnd": 46512890,
+        "rwcalr": 47000000,
+        "rwd": "737777777777777777777777777777777777777777777777777UFEJ2CI",
+        "seed": "GbJYVuHBUMqDTP/ItZsjrb0OwDieWOsis7ZHaAmNACs=",
+        "spt": {
+            "0": {
+                "n": 46513152,
+                "t": 1950000000000000,
+                "v": "maoukzmac33WhUpm6MZztxEodbwKBDA2DMDtMzP6XpCJjqZ9iCwJw0nUrp8n2/YsX//09KXMti9HQMgWAZpAdg=="
+            }
+        },
+        "tc": 2800000000,
+        "ts": 1736510450,
+        "txn": "/ERnhxmqthSe0EaexTNtVuu2pFBNnCiQO5DY+StlpJA=",
+        "txn256": "KspNBzMmAC4ZZGF/8ZxqrLqHjNBfH2fmgD34k35zTyo="
+    }"#;
+
+    /// The hash of [HEADER_JSON], as the next block's `prev`. Like the header it isn't taken from
+    /// mainnet: it was computed without this crate, by encoding the header by hand following
+    /// go-algorand's canonical msgpack rules (keys sorted, zero values omitted, integers in their
+    /// shortest unsigned form) and hashing it with the `BH` prefix.
+    pub(crate) const NEXT_PREV: &str = "blk-3OXUSPNBHYRBQA6K6EPDRTCW3ZF3YW3HNHJTQT7OPS7CMXCZ6F7Q";
+
+    #[test]
+    fn test_hash_is_next_blocks_prev() {
+        let header: BlockHeader = serde_json::from_str(HEADER_JSON).unwrap();
+        let next: BlockHeader =
+            serde_json::from_str(&format!(r#"{{"prev": "{NEXT_PREV}", "rnd": 46512891}}"#))
+                .unwrap();
+
+        assert_eq!(next.branch, header.hash().unwrap());
+    }
+
+    /// The keys of an encoded map, in order.
+    fn encoded_keys(encoded: &[u8]) -> Vec<String> {
+        let entries = rmp_serde::from_slice::<KeysInOrder>(encoded).unwrap().0;
+        entries.into_iter().map(|(key, _)| key).collect()
+    }
+
+    struct KeysInOrder(Vec<(String, serde::de::IgnoredAny)>);
+
+    impl<'de> Deserialize<'de> for KeysInOrder {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct KeysVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for KeysVisitor {
+                type Value = KeysInOrder;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                fn visit_map<A: serde::de::MapAccess<'de>>(
+                    self,
+                    mut map: A,
+                ) -> Result<Self::Value, A::Error> {
+                    let mut keys = vec![];
+                    while let Some(entry) = map.next_entry()? {
+                        keys.push(entry);
+                    }
+                    Ok(KeysInOrder(keys))
+                }
+            }
+
+            deserializer.deserialize_map(KeysVisitor)
+        }
+    }
+}
@@ -0,0 +1,888 @@
+//! Conversion of block transactions into [algonaut_transaction] domain types.
+
+use super::{
+    block::Block,
+    transaction::{self as ext, DeltaAction, TransactionHeader},
+};
+use algonaut_core::{
+    Address, MicroAlgos, MultisigSignature, MultisigSubsig, Round, StateProofPk, VotePk, VrfPk,
+};
+use algonaut_crypto::{Ed25519PublicKey, HashDigest, Signature};
+use algonaut_encoding::decode_base64;
+use algonaut_model::transaction::{
+    ApiAssetParams, ApiBoxReference, ApiHeartbeat, ApiSignedLogic, ApiSignedLogicArg,
+    ApiStateSchema, ApiTransaction, AppArgument, HeartbeatProof, StateProofType,
+};
+use algonaut_transaction::{
+    apply_data::{
+        ApplyData, EvalDelta, InnerTransaction, SignedTransactionWithApplyData, StateDelta,
+        ValueDelta,
+    },
+    error::TransactionError,
+    transaction::TransactionSignature,
+    SignedTransaction, Transaction,
+};
+use serde::de::DeserializeOwned;
+use std::convert::{TryFrom, TryInto};
+
+impl Block {
+    /// Converts the block's transactions, see [TransactionHeader::to_signed_transaction].
+    pub fn signed_transactions(
+        &self,
+    ) -> Result<Vec<SignedTransactionWithApplyData>, TransactionError> {
+        let genesis_hash = opt(&self.genesis_hash, hash_digest)?;
+        self.txns
+            .iter()
+            .flatten()
+            .map(|header| {
+                header.to_signed_transaction(self.genesis_id.as_deref(), genesis_hash.as_ref())
+            })
+            .collect()
+    }
+}
+
+impl TransactionHeader {
+    /// Converts a transaction of a block, with its apply data and inner transactions.
+    ///
+    /// Blocks don't repeat the genesis id and hash in their transactions,
+    /// so they're restored from the given genesis id and hash of the block, to compute the transaction's id.
+    ///
+    /// Note that algod encodes state keys, state byte values and logs as strings,
+    /// so bytes that aren't valid UTF-8 can't be recovered.
+    pub fn to_signed_transaction(
+        &self,
+        genesis_id: Option<&str>,
+        genesis_hash: Option<&HashDigest>,
+    ) -> Result<SignedTransactionWithApplyData, TransactionError> {
+        let mut api_t = api_transaction(required(&self.txn, "txn")?)?;
+        if self.hgi == Some(true) {
+            api_t.genesis_id = genesis_id.map(ToOwned::to_owned);
+        }
+        // current protocol versions require the genesis hash, so it's removed from all transactions,
+        // regardless of hgh
+        if api_t.genesis_hash == HashDigest::default() {
+            if let Some(genesis_hash) = genesis_hash {
+                api_t.genesis_hash = *genesis_hash;
+            }
+        }
+
+        let transaction = Transaction::try_from(api_t)?;
+        let raw_id = transaction.raw_id()?;
+        Ok(SignedTransactionWithApplyData {
+            signed_transaction: SignedTransaction {
+                transaction_id: transaction.id()?,
+                transaction,
+                sig: signature(self)?,
+                auth_address: opt(&self.sgnr, address)?,
+            },
+            apply_data: apply_data(self.apply_data.as_ref(), &raw_id)?,
+        })
+    }
+}
+
+fn inner_transaction(
+    header: &TransactionHeader,
+    parent_id: &HashDigest,
+    index: u64,
+) -> Result<InnerTransaction, TransactionError> {
+    let transaction = Transaction::try_from(api_transaction(required(&header.txn, "txn")?)?)?;
+    let raw_id = transaction.inner_raw_id(parent_id, index)?;
+    Ok(InnerTransaction {
+        transaction_id: transaction.inner_id(parent_id, index)?,
+        transaction,
+        apply_data: apply_data(header.apply_data.as_ref(), &raw_id)?,
+    })
+}
+
+fn signature(header: &TransactionHeader) -> Result<TransactionSignature, TransactionError> {
+    match (&header.sig, &header.msig, &header.lsig) {
+        (Some(sig), None, None) => Ok(TransactionSignature::Single(signature_bytes(sig)?)),
+        (None, Some(msig), None) => Ok(TransactionSignature::Multi(multisig(msig)?)),
+        (None, None, Some(lsig)) => Ok(TransactionSignature::Logic(logic_sig(lsig)?.try_into()?)),
+        (None, None, None) => Ok(TransactionSignature::Unsigned),
+        _ => Err(TransactionError::Deserialization(
+            "Expected at most one of sig, msig and lsig".to_owned(),
+        )),
+    }
+}
+
+fn multisig(msig: &ext::MultisigSig) -> Result<MultisigSignature, TransactionError> {
+    Ok(MultisigSignature {
+        subsigs: msig
+            .subsigs
+            .iter()
+            .flatten()
+            .map(|subsig| {
+                Ok(MultisigSubsig {
+                    key: Ed25519PublicKey(array(required(&subsig.key, "pk")?)?),
+                    sig: opt(&subsig.sig, signature_bytes)?,
+                })
+            })
+            .collect::<Result<_, TransactionError>>()?,
+        threshold: msig.threshold.unwrap_or_default(),
+        version: msig.version.unwrap_or_default(),
+    })
+}
+
+fn logic_sig(lsig: &ext::LogicSig) -> Result<ApiSignedLogic, TransactionError> {
+    Ok(ApiSignedLogic {
+        args: vec(&lsig.args, |arg| Ok(ApiSignedLogicArg(bytes(arg)?)))?.unwrap_or_default(),
+        logic: bytes(required(&lsig.logic, "l")?)?,
+        msig: lsig.msig.as_ref().map(multisig).transpose()?,
+        sig: opt(&lsig.sig, signature_bytes)?,
+    })
+}
+
+fn api_transaction(txn: &ext::Transaction) -> Result<ApiTransaction, TransactionError> {
+    let header = header(txn);
+    let mut api_t = ApiTransaction {
+        fee: header.fee.map(MicroAlgos),
+        first_valid: header.first_valid.map(Round),
+        genesis_id: header.genesis_id.clone(),
+        genesis_hash: opt(header.genesis_hash, hash_digest)?.unwrap_or_default(),
+        group: opt(header.group, hash_digest)?,
+        last_valid: header.last_valid.map(Round),
+        lease: opt(header.lease, hash_digest)?,
+        note: opt(header.note, bytes)?,
+        rekey_to: opt(header.rekey, address)?,
+        sender: address(required(header.sender, "sender")?)?,
+        type_: header.type_.to_owned(),
+        ///////////////
+        asset_amount: None,
+        asset_close_to: None,
+        frozen: None,
+        amount: None,
+        app_arguments: None,
+        on_complete: None,
+        approval_program: None,
+        asset_params: None,
+        foreign_assets: None,
+        accounts: None,
+        foreign_apps: None,
+        global_state_schema: None,
+        app_id: None,
+        local_state_schema: None,
+        clear_state_program: None,
+        asset_receiver: None,
+        asset_sender: None,
+        config_asset: None,
+        close_reminder_to: None,
+        freeze_account: None,
+        asset_id: None,
+        receiver: None,
+        selection_pk: None,
+        state_proof_pk: None,
+        vote_first: None,
+        vote_key_dilution: None,
+        vote_pk: None,
+        vote_last: None,
+        xfer: None,
+        nonparticipating: None,
+        boxes: None,
+        extra_pages: None,
+        state_proof_type: None,
+        state_proof: None,
+        state_proof_message: None,
+        heartbeat: None,
+    };
+
+    match txn {
+        ext::Transaction::Payment {
+            receiver,
+            amount,
+            close_remainder_to,
+            ..
+        } => {
+            api_t.receiver = opt(receiver, address)?;
+            api_t.amount = *amount;
+            api_t.close_reminder_to = opt(close_remainder_to, address)?;
+        }
+        ext::Transaction::KeyRegistration {
+            vote_pk,
+            selection_pk,
+            state_proof_pk,
+            vote_first,
+            vote_last,
+            vote_key_dilution,
+            nonparticipating,
+            ..
+        } => {
+            api_t.vote_pk = opt(vote_pk, |k| Ok(VotePk(array(k)?)))?;
+            api_t.selection_pk = opt(selection_pk, |k| Ok(VrfPk(array(k)?)))?;
+            api_t.state_proof_pk = opt(state_proof_pk, |k| Ok(StateProofPk(array(k)?)))?;
+            api_t.vote_first = vote_first.map(Round);
+            api_t.vote_last = vote_last.map(Round);
+            api_t.vote_key_dilution = *vote_key_dilution;
+            api_t.nonparticipating = *nonparticipating;
+        }
+        ext::Transaction::AssetConfig {
+            config_asset,
+            params,
+            ..
+        } => {
+            api_t.config_asset = *config_asset;
+            api_t.asset_params = params.as_ref().map(asset_params).transpose()?;
+        }
+        ext::Transaction::AssetTransfer {
+            asset_xfer,
+            asset_amount,
+            asset_sender,
+            asset_receiver,
+            asset_close_remainder_to,
+            ..
+        } => {
+            api_t.xfer = *asset_xfer;
+            api_t.asset_amount = *asset_amount;
+            api_t.asset_sender = opt(asset_sender, address)?;
+            api_t.asset_receiver = opt(asset_receiver, address)?;
+            api_t.asset_close_to = opt(asset_close_remainder_to, address)?;
+        }
+        ext::Transaction::AssetFreeze {
+            freeze_account,
+            asset_id,
+            frozen,
+            ..
+        } => {
+            api_t.freeze_account = opt(freeze_account, address)?;
+            api_t.asset_id = *asset_id;
+            api_t.frozen = *frozen;
+        }
+        ext::Transaction::Application {
+            app_id,
+            on_complete,
+            accounts,
+            approval_program,
+            app_arguments,
+            clear_state_program,
+            foreign_apps,
+            foreign_assets,
+            global_state_schema,
+            local_state_schema,
+            extra_program_pages,
+            boxes,
+            ..
+        } => {
+            api_t.app_id = *app_id;
+            api_t.on_complete = on_complete.map(|on_complete| on_complete as u32);
+            api_t.accounts = vec(accounts, address)?;
+            api_t.approval_program = opt(approval_program, bytes)?;
+            api_t.app_arguments = vec(app_arguments, |arg| Ok(AppArgument(bytes(arg)?)))?;
+            api_t.clear_state_program = opt(clear_state_program, bytes)?;
+            api_t.foreign_apps = foreign_apps.clone();
+            api_t.foreign_assets = foreign_assets.clone();
+            api_t.global_state_schema = global_state_schema.as_ref().map(state_schema);
+            api_t.local_state_schema = local_state_schema.as_ref().map(state_schema);
+            api_t.extra_pages = extra_program_pages.map(|pages| pages as u32);
+            api_t.boxes = boxes
+                .as_ref()
+                .map(|boxes| {
+                    boxes
+                        .iter()
+                        .map(|box_| {
+                            Ok(ApiBoxReference {
+                                index: box_.index,
+                                name: opt(&box_.name, bytes)?.unwrap_or_default(),
+                            })
+                        })
+                        .collect::<Result<_, TransactionError>>()
+                })
+                .transpose()?;
+        }
+        ext::Transaction::Heartbeat { heartbeat: hb, .. } => {
+            api_t.heartbeat = hb.as_ref().map(heartbeat).transpose()?;
+        }
+        ext::Transaction::StateProof {
+            state_proof_type,
+            state_proof,
+            message,
+            ..
+        } => {
+            api_t.state_proof_type = state_proof_type
+                .map(StateProofType::try_from)
+                .transpose()
+                .map_err(TransactionError::Deserialization)?;
+            api_t.state_proof = state_proof.as_ref().map(json).transpose()?;
+            api_t.state_proof_message = message.as_ref().map(json).transpose()?;
+        }
+    }
+    Ok(api_t)
+}
+
+/// The fields all transaction types have.
+struct Header<'a> {
+    type_: &'a str,
+    fee: &'a Option<u64>,
+    first_valid: &'a Option<u64>,
+    genesis_hash: &'a Option<String>,
+    last_valid: &'a Option<u64>,
+    sender: &'a Option<String>,
+    genesis_id: &'a Option<String>,
+    group: &'a Option<String>,
+    lease: &'a Option<String>,
+    note: &'a Option<String>,
+    rekey: &'a Option<String>,
+}
+
+fn header(txn: &ext::Transaction) -> Header<'_> {
+    let type_ = match txn {
+        ext::Transaction::Payment { .. } => "pay",
+        ext::Transaction::KeyRegistration { .. } => "keyreg",
+        ext::Transaction::AssetConfig { .. } => "acfg",
+        ext::Transaction::AssetTransfer { .. } => "axfer",
+        ext::Transaction::AssetFreeze { .. } => "afrz",
+        ext::Transaction::Application { .. } => "appl",
+        ext::Transaction::Heartbeat { .. } => "hb",
+        ext::Transaction::StateProof { .. } => "stpf",
+    };
+    match txn {
+        ext::Transaction::Payment {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::KeyRegistration {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::AssetConfig {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::AssetTransfer {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::AssetFreeze {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::Application {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::Heartbeat {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        }
+        | ext::Transaction::StateProof {
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+            ..
+        } => Header {
+            type_,
+            fee,
+            first_valid,
+            genesis_hash,
+            last_valid,
+            sender,
+            genesis_id,
+            group,
+            lease,
+            note,
+            rekey,
+        },
+    }
+}
+
+fn asset_params(params: &ext::AssetParams) -> Result<ApiAssetParams, TransactionError> {
+    Ok(ApiAssetParams {
+        meta_data_hash: opt(&params.meta_data_hash, bytes)?,
+        asset_name: params.asset_name.clone(),
+        url: params.url.clone(),
+        clawback: opt(&params.clawback, address)?,
+        decimals: params.decimals,
+        default_frozen: params.default_frozen,
+        freeze: opt(&params.freeze, address)?,
+        manager: opt(&params.manager, address)?,
+        reserve: opt(&params.reserve, address)?,
+        total: params.total,
+        unit_name: params.unit_name.clone(),
+    })
+}
+
+fn state_schema(schema: &ext::StateSchema) -> ApiStateSchema {
+    ApiStateSchema {
+        number_byteslices: schema.byte_slices,
+        number_ints: schema.ints,
+    }
+}
+
+fn heartbeat(hb: &ext::HeartbeatFields) -> Result<ApiHeartbeat, TransactionError> {
+    let proof = hb.proof.as_ref().ok_or_else(|| missing("prf"))?;
+    Ok(ApiHeartbeat {
+        address: address(required(&hb.address, "a")?)?,
+        key_dilution: hb.key_dilution,
+        proof: HeartbeatProof {
+            pk: Ed25519PublicKey(array(required(&proof.pk, "p")?)?),
+            pk1_sig: signature_bytes(required(&proof.pk1_sig, "p1s")?)?,
+            pk2: Ed25519PublicKey(array(required(&proof.pk2, "p2")?)?),
+            pk2_sig: signature_bytes(required(&proof.pk2_sig, "p2s")?)?,
+            sig: signature_bytes(required(&proof.sig, "s")?)?,
+        },
+        seed: hash_digest(required(&hb.seed, "sd")?)?,
+        vote_id: VotePk(array(required(&hb.vote_id, "vid")?)?),
+    })
+}
+
+fn apply_data(
+    apply_data: Option<&ext::ApplyData>,
+    transaction_id: &HashDigest,
+) -> Result<ApplyData, TransactionError> {
+    let field = |f: fn(&ext::ApplyData) -> Option<u64>| apply_data.and_then(f);
+    Ok(ApplyData {
+        closing_amount: MicroAlgos(field(|ad| ad.closing_amount).unwrap_or_default()),
+        asset_closing_amount: field(|ad| ad.asset_closing_amount).unwrap_or_default(),
+        sender_rewards: MicroAlgos(field(|ad| ad.sender_rewards).unwrap_or_default()),
+        receiver_rewards: MicroAlgos(field(|ad| ad.receiver_rewards).unwrap_or_default()),
+        close_rewards: MicroAlgos(field(|ad| ad.close_rewards).unwrap_or_default()),
+        config_asset: field(|ad| ad.config_asset),
+        application_id: field(|ad| ad.application_id),
+        eval_delta: apply_data
+            .and_then(|ad| ad.delta.as_ref())
+            .map(|delta| eval_delta(delta, transaction_id))
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
+
+fn eval_delta(
+    delta: &ext::EvalDelta,
+    transaction_id: &HashDigest,
+) -> Result<EvalDelta, TransactionError> {
+    Ok(EvalDelta {
+        global_delta: delta
+            .global_delta
+            .as_ref()
+            .map(state_delta)
+            .transpose()?
+            .unwrap_or_default(),
+        local_deltas: delta
+            .local_deltas
+            .iter()
+            .flatten()
+            .map(|(index, delta)| {
+                let index = index.parse().map_err(|_| {
+                    TransactionError::Deserialization(format!("Invalid account index: {index}"))
+                })?;
+                Ok((index, state_delta(delta)?))
+            })
+            .collect::<Result<_, TransactionError>>()?,
+        shared_accounts: vec(&delta.shared_accounts, address)?.unwrap_or_default(),
+        logs: delta
+            .logs
+            .iter()
+            .flatten()
+            .map(|log| log.as_bytes().to_vec())
+            .collect(),
+        inner_txns: delta
+            .inner_txns
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, header)| inner_transaction(header, transaction_id, index as u64))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn state_delta(delta: &ext::StateDelta) -> Result<StateDelta, TransactionError> {
+    delta
+        .iter()
+        .map(|(key, value)| Ok((key.as_bytes().to_vec(), value_delta(value)?)))
+        .collect()
+}
+
+fn value_delta(value: &ext::ValueDelta) -> Result<ValueDelta, TransactionError> {
+    match value.action {
+        Some(DeltaAction::SetBytesAction) => Ok(ValueDelta::SetBytes(
+            value
+                .bytes
+                .as_deref()
+                .unwrap_or_default()
+                .as_bytes()
+                .to_vec(),
+        )),
+        Some(DeltaAction::SetUintAction) => Ok(ValueDelta::SetUint(value.uint.unwrap_or_default())),
+        Some(DeltaAction::DeleteAction) => Ok(ValueDelta::Delete),
+        None => Err(missing("at")),
+    }
+}
+
+/// Decodes a JSON value, e.g. a state proof, whose bytes are base64 strings.
+fn json<T: DeserializeOwned>(value: &serde_json::Value) -> Result<T, TransactionError> {
+    T::deserialize(value).map_err(|e| TransactionError::Deserialization(e.to_string()))
+}
+
+fn opt<T>(
+    value: &Option<String>,
+    f: fn(&str) -> Result<T, TransactionError>,
+) -> Result<Option<T>, TransactionError> {
+    value.as_deref().map(f).transpose()
+}
+
+fn vec<T>(
+    values: &Option<Vec<String>>,
+    f: fn(&str) -> Result<T, TransactionError>,
+) -> Result<Option<Vec<T>>, TransactionError> {
+    values
+        .as_ref()
+        .map(|values| values.iter().map(|value| f(value)).collect())
+        .transpose()
+}
+
+fn required<'a, T>(value: &'a Option<T>, field: &str) -> Result<&'a T, TransactionError> {
+    value.as_ref().ok_or_else(|| missing(field))
+}
+
+fn missing(field: &str) -> TransactionError {
+    TransactionError::Deserialization(format!("{field} missing"))
+}
+
+fn bytes(base64: &str) -> Result<Vec<u8>, TransactionError> {
+    decode_base64(base64.as_bytes())
+        .map_err(|e| TransactionError::Deserialization(format!("Invalid base64: {base64}: {e}")))
+}
+
+fn array<const N: usize>(base64: &str) -> Result<[u8; N], TransactionError> {
+    bytes(base64)?.try_into().map_err(|bytes: Vec<u8>| {
+        TransactionError::Deserialization(format!(
+            "Expected {N} bytes, got {}: {base64}",
+            bytes.len()
+        ))
+    })
+}
+
+fn hash_digest(base64: &str) -> Result<HashDigest, TransactionError> {
+    Ok(HashDigest(array(base64)?))
+}
+
+fn signature_bytes(base64: &str) -> Result<Signature, TransactionError> {
+    Ok(Signature(array(base64)?))
+}
+
+fn address(address: &str) -> Result<Address, TransactionError> {
+    address.parse().map_err(TransactionError::Deserialization)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::block::BlockResponse;
+    use algonaut_crypto::HashType;
+    use algonaut_transaction::{Pay, RegisterKey, TxnBuilder};
+
+    const SENDER: &str = "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA";
+    const RECEIVER: &str = "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA";
+    const GENESIS_HASH: &str = "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=";
+
+    fn block(txns: &str) -> Block {
+        let json = format!(
+            r#"{{"block": {{"gen": "mainnet-v1.0", "gh": "{GENESIS_HASH}", "rnd": 101, "txns": {txns}}}}}"#
+        );
+        serde_json::from_str::<BlockResponse>(&json).unwrap().block
+    }
+
+    #[test]
+    fn test_restores_genesis_and_computes_ids() {
+        let sender = SENDER.parse().unwrap();
+        let genesis_hash = hash_digest(GENESIS_HASH).unwrap();
+        let payment = TxnBuilder::new(
+            MicroAlgos(1000),
+            Round(100),
+            Round(1100),
+            genesis_hash,
+            Pay::new(sender, RECEIVER.parse().unwrap(), MicroAlgos(5)).build(),
+        )
+        .genesis_id("mainnet-v1.0".to_owned())
+        .note(b"hi".to_vec())
+        .build()
+        .unwrap();
+        let key_registration = TxnBuilder::new(
+            MicroAlgos(1000),
+            Round(100),
+            Round(1100),
+            genesis_hash,
+            RegisterKey::online(
+                sender,
+                VotePk([1; 32]),
+                VrfPk([2; 32]),
+                Round(100),
+                Round(200),
+                10,
+            )
+            .state_proof_pk(StateProofPk([3; 64]))
+            .build(),
+        )
+        .build()
+        .unwrap();
+
+        let block = block(&format!(
+            r#"[{{
+                "hgi": true,
+                "sig": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==",
+                "txn": {{
+                    "amt": 5,
+                    "fee": 1000,
+                    "fv": 100,
+                    "lv": 1100,
+                    "note": "aGk=",
+                    "rcv": "{RECEIVER}",
+                    "snd": "{SENDER}",
+                    "type": "pay"
+                }}
+            }}, {{
+                "sig": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==",
+                "txn": {{
+                    "fee": 1000,
+                    "fv": 100,
+                    "lv": 1100,
+                    "selkey": "AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=",
+                    "snd": "{SENDER}",
+                    "sprfkey": "AwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAw==",
+                    "type": "keyreg",
+                    "votefst": 100,
+                    "votekd": 10,
+                    "votekey": "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=",
+                    "votelst": 200
+                }}
+            }}]"#
+        ));
+
+        let txns = block.signed_transactions().unwrap();
+
+        assert_eq!(2, txns.len());
+        assert_eq!(payment, txns[0].signed_transaction.transaction);
+        assert_eq!(
+            payment.id().unwrap(),
+            txns[0].signed_transaction.transaction_id
+        );
+        assert_eq!(key_registration, txns[1].signed_transaction.transaction);
+        assert_eq!(
+            key_registration.id().unwrap(),
+            txns[1].signed_transaction.transaction_id
+        );
+    }
+
+    #[test]
+    fn test_converts_apply_data_and_inner_transactions() {
+        let inner_payment = format!(
+            r#"{{
+                "txn": {{
+                    "amt": 1,
+                    "fv": 100,
+                    "lv": 1100,
+                    "rcv": "{RECEIVER}",
+                    "snd": "{SENDER}",
+                    "type": "pay"
+                }}
+            }}"#
+        );
+        let block = block(&format!(
+            r#"[{{
+                "hgi": true,
+                "lsig": {{"l": "BoEB"}},
+                "sgnr": "{RECEIVER}",
+                "apid": 7,
+                "dt": {{
+                    "gd": {{"counter": {{"at": 2, "ui": 5}}}},
+                    "ld": {{"0": {{"k": {{"at": 1, "bs": "v"}}, "old": {{"at": 3}}}}}},
+                    "lg": ["hello"],
+                    "itx": [{inner_payment}, {inner_payment}]
+                }},
+                "txn": {{
+                    "apaa": ["aGk="],
+                    "fee": 3000,
+                    "fv": 100,
+                    "lv": 1100,
+                    "snd": "{SENDER}",
+                    "type": "appl"
+                }}
+            }}]"#
+        ));
+
+        let txn = block.signed_transactions().unwrap().remove(0);
+
+        let signed_transaction = txn.signed_transaction;
+        assert_eq!(
+            Some(RECEIVER.parse().unwrap()),
+            signed_transaction.auth_address
+        );
+        assert!(matches!(
+            signed_transaction.sig,
+            TransactionSignature::Logic(_)
+        ));
+        let apply_data = txn.apply_data;
+        assert_eq!(Some(7), apply_data.application_id);
+        let delta = apply_data.eval_delta;
+        assert_eq!(
+            Some(&ValueDelta::SetUint(5)),
+            delta.global_delta.get(b"counter".as_slice())
+        );
+        let local_delta = &delta.local_deltas[&0];
+        assert_eq!(
+            Some(&ValueDelta::SetBytes(b"v".to_vec())),
+            local_delta.get(b"k".as_slice())
+        );
+        assert_eq!(
+            Some(&ValueDelta::Delete),
+            local_delta.get(b"old".as_slice())
+        );
+        assert_eq!(vec![b"hello".to_vec()], delta.logs);
+
+        // identical inner transactions have different ids
+        let parent_id = signed_transaction.transaction.raw_id().unwrap();
+        let inner_txns = delta.inner_txns;
+        assert_eq!(2, inner_txns.len());
+        for (index, inner) in inner_txns.iter().enumerate() {
+            assert_eq!(None, inner.transaction.genesis_id);
+            assert_eq!(HashDigest::default(), inner.transaction.genesis_hash);
+            assert_eq!(
+                inner
+                    .transaction
+                    .inner_id(&parent_id, index as u64)
+                    .unwrap(),
+                inner.transaction_id
+            );
+        }
+        assert_ne!(inner_txns[0].transaction_id, inner_txns[1].transaction_id);
+    }
+
+    #[test]
+    fn test_converts_state_proof_transactions() {
+        // sptype is omitted, like go-algorand omits zero values; bytes are base64
+        let block = block(&format!(
+            r#"[{{
+                "hgi": true,
+                "txn": {{
+                    "fv": 100,
+                    "lv": 1100,
+                    "snd": "{SENDER}",
+                    "sp": {{
+                        "P": {{"hsh": {{"t": 1}}, "pth": ["AQI="], "td": 2}},
+                        "S": {{"hsh": {{"t": 1}}, "td": 1}},
+                        "c": "AwQ=",
+                        "pr": [3],
+                        "r": {{
+                            "3": {{
+                                "p": {{"p": {{"cmt": "BQY=", "lf": 256}}, "w": 1000}},
+                                "s": {{
+                                    "l": 7,
+                                    "s": {{
+                                        "idx": 9,
+                                        "prf": {{"hsh": {{"t": 1}}, "pth": ["Bwg="], "td": 16}},
+                                        "sig": "CQo=",
+                                        "vkey": {{"k": "Cww="}}
+                                    }}
+                                }}
+                            }}
+                        }},
+                        "w": 2000
+                    }},
+                    "spmsg": {{"P": 3, "b": "DQ4=", "f": 257, "l": 512, "v": "Dw=="}},
+                    "type": "stpf"
+                }}
+            }}]"#
+        ));
+
+        let txns = block.signed_transactions().unwrap();
+
+        let signed_transaction = &txns[0].signed_transaction;
+        assert_eq!(TransactionSignature::Unsigned, signed_transaction.sig);
+        let state_proof = match &signed_transaction.transaction.txn_type {
+            algonaut_transaction::TransactionType::StateProofTransaction(state_proof) => {
+                state_proof
+            }
+            txn_type => panic!("not a state proof transaction: {:?}", txn_type),
+        };
+        assert_eq!(
+            StateProofType::StateProofBasic,
+            state_proof.state_proof_type
+        );
+        assert_eq!(vec![3, 4], state_proof.state_proof.sig_commit.0);
+        assert_eq!(
+            HashType::Sumhash,
+            state_proof.state_proof.part_proofs.hash_factory.hash_type
+        );
+        let reveal = &state_proof.state_proof.reveals[&3];
+        assert_eq!(vec![5, 6], reveal.part.pk.commitment);
+        assert_eq!(1000, reveal.part.weight);
+        assert_eq!(vec![9, 10], reveal.sig_slot.sig.signature);
+        assert_eq!(vec![11, 12], reveal.sig_slot.sig.verifying_key.public_key);
+        assert_eq!(16, reveal.sig_slot.sig.proof.tree_depth);
+        assert_eq!(257, state_proof.message.first_attested_round);
+        assert_eq!(vec![15], state_proof.message.voters_commitment);
+        assert_eq!(
+            signed_transaction.transaction.id().unwrap(),
+            signed_transaction.transaction_id
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Deserialization helpers for models served both as JSON and msgpack.
+//!
+//! In msgpack algod encodes binary values as raw bytes. In JSON, addresses are base32 strings
+//! with checksum, hashes and keys base64 strings, and go strings (state keys and values) are
+//! written as they are, which is lossy for non UTF-8 data.
+
+use algonaut_core::Address;
+use algonaut_encoding::decode_base64;
+use data_encoding::BASE32_NOPAD;
+use serde::de::value::BytesDeserializer;
+use serde::de::{DeserializeOwned, Error, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_bytes::ByteBuf;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+pub(crate) fn address<'de, D>(deserializer: D) -> Result<Address, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    } else {
+        Address::deserialize(deserializer)
+    }
+}
+
+pub(crate) fn opt_address<'de, D>(deserializer: D) -> Result<Option<Address>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        Option::<String>::deserialize(deserializer)?
+            .map(|address| address.parse().map_err(D::Error::custom))
+            .transpose()
+    } else {
+        Option::<Address>::deserialize(deserializer)
+    }
+}
+
+pub(crate) fn addresses<'de, D>(deserializer: D) -> Result<Vec<Address>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|address| address.parse().map_err(D::Error::custom))
+            .collect()
+    } else {
+        Vec::<Address>::deserialize(deserializer)
+    }
+}
+
+/// Fixed size binary values (hashes, keys), base64 encoded in JSON.
+pub(crate) fn base64<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    if deserializer.is_human_readable() {
+        let bytes = decode_base64(String::deserialize(deserializer)?.as_bytes())
+            .map_err(D::Error::custom)?;
+        T::deserialize(BytesDeserializer::<D::Error>::new(&bytes))
+    } else {
+        T::deserialize(deserializer)
+    }
+}
+
+pub(crate) fn opt_base64<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    if deserializer.is_human_readable() {
+        Option::<String>::deserialize(deserializer)?
+            .map(|str| {
+                let bytes = decode_base64(str.as_bytes()).map_err(D::Error::custom)?;
+                T::deserialize(BytesDeserializer::<D::Error>::new(&bytes))
+            })
+            .transpose()
+    } else {
+        Option::<T>::deserialize(deserializer)
+    }
+}
+
+/// A block hash, encoded as "blk-" followed by its base32 representation in JSON.
+pub(crate) fn block_hash<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    if deserializer.is_human_readable() {
+        let str = String::deserialize(deserializer)?;
+        let base32 = str
+            .strip_prefix("blk-")
+            .ok_or_else(|| D::Error::custom(format!("invalid block hash: {str}")))?;
+        let bytes = BASE32_NOPAD
+            .decode(base32.as_bytes())
+            .map_err(D::Error::custom)?;
+        T::deserialize(BytesDeserializer::<D::Error>::new(&bytes))
+    } else {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Variable length binary values, base64 encoded in JSON.
+pub(crate) fn bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(opt_bytes(deserializer)?.unwrap_or_default())
+}
+
+pub(crate) fn opt_bytes<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        Option::<String>::deserialize(deserializer)?
+            .map(|str| decode_base64(str.as_bytes()).map_err(D::Error::custom))
+            .transpose()
+    } else {
+        Ok(Option::<ByteBuf>::deserialize(deserializer)?.map(ByteBuf::into_vec))
+    }
+}
+
+/// Go strings, which are raw bytes in both formats.
+pub(crate) fn raw_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(ByteBuf::deserialize(deserializer)?.into_vec())
+}
+
+/// Go strings meant to be text, e.g. asset names, which aren't guaranteed to be valid UTF-8.
+pub(crate) fn lossy_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(String::from_utf8_lossy(&raw_bytes(deserializer)?).into_owned())
+}
+
+/// Maps keyed by go strings, e.g. state keys.
+pub(crate) fn raw_bytes_map<'de, D, V>(deserializer: D) -> Result<HashMap<Vec<u8>, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    Ok(HashMap::<ByteBuf, V>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| (key.into_vec(), value))
+        .collect())
+}
+
+/// Maps keyed by a hash, e.g. transaction ids, returned keyed by the hash's base32 representation.
+/// JSON keys are accepted in base32 and base64.
+pub(crate) fn base32_keyed_map<'de, D, V>(deserializer: D) -> Result<HashMap<String, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let human_readable = deserializer.is_human_readable();
+    HashMap::<ByteBuf, V>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, value)| {
+            let bytes = if human_readable {
+                decode_hash_str(&key).map_err(D::Error::custom)?
+            } else {
+                key.into_vec()
+            };
+            Ok((BASE32_NOPAD.encode(&bytes), value))
+        })
+        .collect()
+}
+
+fn decode_hash_str(str: &[u8]) -> Result<Vec<u8>, String> {
+    match BASE32_NOPAD.decode(str) {
+        Ok(bytes) => Ok(bytes),
+        Err(_) => decode_base64(str),
+    }
+}
+
+/// Maps whose keys aren't strings, as key value pairs. JSON has no such keys, so algod writes
+/// them JSON encoded.
+pub(crate) fn struct_keyed_map<'de, D, K, V>(deserializer: D) -> Result<Vec<(K, V)>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: DeserializeOwned,
+    V: Deserialize<'de>,
+{
+    if deserializer.is_human_readable() {
+        deserializer
+            .deserialize_map(PairsVisitor::<String, V>(PhantomData))?
+            .into_iter()
+            .map(|(key, value)| Ok((serde_json::from_str(&key).map_err(D::Error::custom)?, value)))
+            .collect()
+    } else {
+        deserializer.deserialize_map(PairsVisitor(PhantomData))
+    }
+}
+
+struct PairsVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> Visitor<'de> for PairsVisitor<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = Vec<(K, V)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut pairs = vec![];
+        while let Some(pair) = map.next_entry()? {
+            pairs.push(pair);
+        }
+        Ok(pairs)
+    }
+}
@@ -0,0 +1,741 @@
+use super::block_header::BlockHeader;
+use super::encoding;
+use algonaut_core::{Address, MicroAlgos, StateProofPk, VotePk, VrfPk};
+use algonaut_crypto::HashDigest;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+/// Prefix of the box keys in [LedgerStateDelta::kv_mods].
+const BOX_KEY_PREFIX: &[u8] = b"bx:";
+
+/// The changes made to the ledger by a round, decoded from JSON or msgpack.
+///
+/// Deltas only contain the new values of what changed, not the previous ones
+/// (with the exception of [KvValueDelta::old_data]).
+/// Fields omitted by algod (zero values) are set to their defaults.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct LedgerStateDelta {
+    #[serde(rename = "Accts", default)]
+    pub accounts: AccountDeltas,
+    /// Modified key value pairs, e.g. boxes.
+    #[serde(
+        rename = "KvMods",
+        default,
+        deserialize_with = "encoding::raw_bytes_map"
+    )]
+    pub kv_mods: HashMap<Vec<u8>, KvValueDelta>,
+    /// Transactions of the round, keyed by transaction id.
+    #[serde(
+        rename = "Txids",
+        default,
+        deserialize_with = "encoding::base32_keyed_map"
+    )]
+    pub tx_ids: HashMap<String, IncludedTransaction>,
+    /// Leases set by the round's transactions, with the round they expire at.
+    #[serde(
+        rename = "Txleases",
+        default,
+        deserialize_with = "encoding::struct_keyed_map"
+    )]
+    pub tx_leases: Vec<(TxLease, u64)>,
+    /// Apps and assets created or deleted, keyed by id.
+    #[serde(rename = "Creatables", default)]
+    pub creatables: HashMap<u64, ModifiedCreatable>,
+    #[serde(rename = "Hdr", default)]
+    pub header: Option<BlockHeader>,
+    /// Next round for which a state proof is expected.
+    #[serde(rename = "StateProofNext", default)]
+    pub state_proof_next: u64,
+    #[serde(rename = "PrevTimestamp", default)]
+    pub prev_timestamp: i64,
+    /// Totals of the ledger after this round.
+    #[serde(rename = "Totals", default)]
+    pub totals: AccountTotals,
+}
+
+impl LedgerStateDelta {
+    /// The accounts modified by this round, with their new balances.
+    ///
+    /// Deltas don't contain previous balances, so this includes accounts modified
+    /// in other ways (e.g. by key registrations) whose balance stayed the same.
+    /// Use [changed_balances](Self::changed_balances) to get only the changed balances.
+    pub fn modified_accounts(&self) -> impl Iterator<Item = (&Address, MicroAlgos)> {
+        self.accounts
+            .accounts
+            .iter()
+            .map(|record| (&record.address, record.micro_algos))
+    }
+
+    /// The new balances of the accounts whose balance changed in this round.
+    ///
+    /// `previous` are the balances before this round. Accounts missing from it are
+    /// considered changed.
+    pub fn changed_balances<'a>(
+        &'a self,
+        previous: &'a HashMap<Address, MicroAlgos>,
+    ) -> impl Iterator<Item = (&'a Address, MicroAlgos)> + 'a {
+        self.modified_accounts()
+            .filter(move |(address, balance)| previous.get(address) != Some(balance))
+    }
+
+    /// Boxes created or written in this round.
+    pub fn written_boxes(&self) -> impl Iterator<Item = BoxWrite<'_>> {
+        self.kv_mods.iter().filter_map(|(key, delta)| {
+            let (app_id, name) = parse_box_key(key)?;
+            Some(BoxWrite {
+                app_id,
+                name,
+                value: delta.data.as_deref()?,
+            })
+        })
+    }
+
+    /// Boxes deleted in this round, as (app id, box name).
+    pub fn deleted_boxes(&self) -> impl Iterator<Item = (u64, &[u8])> {
+        self.kv_mods
+            .iter()
+            .filter(|(_, delta)| delta.data.is_none())
+            .filter_map(|(key, _)| parse_box_key(key))
+    }
+
+    /// Apps and assets created in this round.
+    pub fn created(&self) -> impl Iterator<Item = (u64, &ModifiedCreatable)> {
+        self.creatables
+            .iter()
+            .filter(|(_, creatable)| creatable.created)
+            .map(|(id, creatable)| (*id, creatable))
+    }
+
+    /// Apps and assets deleted in this round.
+    pub fn deleted(&self) -> impl Iterator<Item = (u64, &ModifiedCreatable)> {
+        self.creatables
+            .iter()
+            .filter(|(_, creatable)| !creatable.created)
+            .map(|(id, creatable)| (*id, creatable))
+    }
+}
+
+/// Box keys are the prefix, followed by the app id (big endian) and the box name.
+fn parse_box_key(key: &[u8]) -> Option<(u64, &[u8])> {
+    let key = key.strip_prefix(BOX_KEY_PREFIX)?;
+    if key.len() < 8 {
+        return None;
+    }
+    let (app_id, name) = key.split_at(8);
+    Some((u64::from_be_bytes(app_id.try_into().ok()?), name))
+}
+
+/// A box written in a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxWrite<'a> {
+    pub app_id: u64,
+    pub name: &'a [u8],
+    pub value: &'a [u8],
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct AccountDeltas {
+    #[serde(rename = "Accts", default)]
+    pub accounts: Vec<BalanceRecord>,
+    #[serde(rename = "AppResources", default)]
+    pub app_resources: Vec<AppResourceRecord>,
+    #[serde(rename = "AssetResources", default)]
+    pub asset_resources: Vec<AssetResourceRecord>,
+}
+
+/// The new state of a modified account, without its apps and assets.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BalanceRecord {
+    #[serde(rename = "Addr", deserialize_with = "encoding::address")]
+    pub address: Address,
+    #[serde(rename = "Status", default)]
+    pub status: AccountStatus,
+    #[serde(rename = "MicroAlgos", default)]
+    pub micro_algos: MicroAlgos,
+    #[serde(rename = "RewardsBase", default)]
+    pub rewards_base: u64,
+    #[serde(rename = "RewardedMicroAlgos", default)]
+    pub rewarded_micro_algos: MicroAlgos,
+    #[serde(
+        rename = "AuthAddr",
+        default,
+        deserialize_with = "encoding::opt_address"
+    )]
+    pub auth_address: Option<Address>,
+    #[serde(rename = "IncentiveEligible", default)]
+    pub incentive_eligible: bool,
+    #[serde(rename = "TotalAppSchema", default)]
+    pub total_app_schema: StateSchema,
+    #[serde(rename = "TotalExtraAppPages", default)]
+    pub total_extra_app_pages: u32,
+    #[serde(rename = "TotalAppParams", default)]
+    pub total_app_params: u64,
+    #[serde(rename = "TotalAppLocalStates", default)]
+    pub total_app_local_states: u64,
+    #[serde(rename = "TotalAssetParams", default)]
+    pub total_asset_params: u64,
+    #[serde(rename = "TotalAssets", default)]
+    pub total_assets: u64,
+    #[serde(rename = "TotalBoxes", default)]
+    pub total_boxes: u64,
+    #[serde(rename = "TotalBoxBytes", default)]
+    pub total_box_bytes: u64,
+    #[serde(rename = "LastProposed", default)]
+    pub last_proposed: u64,
+    #[serde(rename = "LastHeartbeat", default)]
+    pub last_heartbeat: u64,
+    #[serde(rename = "VoteID", default, deserialize_with = "encoding::opt_base64")]
+    pub vote_pk: Option<VotePk>,
+    #[serde(
+        rename = "SelectionID",
+        default,
+        deserialize_with = "encoding::opt_base64"
+    )]
+    pub selection_pk: Option<VrfPk>,
+    #[serde(
+        rename = "StateProofID",
+        default,
+        deserialize_with = "encoding::opt_base64"
+    )]
+    pub state_proof_pk: Option<StateProofPk>,
+    #[serde(rename = "VoteFirstValid", default)]
+    pub vote_first: u64,
+    #[serde(rename = "VoteLastValid", default)]
+    pub vote_last: u64,
+    #[serde(rename = "VoteKeyDilution", default)]
+    pub vote_key_dilution: u64,
+}
+
+/// Participation status of an account.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AccountStatus {
+    #[default]
+    Offline,
+    Online,
+    NotParticipating,
+}
+
+impl<'de> Deserialize<'de> for AccountStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            0 => Ok(AccountStatus::Offline),
+            1 => Ok(AccountStatus::Online),
+            2 => Ok(AccountStatus::NotParticipating),
+            status => Err(D::Error::custom(format!(
+                "unsupported account status: {status}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct StateSchema {
+    #[serde(rename = "nui", default)]
+    pub number_ints: u64,
+    #[serde(rename = "nbs", default)]
+    pub number_byteslices: u64,
+}
+
+/// A modified app of an account: its params if the account is the creator, or its local state.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AppResourceRecord {
+    #[serde(rename = "Aidx")]
+    pub app_id: u64,
+    #[serde(rename = "Addr", deserialize_with = "encoding::address")]
+    pub address: Address,
+    #[serde(rename = "Params", default)]
+    pub params: AppParamsDelta,
+    #[serde(rename = "State", default)]
+    pub state: AppLocalStateDelta,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AppParamsDelta {
+    /// Not set if the params didn't change.
+    #[serde(rename = "Params", default)]
+    pub params: Option<AppParams>,
+    #[serde(rename = "Deleted", default)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AppLocalStateDelta {
+    /// Not set if the local state didn't change.
+    #[serde(rename = "LocalState", default)]
+    pub local_state: Option<AppLocalState>,
+    #[serde(rename = "Deleted", default)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AppParams {
+    #[serde(rename = "approv", default, deserialize_with = "encoding::bytes")]
+    pub approval_program: Vec<u8>,
+    #[serde(rename = "clearp", default, deserialize_with = "encoding::bytes")]
+    pub clear_state_program: Vec<u8>,
+    #[serde(rename = "gs", default, deserialize_with = "encoding::raw_bytes_map")]
+    pub global_state: HashMap<Vec<u8>, TealValue>,
+    #[serde(rename = "gsch", default)]
+    pub global_state_schema: StateSchema,
+    #[serde(rename = "lsch", default)]
+    pub local_state_schema: StateSchema,
+    #[serde(rename = "epp", default)]
+    pub extra_program_pages: u32,
+    #[serde(rename = "v", default)]
+    pub version: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AppLocalState {
+    #[serde(rename = "hsch", default)]
+    pub schema: StateSchema,
+    #[serde(rename = "tkv", default, deserialize_with = "encoding::raw_bytes_map")]
+    pub key_value: HashMap<Vec<u8>, TealValue>,
+}
+
+/// A state value: bytes if `value_type` is 1, uint if it's 2.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TealValue {
+    #[serde(rename = "tt", default)]
+    pub value_type: u64,
+    #[serde(rename = "tb", default, deserialize_with = "encoding::raw_bytes")]
+    pub bytes: Vec<u8>,
+    #[serde(rename = "ui", default)]
+    pub uint: u64,
+}
+
+/// A modified asset of an account: its params if the account is the creator, or its holding.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AssetResourceRecord {
+    #[serde(rename = "Aidx")]
+    pub asset_id: u64,
+    #[serde(rename = "Addr", deserialize_with = "encoding::address")]
+    pub address: Address,
+    #[serde(rename = "Params", default)]
+    pub params: AssetParamsDelta,
+    #[serde(rename = "Holding", default)]
+    pub holding: AssetHoldingDelta,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AssetParamsDelta {
+    /// Not set if the params didn't change.
+    #[serde(rename = "Params", default)]
+    pub params: Option<AssetParams>,
+    #[serde(rename = "Deleted", default)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AssetHoldingDelta {
+    /// Not set if the holding didn't change.
+    #[serde(rename = "Holding", default)]
+    pub holding: Option<AssetHolding>,
+    #[serde(rename = "Deleted", default)]
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AssetParams {
+    #[serde(rename = "t", default)]
+    pub total: u64,
+    #[serde(rename = "dc", default)]
+    pub decimals: u32,
+    #[serde(rename = "df", default)]
+    pub default_frozen: bool,
+    #[serde(rename = "un", default, deserialize_with = "encoding::lossy_string")]
+    pub unit_name: String,
+    #[serde(rename = "an", default, deserialize_with = "encoding::lossy_string")]
+    pub asset_name: String,
+    #[serde(rename = "au", default, deserialize_with = "encoding::lossy_string")]
+    pub url: String,
+    #[serde(rename = "am", default, deserialize_with = "encoding::opt_base64")]
+    pub meta_data_hash: Option<HashDigest>,
+    #[serde(rename = "m", default, deserialize_with = "encoding::opt_address")]
+    pub manager: Option<Address>,
+    #[serde(rename = "r", default, deserialize_with = "encoding::opt_address")]
+    pub reserve: Option<Address>,
+    #[serde(rename = "f", default, deserialize_with = "encoding::opt_address")]
+    pub freeze: Option<Address>,
+    #[serde(rename = "c", default, deserialize_with = "encoding::opt_address")]
+    pub clawback: Option<Address>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct AssetHolding {
+    #[serde(rename = "a", default)]
+    pub amount: u64,
+    #[serde(rename = "f", default)]
+    pub frozen: bool,
+}
+
+/// A modified key value pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct KvValueDelta {
+    /// Not set if the pair was deleted.
+    #[serde(rename = "Data", default, deserialize_with = "encoding::opt_bytes")]
+    pub data: Option<Vec<u8>>,
+    #[serde(rename = "OldData", default, deserialize_with = "encoding::opt_bytes")]
+    pub old_data: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct IncludedTransaction {
+    #[serde(rename = "LastValid", default)]
+    pub last_valid: u64,
+    /// Position of the transaction in the block.
+    #[serde(rename = "Intra", default)]
+    pub intra: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct TxLease {
+    #[serde(rename = "sender", deserialize_with = "encoding::address")]
+    pub sender: Address,
+    #[serde(rename = "lease", deserialize_with = "encoding::base64")]
+    pub lease: HashDigest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ModifiedCreatable {
+    #[serde(rename = "Ctype", default)]
+    pub creatable_type: CreatableType,
+    /// Whether the creatable was created, or deleted.
+    #[serde(rename = "Created", default)]
+    pub created: bool,
+    #[serde(rename = "Creator", deserialize_with = "encoding::address")]
+    pub creator: Address,
+    /// Number of times the creatable was modified in the round.
+    #[serde(rename = "Ndeltas", default)]
+    pub number_of_deltas: i64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CreatableType {
+    #[default]
+    Asset,
+    App,
+}
+
+impl<'de> Deserialize<'de> for CreatableType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u64::deserialize(deserializer)? {
+            0 => Ok(CreatableType::Asset),
+            1 => Ok(CreatableType::App),
+            creatable_type => Err(D::Error::custom(format!(
+                "unsupported creatable type: {creatable_type}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct AccountTotals {
+    #[serde(rename = "online", default)]
+    pub online: AlgoCount,
+    #[serde(rename = "offline", default)]
+    pub offline: AlgoCount,
+    #[serde(rename = "notpart", default)]
+    pub not_participating: AlgoCount,
+    #[serde(rename = "rwdlvl", default)]
+    pub rewards_level: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct AlgoCount {
+    #[serde(rename = "mon", default)]
+    pub money: MicroAlgos,
+    #[serde(rename = "rwd", default)]
+    pub reward_units: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_encoding::BASE32_NOPAD;
+    use serde::Serialize;
+    use serde_bytes::ByteBuf;
+
+    const SENDER: &str = "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA";
+    const RECEIVER: &str = "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA";
+    const TX_ID: &str = "FVYRMQVXE2YEIALCPSU7XLBS6XEFGD5RSA6MJWYCEWDRPEQ2JCAQ";
+
+    fn box_key(app_id: u64, name: &[u8]) -> Vec<u8> {
+        [BOX_KEY_PREFIX, &app_id.to_be_bytes(), name].concat()
+    }
+
+    #[test]
+    fn test_decodes_json() {
+        let json = r#"{
+            "Accts": {
+                "Accts": [{
+                    "Addr": "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA",
+                    "Status": 1,
+                    "MicroAlgos": 5000000,
+                    "AuthAddr": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                    "TotalAppSchema": { "nui": 1 },
+                    "TotalBoxes": 1,
+                    "VoteID": "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=",
+                    "StateProofID": "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQ==",
+                    "VoteLastValid": 3000000
+                }],
+                "AppResources": [{
+                    "Aidx": 1,
+                    "Addr": "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA",
+                    "Params": {
+                        "Params": {
+                            "approv": "CIEBQw==",
+                            "clearp": "CIEBQw==",
+                            "gs": { "counter": { "tt": 2, "ui": 7 }, "owner": { "tt": 1, "tb": "alice" } },
+                            "gsch": { "nui": 1, "nbs": 1 }
+                        }
+                    }
+                }],
+                "AssetResources": [{
+                    "Aidx": 2,
+                    "Addr": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                    "Holding": { "Holding": { "a": 10 } }
+                }, {
+                    "Aidx": 3,
+                    "Addr": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA",
+                    "Params": { "Deleted": true }
+                }]
+            },
+            "KvMods": {
+                "bx:\u0000\u0000\u0000\u0000\u0000\u0000\u0000\u0001name": { "Data": "dmFsdWU=" },
+                "bx:\u0000\u0000\u0000\u0000\u0000\u0000\u0000\u0001old": { "OldData": "dmFsdWU=" }
+            },
+            "Txids": {
+                "FVYRMQVXE2YEIALCPSU7XLBS6XEFGD5RSA6MJWYCEWDRPEQ2JCAQ": { "LastValid": 1001, "Intra": 1 }
+            },
+            "Creatables": {
+                "3": { "Ctype": 0, "Creator": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA", "Ndeltas": 1 },
+                "4": { "Ctype": 1, "Created": true, "Creator": "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA", "Ndeltas": 1 }
+            },
+            "Hdr": {
+                "gen": "mainnet-v1.0",
+                "gh": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                "prev": "blk-FVYRMQVXE2YEIALCPSU7XLBS6XEFGD5RSA6MJWYCEWDRPEQ2JCAQ",
+                "rnd": 1000,
+                "ts": 1736510450
+            },
+            "PrevTimestamp": 1736510447,
+            "Totals": {
+                "online": { "mon": 10000000, "rwd": 10 },
+                "offline": { "mon": 5000000 },
+                "notpart": {},
+                "rwdlvl": 218288
+            }
+        }"#;
+
+        let delta: LedgerStateDelta = serde_json::from_str(json).unwrap();
+
+        let sender = SENDER.parse::<Address>().unwrap();
+        let receiver = RECEIVER.parse::<Address>().unwrap();
+
+        let account = &delta.accounts.accounts[0];
+        assert_eq!(AccountStatus::Online, account.status);
+        assert_eq!(Some(receiver), account.auth_address);
+        assert_eq!(1, account.total_app_schema.number_ints);
+        assert_eq!(
+            Some(VotePk(core::array::from_fn(|i| i as u8))),
+            account.vote_pk
+        );
+        assert_eq!(None, account.selection_pk);
+        assert_eq!(Some(StateProofPk([1; 64])), account.state_proof_pk);
+        assert_eq!(
+            vec![(&sender, MicroAlgos(5000000))],
+            delta.modified_accounts().collect::<Vec<_>>()
+        );
+        let unchanged = HashMap::from([(sender, MicroAlgos(5000000))]);
+        assert_eq!(0, delta.changed_balances(&unchanged).count());
+        let changed = HashMap::from([(sender, MicroAlgos(4000000))]);
+        assert_eq!(
+            vec![(&sender, MicroAlgos(5000000))],
+            delta.changed_balances(&changed).collect::<Vec<_>>()
+        );
+        assert_eq!(1, delta.changed_balances(&HashMap::new()).count());
+
+        let app = delta.accounts.app_resources[0]
+            .params
+            .params
+            .as_ref()
+            .unwrap();
+        assert_eq!(vec![8, 129, 1, 67], app.approval_program);
+        assert_eq!(7, app.global_state[b"counter".as_slice()].uint);
+        assert_eq!(
+            b"alice".to_vec(),
+            app.global_state[b"owner".as_slice()].bytes
+        );
+        assert!(delta.accounts.app_resources[0].state.local_state.is_none());
+
+        let assets = &delta.accounts.asset_resources;
+        assert_eq!(Some(10), assets[0].holding.holding.map(|h| h.amount));
+        assert!(assets[1].params.deleted);
+
+        assert_eq!(
+            vec![BoxWrite {
+                app_id: 1,
+                name: b"name",
+                value: b"value"
+            }],
+            delta.written_boxes().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(1, b"old".as_slice())],
+            delta.deleted_boxes().collect::<Vec<_>>()
+        );
+
+        assert_eq!(1, delta.tx_ids[TX_ID].intra);
+        assert_eq!(
+            vec![(4, CreatableType::App)],
+            delta
+                .created()
+                .map(|(id, c)| (id, c.creatable_type))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![3],
+            delta.deleted().map(|(id, _)| id).collect::<Vec<_>>()
+        );
+
+        let header = delta.header.unwrap();
+        assert_eq!(1000, header.round);
+        assert_eq!(
+            "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+            header.genesis_hash.to_string()
+        );
+        assert_eq!(TX_ID, BASE32_NOPAD.encode(&header.branch.0));
+        assert_eq!(MicroAlgos(10000000), delta.totals.online.money);
+        assert_eq!(218288, delta.totals.rewards_level);
+    }
+
+    #[derive(Serialize)]
+    struct MsgPackDelta {
+        #[serde(rename = "Accts")]
+        accounts: MsgPackAccountDeltas,
+        #[serde(rename = "KvMods")]
+        kv_mods: HashMap<ByteBuf, MsgPackKvValueDelta>,
+        #[serde(rename = "Txids")]
+        tx_ids: HashMap<ByteBuf, IncludedTransactionFields>,
+        #[serde(rename = "Txleases")]
+        tx_leases: HashMap<MsgPackTxLease, u64>,
+    }
+
+    #[derive(Serialize)]
+    struct MsgPackAccountDeltas {
+        #[serde(rename = "Accts")]
+        accounts: Vec<MsgPackBalanceRecord>,
+    }
+
+    #[derive(Serialize)]
+    struct MsgPackBalanceRecord {
+        #[serde(rename = "Addr")]
+        address: Address,
+        #[serde(rename = "MicroAlgos")]
+        micro_algos: u64,
+        #[serde(rename = "AuthAddr")]
+        auth_address: Address,
+    }
+
+    #[derive(Serialize)]
+    struct MsgPackKvValueDelta {
+        #[serde(rename = "Data")]
+        data: ByteBuf,
+    }
+
+    #[derive(Serialize)]
+    struct IncludedTransactionFields {
+        #[serde(rename = "LastValid")]
+        last_valid: u64,
+        #[serde(rename = "Intra")]
+        intra: u64,
+    }
+
+    #[derive(Serialize, PartialEq, Eq, Hash)]
+    struct MsgPackTxLease {
+        sender: Address,
+        lease: ByteBuf,
+    }
+
+    #[test]
+    fn test_decodes_msg_pack() {
+        let sender = SENDER.parse::<Address>().unwrap();
+        let receiver = RECEIVER.parse::<Address>().unwrap();
+        let tx_id = HashDigest(core::array::from_fn(|i| i as u8));
+        // Box names aren't necessarily valid UTF-8.
+        let key = box_key(u64::MAX, &[0xff, 0xfe]);
+
+        let bytes = rmp_serde::to_vec_named(&MsgPackDelta {
+            accounts: MsgPackAccountDeltas {
+                accounts: vec![MsgPackBalanceRecord {
+                    address: sender,
+                    micro_algos: 1000,
+                    auth_address: receiver,
+                }],
+            },
+            kv_mods: HashMap::from([(
+                ByteBuf::from(key),
+                MsgPackKvValueDelta {
+                    data: ByteBuf::from(vec![0, 1]),
+                },
+            )]),
+            tx_ids: HashMap::from([(
+                ByteBuf::from(tx_id.0.to_vec()),
+                IncludedTransactionFields {
+                    last_valid: 1001,
+                    intra: 2,
+                },
+            )]),
+            tx_leases: HashMap::from([(
+                MsgPackTxLease {
+                    sender,
+                    lease: ByteBuf::from(vec![1; 32]),
+                },
+                1001,
+            )]),
+        })
+        .unwrap();
+
+        let delta: LedgerStateDelta = rmp_serde::from_slice(&bytes).unwrap();
+
+        let account = &delta.accounts.accounts[0];
+        assert_eq!(sender, account.address);
+        assert_eq!(MicroAlgos(1000), account.micro_algos);
+        assert_eq!(Some(receiver), account.auth_address);
+        assert_eq!(
+            vec![BoxWrite {
+                app_id: u64::MAX,
+                name: &[0xff, 0xfe],
+                value: &[0, 1]
+            }],
+            delta.written_boxes().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(2),
+            delta
+                .tx_ids
+                .get("AAAQEAYEAUDAOCAJBIFQYDIOB4IBCEQTCQKRMFYYDENBWHA5DYPQ")
+                .map(|included| included.intra)
+        );
+        assert_eq!(
+            vec![(
+                TxLease {
+                    sender,
+                    lease: HashDigest([1; 32])
+                },
+                1001
+            )],
+            delta.tx_leases
+        );
+        assert!(delta.header.is_none());
+    }
+}
@@ -1,2 +1,7 @@
 pub mod block;
+pub mod block_header;
+pub mod block_transaction;
+mod encoding;
+pub mod ledger_state_delta;
+pub mod msgpack;
 pub mod transaction;
@@ -0,0 +1,1160 @@
+//! Responses decoded from msgpack, straight into [algonaut_transaction] types.
+//!
+//! Unlike JSON, msgpack encodes binary values losslessly, and algod returns the transactions'
+//! msgpack encoding as it was signed, so their ids can be computed exactly from these bytes.
+//!
+//! [to_json] converts msgpack responses into algod's JSON encoding instead,
+//! to decode them into the same models as JSON responses.
+
+use super::block_header::BlockHeader;
+use crate::models::{AccountStateDelta, EvalDeltaKeyValue};
+use algonaut_core::{Address, MicroAlgos};
+use algonaut_crypto::HashDigest;
+use algonaut_transaction::{
+    apply_data::{
+        ApplyData, EvalDelta, InnerTransaction, SignedTransactionWithApplyData, StateDelta,
+        ValueDelta,
+    },
+    error::TransactionError,
+    SignedTransaction, Transaction,
+};
+use data_encoding::{BASE32_NOPAD, BASE64};
+use rmp_serde::decode::Error;
+use serde::{de::Error as _, Deserialize};
+use serde_bytes::ByteBuf;
+use serde_json::Value;
+use sha2::{Digest, Sha512_256};
+use std::{collections::HashMap, convert::TryFrom, fmt::Display};
+
+/// Maximum nesting of msgpack arrays and maps, to bound the recursion on malformed input.
+/// Blocks with several levels of inner transactions stay well below it.
+const MAX_DEPTH: usize = 64;
+
+/// A block, decoded from msgpack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsgpackBlock {
+    pub header: BlockHeader,
+    /// The msgpack encoding of the block's transactions, with their apply data,
+    /// as they're stored in the block (without the genesis id and hash).
+    pub txns: Vec<Vec<u8>>,
+}
+
+impl MsgpackBlock {
+    /// Decodes algod's msgpack block response.
+    pub fn from_msgpack(bytes: &[u8]) -> Result<MsgpackBlock, Error> {
+        let block =
+            map_value(bytes, "block")?.ok_or_else(|| Error::custom("missing field `block`"))?;
+        let txns = match map_value(block, "txns")? {
+            Some(txns) => array_items(txns)?
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+            None => vec![],
+        };
+        Ok(MsgpackBlock {
+            header: rmp_serde::from_slice(block)?,
+            txns,
+        })
+    }
+
+    /// Decodes the block's transactions, with their apply data and inner transactions,
+    /// restoring their genesis id and hash from the header.
+    ///
+    /// The ids are computed from the transactions' bytes, with the genesis fields added back,
+    /// and the ids of inner transactions from the inner transactions' bytes.
+    /// Unlike in [Block::signed_transactions](super::block::Block::signed_transactions),
+    /// logs and state keys and values are decoded losslessly.
+    pub fn signed_transactions(
+        &self,
+    ) -> Result<Vec<SignedTransactionWithApplyData>, TransactionError> {
+        self.txns
+            .iter()
+            .map(|bytes| self.signed_transaction(bytes))
+            .collect()
+    }
+
+    fn signed_transaction(
+        &self,
+        bytes: &[u8],
+    ) -> Result<SignedTransactionWithApplyData, TransactionError> {
+        let fields: BlockTransactionFields = decode(bytes)?;
+        let mut signed_transaction: SignedTransaction = decode(bytes)?;
+        let genesis_id = Some(self.header.genesis_id.as_str()).filter(|_| fields.hgi);
+        let transaction = &mut signed_transaction.transaction;
+        if let Some(genesis_id) = genesis_id {
+            transaction.genesis_id = Some(genesis_id.to_owned());
+        }
+        // like in the JSON conversion, restored regardless of hgh
+        if transaction.genesis_hash == HashDigest::default() {
+            transaction.genesis_hash = self.header.genesis_hash;
+        }
+
+        let raw_transaction = with_genesis(
+            required(bytes, "txn")?,
+            genesis_id,
+            &self.header.genesis_hash,
+        )
+        .map_err(deserialization)?;
+        let raw_id = raw_transaction_id(&raw_transaction);
+        signed_transaction.transaction_id = BASE32_NOPAD.encode(&raw_id.0);
+        signed_transaction.auth_address = fields.sgnr;
+        Ok(SignedTransactionWithApplyData {
+            signed_transaction,
+            apply_data: apply_data(bytes, &raw_id)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct BlockTransactionFields {
+    /// Whether the genesis id was removed from the transaction.
+    #[serde(default)]
+    hgi: bool,
+    sgnr: Option<Address>,
+}
+
+/// The apply data of a transaction of a block, or of an inner transaction.
+/// The eval delta is decoded separately, see [eval_delta].
+#[derive(Deserialize)]
+struct ApplyDataFields {
+    #[serde(default)]
+    ca: u64,
+    #[serde(default)]
+    aca: u64,
+    #[serde(default)]
+    rs: u64,
+    #[serde(default)]
+    rr: u64,
+    #[serde(default)]
+    rc: u64,
+    caid: Option<u64>,
+    apid: Option<u64>,
+}
+
+/// go-algorand encodes state keys, byte values and logs as strings of arbitrary bytes,
+/// which rmp_serde decodes as bytes if they aren't valid UTF-8.
+#[derive(Deserialize)]
+struct EvalDeltaFields {
+    #[serde(default)]
+    gd: HashMap<ByteBuf, ValueDeltaFields>,
+    #[serde(default)]
+    ld: HashMap<u64, HashMap<ByteBuf, ValueDeltaFields>>,
+    #[serde(default)]
+    sa: Vec<Address>,
+    #[serde(default)]
+    lg: Vec<ByteBuf>,
+}
+
+#[derive(Deserialize)]
+struct ValueDeltaFields {
+    #[serde(default)]
+    at: u64,
+    #[serde(default)]
+    bs: ByteBuf,
+    #[serde(default)]
+    ui: u64,
+}
+
+/// Decodes the apply data of `entry`, a transaction of a block or an inner transaction,
+/// with `transaction_id` its raw id, from which the ids of its inner transactions are computed.
+fn apply_data(entry: &[u8], transaction_id: &HashDigest) -> Result<ApplyData, TransactionError> {
+    let fields: ApplyDataFields = decode(entry)?;
+    Ok(ApplyData {
+        closing_amount: MicroAlgos(fields.ca),
+        asset_closing_amount: fields.aca,
+        sender_rewards: MicroAlgos(fields.rs),
+        receiver_rewards: MicroAlgos(fields.rr),
+        close_rewards: MicroAlgos(fields.rc),
+        config_asset: fields.caid,
+        application_id: fields.apid,
+        eval_delta: match map_value(entry, "dt").map_err(deserialization)? {
+            Some(delta) => eval_delta(delta, transaction_id)?,
+            None => EvalDelta::default(),
+        },
+    })
+}
+
+fn eval_delta(delta: &[u8], transaction_id: &HashDigest) -> Result<EvalDelta, TransactionError> {
+    let fields: EvalDeltaFields = decode(delta)?;
+    let inner_txns = match map_value(delta, "itx").map_err(deserialization)? {
+        Some(itx) => array_items(itx)
+            .map_err(deserialization)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| inner_transaction(entry, transaction_id, index as u64))
+            .collect::<Result<_, _>>()?,
+        None => vec![],
+    };
+    Ok(EvalDelta {
+        global_delta: state_delta(fields.gd)?,
+        local_deltas: fields
+            .ld
+            .into_iter()
+            .map(|(index, delta)| Ok((index, state_delta(delta)?)))
+            .collect::<Result<_, TransactionError>>()?,
+        shared_accounts: fields.sa,
+        logs: fields.lg.into_iter().map(ByteBuf::into_vec).collect(),
+        inner_txns,
+    })
+}
+
+fn state_delta(delta: HashMap<ByteBuf, ValueDeltaFields>) -> Result<StateDelta, TransactionError> {
+    delta
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value.at {
+                1 => ValueDelta::SetBytes(value.bs.into_vec()),
+                2 => ValueDelta::SetUint(value.ui),
+                3 => ValueDelta::Delete,
+                0 => return Err(missing("at")),
+                action => {
+                    return Err(TransactionError::Deserialization(format!(
+                        "Invalid delta action: {action}"
+                    )))
+                }
+            };
+            Ok((key.into_vec(), value))
+        })
+        .collect()
+}
+
+fn inner_transaction(
+    entry: &[u8],
+    parent_id: &HashDigest,
+    index: u64,
+) -> Result<InnerTransaction, TransactionError> {
+    let raw_transaction = required(entry, "txn")?;
+    let raw_id = inner_raw_transaction_id(raw_transaction, parent_id, index);
+    Ok(InnerTransaction {
+        transaction: decode(raw_transaction)?,
+        transaction_id: BASE32_NOPAD.encode(&raw_id.0),
+        apply_data: apply_data(entry, &raw_id)?,
+    })
+}
+
+fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, TransactionError> {
+    rmp_serde::from_slice(bytes).map_err(deserialization)
+}
+
+/// The encoding of the value of `key` in the msgpack map `bytes`, which must have the key.
+fn required<'a>(bytes: &'a [u8], key: &str) -> Result<&'a [u8], TransactionError> {
+    map_value(bytes, key)
+        .map_err(deserialization)?
+        .ok_or_else(|| missing(key))
+}
+
+fn missing(field: &str) -> TransactionError {
+    TransactionError::Deserialization(format!("{field} missing"))
+}
+
+fn deserialization(e: impl Display) -> TransactionError {
+    TransactionError::Deserialization(e.to_string())
+}
+
+/// A pending transaction, decoded from msgpack.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTransaction {
+    /// The transaction, with its id computed from [PendingTransaction::raw_signed_transaction].
+    pub signed_transaction: SignedTransaction,
+    /// The msgpack encoding of the signed transaction, as returned by algod.
+    pub raw_signed_transaction: Vec<u8>,
+    /// The round where this transaction was confirmed, if present.
+    pub confirmed_round: Option<u64>,
+    /// Indicates that the transaction was kicked out of this node's transaction pool (and specifies why that happened).
+    /// An empty string indicates the transaction wasn't kicked out of this node's txpool due to an error.
+    pub pool_error: String,
+    /// The application index if the transaction was found and it created an application.
+    pub application_index: Option<u64>,
+    /// The asset index if the transaction was found and it created an asset.
+    pub asset_index: Option<u64>,
+    /// Closing amount for the transaction.
+    pub closing_amount: Option<u64>,
+    /// The number of the asset's unit that were transferred to the close-to address.
+    pub asset_closing_amount: Option<u64>,
+    /// Rewards in microalgos applied to the sender account.
+    pub sender_rewards: Option<u64>,
+    /// Rewards in microalgos applied to the receiver account.
+    pub receiver_rewards: Option<u64>,
+    /// Rewards in microalgos applied to the close remainder to account.
+    pub close_rewards: Option<u64>,
+    /// Logs for the application being executed by this transaction.
+    pub logs: Vec<Vec<u8>>,
+    /// Global state changes of the application being executed by this transaction.
+    pub global_state_delta: Option<Vec<EvalDeltaKeyValue>>,
+    /// Local state changes of the application being executed by this transaction.
+    pub local_state_delta: Option<Vec<AccountStateDelta>>,
+    /// Inner transactions issued by the application being executed by this transaction.
+    pub inner_txns: Vec<PendingInnerTransaction>,
+}
+
+/// An inner transaction of a [PendingTransaction]. Inner transactions aren't signed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingInnerTransaction {
+    /// The transaction, with its id computed from [PendingInnerTransaction::raw_transaction],
+    /// see [Transaction::inner_id].
+    pub transaction: Transaction,
+    pub transaction_id: String,
+    /// The msgpack encoding of the transaction, as returned by algod.
+    pub raw_transaction: Vec<u8>,
+    /// The application index if the transaction created an application.
+    pub application_index: Option<u64>,
+    /// The asset index if the transaction created an asset.
+    pub asset_index: Option<u64>,
+    /// Closing amount for the transaction.
+    pub closing_amount: Option<u64>,
+    /// The number of the asset's unit that were transferred to the close-to address.
+    pub asset_closing_amount: Option<u64>,
+    /// Logs for the application being executed by this transaction.
+    pub logs: Vec<Vec<u8>>,
+    /// Global state changes of the application being executed by this transaction.
+    pub global_state_delta: Option<Vec<EvalDeltaKeyValue>>,
+    /// Local state changes of the application being executed by this transaction.
+    pub local_state_delta: Option<Vec<AccountStateDelta>>,
+    /// Inner transactions issued by the application being executed by this transaction.
+    pub inner_txns: Vec<PendingInnerTransaction>,
+}
+
+/// The fields of a pending transaction response, except the transaction and its inner transactions,
+/// which are decoded from their exact bytes.
+#[derive(Deserialize)]
+struct PendingTransactionFields {
+    #[serde(rename = "confirmed-round")]
+    confirmed_round: Option<u64>,
+    #[serde(rename = "pool-error", default)]
+    pool_error: String,
+    #[serde(rename = "application-index")]
+    application_index: Option<u64>,
+    #[serde(rename = "asset-index")]
+    asset_index: Option<u64>,
+    #[serde(rename = "closing-amount")]
+    closing_amount: Option<u64>,
+    #[serde(rename = "asset-closing-amount")]
+    asset_closing_amount: Option<u64>,
+    #[serde(rename = "sender-rewards")]
+    sender_rewards: Option<u64>,
+    #[serde(rename = "receiver-rewards")]
+    receiver_rewards: Option<u64>,
+    #[serde(rename = "close-rewards")]
+    close_rewards: Option<u64>,
+    #[serde(rename = "logs", default)]
+    logs: Vec<ByteBuf>,
+    #[serde(rename = "global-state-delta")]
+    global_state_delta: Option<Vec<EvalDeltaKeyValue>>,
+    #[serde(rename = "local-state-delta")]
+    local_state_delta: Option<Vec<AccountStateDelta>>,
+}
+
+impl PendingTransaction {
+    /// Decodes algod's msgpack pending transaction response.
+    pub fn from_msgpack(bytes: &[u8]) -> Result<PendingTransaction, Error> {
+        let raw_signed_transaction =
+            map_value(bytes, "txn")?.ok_or_else(|| Error::custom("missing field `txn`"))?;
+        let raw_transaction = map_value(raw_signed_transaction, "txn")?
+            .ok_or_else(|| Error::custom("missing field `txn.txn`"))?;
+        let fields: PendingTransactionFields = rmp_serde::from_slice(bytes)?;
+
+        let raw_id = raw_transaction_id(raw_transaction);
+        let mut signed_transaction: SignedTransaction =
+            rmp_serde::from_slice(raw_signed_transaction)?;
+        signed_transaction.transaction_id = BASE32_NOPAD.encode(&raw_id.0);
+        Ok(PendingTransaction {
+            signed_transaction,
+            raw_signed_transaction: raw_signed_transaction.to_owned(),
+            confirmed_round: fields.confirmed_round,
+            pool_error: fields.pool_error,
+            application_index: fields.application_index,
+            asset_index: fields.asset_index,
+            closing_amount: fields.closing_amount,
+            asset_closing_amount: fields.asset_closing_amount,
+            sender_rewards: fields.sender_rewards,
+            receiver_rewards: fields.receiver_rewards,
+            close_rewards: fields.close_rewards,
+            logs: fields.logs.into_iter().map(ByteBuf::into_vec).collect(),
+            global_state_delta: fields.global_state_delta,
+            local_state_delta: fields.local_state_delta,
+            inner_txns: pending_inner_txns(bytes, &raw_id)?,
+        })
+    }
+}
+
+/// Decodes the inner transactions of the pending transaction response `bytes`,
+/// issued by the transaction with the raw id `parent_id`.
+fn pending_inner_txns(
+    bytes: &[u8],
+    parent_id: &HashDigest,
+) -> Result<Vec<PendingInnerTransaction>, Error> {
+    let items = match map_value(bytes, "inner-txns")? {
+        Some(items) => array_items(items)?,
+        None => return Ok(vec![]),
+    };
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, bytes)| {
+            // inner transactions are wrapped in a signed transaction without signature
+            let raw_transaction = map_value(bytes, "txn")?
+                .and_then(|txn| map_value(txn, "txn").transpose())
+                .transpose()?
+                .ok_or_else(|| Error::custom("missing field `txn.txn`"))?;
+            let fields: PendingTransactionFields = rmp_serde::from_slice(bytes)?;
+            let raw_id = inner_raw_transaction_id(raw_transaction, parent_id, index as u64);
+            Ok(PendingInnerTransaction {
+                transaction: rmp_serde::from_slice(raw_transaction)?,
+                transaction_id: BASE32_NOPAD.encode(&raw_id.0),
+                raw_transaction: raw_transaction.to_owned(),
+                application_index: fields.application_index,
+                asset_index: fields.asset_index,
+                closing_amount: fields.closing_amount,
+                asset_closing_amount: fields.asset_closing_amount,
+                logs: fields.logs.into_iter().map(ByteBuf::into_vec).collect(),
+                global_state_delta: fields.global_state_delta,
+                local_state_delta: fields.local_state_delta,
+                inner_txns: pending_inner_txns(bytes, &raw_id)?,
+            })
+        })
+        .collect()
+}
+
+/// The raw id of a transaction with the msgpack encoding `bytes`.
+fn raw_transaction_id(bytes: &[u8]) -> HashDigest {
+    HashDigest(Sha512_256::digest([b"TX".as_slice(), bytes].concat()).into())
+}
+
+/// The raw id of an inner transaction with the msgpack encoding `bytes`,
+/// see [Transaction::inner_raw_id].
+fn inner_raw_transaction_id(bytes: &[u8], parent_id: &HashDigest, index: u64) -> HashDigest {
+    let mut hashed = b"TX".to_vec();
+    hashed.extend_from_slice(&parent_id.0);
+    hashed.extend_from_slice(&index.to_be_bytes());
+    hashed.extend_from_slice(bytes);
+    HashDigest(Sha512_256::digest(hashed).into())
+}
+
+/// The msgpack map of a transaction `txn` of a block, with the genesis id and hash the block removed
+/// added back, if they're set. Keys stay sorted, as in the canonical encoding the transaction was signed in.
+fn with_genesis(
+    txn: &[u8],
+    genesis_id: Option<&str>,
+    genesis_hash: &HashDigest,
+) -> Result<Vec<u8>, Error> {
+    let mut entries = map_entries(txn)?
+        .into_iter()
+        .map(|(key, entry)| (key.unwrap_or_default().to_vec(), entry.to_vec()))
+        .collect::<Vec<_>>();
+    let has_key = |entries: &[(Vec<u8>, Vec<u8>)], key: &str| {
+        entries.iter().any(|(k, _)| k.as_slice() == key.as_bytes())
+    };
+    let encode = |key: &str, value: Vec<u8>| -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut entry = rmp_serde::to_vec(key).map_err(Error::custom)?;
+        entry.extend(value);
+        Ok((key.as_bytes().to_vec(), entry))
+    };
+
+    let len = entries.len();
+    match genesis_id {
+        Some(genesis_id) if !genesis_id.is_empty() && !has_key(&entries, "gen") => {
+            let value = rmp_serde::to_vec(genesis_id).map_err(Error::custom)?;
+            entries.push(encode("gen", value)?);
+        }
+        _ => {}
+    }
+    if *genesis_hash != HashDigest::default() && !has_key(&entries, "gh") {
+        let value =
+            rmp_serde::to_vec(&ByteBuf::from(genesis_hash.0.to_vec())).map_err(Error::custom)?;
+        entries.push(encode("gh", value)?);
+    }
+    if entries.len() == len {
+        return Ok(txn.to_vec());
+    }
+
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut bytes = match entries.len() {
+        len if len < 16 => vec![0x80 | len as u8],
+        len => match u16::try_from(len) {
+            Ok(len) => [&[0xde][..], &len.to_be_bytes()].concat(),
+            Err(_) => [&[0xdf][..], &(len as u32).to_be_bytes()].concat(),
+        },
+    };
+    for (_, entry) in entries {
+        bytes.extend(entry);
+    }
+    Ok(bytes)
+}
+
+/// Converts a msgpack response of algod into algod's JSON encoding of it,
+/// to decode it into the models of the JSON response.
+///
+/// As in algod's JSON encoding, binary values are base64 encoded, except for addresses,
+/// which are encoded with their checksum, and block hashes, which are prefixed with `blk-`.
+/// Map keys are converted to strings. Like in algod's JSON encoding, strings that aren't
+/// valid UTF-8, e.g. logs and state keys in blocks, can't be represented: use the types of this module
+/// to decode them losslessly.
+pub fn to_json(bytes: &[u8]) -> Result<Value, Error> {
+    if value_len(bytes)? != bytes.len() {
+        return Err(Error::custom("trailing bytes after msgpack value"));
+    }
+    json_value(bytes, "", "")
+}
+
+/// Keys of addresses in algod's responses, regardless of the parent key.
+const ADDRESS_KEYS: &[&str] = &[
+    "aclose",
+    "apat",
+    "arcv",
+    "asnd",
+    "close",
+    "fadd",
+    "fees",
+    "partupdabs",
+    "partupdrmv",
+    "prp",
+    "rcv",
+    "rekey",
+    "rwd",
+    "sa",
+    "sgnr",
+    "snd",
+];
+
+/// Converts the msgpack value `bytes`, which is the value of `key` in a map,
+/// itself the value of `parent_key`.
+fn json_value(bytes: &[u8], key: &str, parent_key: &str) -> Result<Value, Error> {
+    Ok(match header(bytes)? {
+        Header::Scalar(len) => rmp_serde::from_slice(&bytes[..len])?,
+        Header::Str(len, offset) => {
+            Value::String(String::from_utf8_lossy(&bytes[offset..offset + len]).into_owned())
+        }
+        Header::Bytes(_, _) if matches!(bytes[0], 0xc7..=0xc9) => {
+            return Err(Error::custom("unexpected msgpack extension"))
+        }
+        Header::Bytes(len, offset) => {
+            Value::String(json_bytes(&bytes[offset..offset + len], key, parent_key))
+        }
+        Header::Array(items, mut offset) => {
+            let mut values = Vec::with_capacity(items.min(bytes.len()));
+            for _ in 0..items {
+                let len = value_len(&bytes[offset..])?;
+                values.push(json_value(&bytes[offset..offset + len], key, parent_key)?);
+                offset += len;
+            }
+            Value::Array(values)
+        }
+        Header::Map(_, _) => {
+            let mut map = serde_json::Map::new();
+            for (entry_key, entry) in map_entries(bytes)? {
+                let key_len = value_len(entry)?;
+                let entry_key = match entry_key {
+                    Some(entry_key) => String::from_utf8_lossy(entry_key).into_owned(),
+                    None => json_key(&entry[..key_len])?,
+                };
+                let value = json_value(&entry[key_len..], &entry_key, key)?;
+                map.insert(entry_key, value);
+            }
+            Value::Object(map)
+        }
+    })
+}
+
+fn json_bytes(bytes: &[u8], key: &str, parent_key: &str) -> String {
+    let is_address = ADDRESS_KEYS.contains(&key)
+        || (parent_key == "apar" && ["c", "f", "m", "r"].contains(&key))
+        || (parent_key == "hb" && key == "a");
+    match <[u8; 32]>::try_from(bytes) {
+        Ok(bytes) if is_address => Address(bytes).to_string(),
+        Ok(bytes) if key == "prev" => format!("blk-{}", BASE32_NOPAD.encode(&bytes)),
+        _ => BASE64.encode(bytes),
+    }
+}
+
+/// Converts a map key that isn't a string, e.g. an account index of local state deltas.
+fn json_key(bytes: &[u8]) -> Result<String, Error> {
+    match header(bytes)? {
+        Header::Scalar(_) => Ok(rmp_serde::from_slice::<Value>(bytes)?.to_string()),
+        Header::Bytes(len, offset) if !matches!(bytes[0], 0xc7..=0xc9) => {
+            Ok(BASE64.encode(&bytes[offset..offset + len]))
+        }
+        _ => Err(Error::custom("unexpected msgpack map key")),
+    }
+}
+
+/// The encoding of the value of `key` in the msgpack map `bytes`, if the map has the key.
+fn map_value<'a>(bytes: &'a [u8], key: &str) -> Result<Option<&'a [u8]>, Error> {
+    if !matches!(header(bytes)?, Header::Map(_, _)) {
+        return Err(Error::custom(format!("expected a map with key: {key}")));
+    }
+    for (entry_key, entry) in map_entries(bytes)? {
+        if entry_key == Some(key.as_bytes()) {
+            return Ok(Some(&entry[value_len(entry)?..]));
+        }
+    }
+    Ok(None)
+}
+
+/// An entry of a msgpack map: the content of its key, if it's a string,
+/// and the encoding of the key followed by the value.
+type MapEntry<'a> = (Option<&'a [u8]>, &'a [u8]);
+
+/// The entries of the msgpack map `bytes`.
+fn map_entries(bytes: &[u8]) -> Result<Vec<MapEntry<'_>>, Error> {
+    let (len, mut offset) = match header(bytes)? {
+        Header::Map(len, offset) => (len, offset),
+        _ => return Err(Error::custom("expected a map")),
+    };
+    let mut entries = vec![];
+    for _ in 0..len {
+        let key_len = value_len(&bytes[offset..])?;
+        let value_len = value_len(&bytes[offset + key_len..])?;
+        let entry = &bytes[offset..offset + key_len + value_len];
+        entries.push((str_value(&entry[..key_len]), entry));
+        offset += entry.len();
+    }
+    Ok(entries)
+}
+
+/// The encodings of the items of the msgpack array `bytes`.
+fn array_items(bytes: &[u8]) -> Result<Vec<&[u8]>, Error> {
+    let (len, mut offset) = match header(bytes)? {
+        Header::Array(len, offset) => (len, offset),
+        _ => return Err(Error::custom("expected an array")),
+    };
+    let mut items = vec![];
+    for _ in 0..len {
+        let len = value_len(&bytes[offset..])?;
+        items.push(&bytes[offset..offset + len]);
+        offset += len;
+    }
+    Ok(items)
+}
+
+/// The content of a msgpack string.
+fn str_value(bytes: &[u8]) -> Option<&[u8]> {
+    match header(bytes).ok()? {
+        Header::Str(len, offset) => bytes.get(offset..offset.checked_add(len)?),
+        _ => None,
+    }
+}
+
+/// The first byte(s) of a msgpack value, with the length of the header.
+enum Header {
+    /// Values without content, e.g. integers.
+    Scalar(usize),
+    /// Strings, binary and extension values: content length and header length.
+    Str(usize, usize),
+    Bytes(usize, usize),
+    /// Number of items and header length.
+    Array(usize, usize),
+    /// Number of entries and header length.
+    Map(usize, usize),
+}
+
+fn header(bytes: &[u8]) -> Result<Header, Error> {
+    let marker = *bytes.first().ok_or_else(unexpected_end)?;
+    let len = |size: usize| -> Result<usize, Error> {
+        let len_bytes = bytes.get(1..1 + size).ok_or_else(unexpected_end)?;
+        Ok(len_bytes
+            .iter()
+            .fold(0usize, |len, byte| (len << 8) | *byte as usize))
+    };
+    // the type byte of extensions follows the length
+    let ext_len = |size: usize| -> Result<usize, Error> {
+        len(size)?.checked_add(1).ok_or_else(unexpected_end)
+    };
+    Ok(match marker {
+        0x00..=0x7f | 0xe0..=0xff | 0xc0 | 0xc2 | 0xc3 => Header::Scalar(1),
+        0x80..=0x8f => Header::Map((marker & 0x0f) as usize, 1),
+        0x90..=0x9f => Header::Array((marker & 0x0f) as usize, 1),
+        0xa0..=0xbf => Header::Str((marker & 0x1f) as usize, 1),
+        0xc4 => Header::Bytes(len(1)?, 2),
+        0xc5 => Header::Bytes(len(2)?, 3),
+        0xc6 => Header::Bytes(len(4)?, 5),
+        0xc7 => Header::Bytes(ext_len(1)?, 2),
+        0xc8 => Header::Bytes(ext_len(2)?, 3),
+        0xc9 => Header::Bytes(ext_len(4)?, 5),
+        0xca => Header::Scalar(5),
+        0xcb => Header::Scalar(9),
+        0xcc | 0xd0 => Header::Scalar(2),
+        0xcd | 0xd1 => Header::Scalar(3),
+        0xce | 0xd2 => Header::Scalar(5),
+        0xcf | 0xd3 => Header::Scalar(9),
+        0xd4 => Header::Scalar(3),
+        0xd5 => Header::Scalar(4),
+        0xd6 => Header::Scalar(6),
+        0xd7 => Header::Scalar(10),
+        0xd8 => Header::Scalar(18),
+        0xd9 => Header::Str(len(1)?, 2),
+        0xda => Header::Str(len(2)?, 3),
+        0xdb => Header::Str(len(4)?, 5),
+        0xdc => Header::Array(len(2)?, 3),
+        0xdd => Header::Array(len(4)?, 5),
+        0xde => Header::Map(len(2)?, 3),
+        0xdf => Header::Map(len(4)?, 5),
+        0xc1 => return Err(Error::custom("invalid msgpack marker: 0xc1")),
+    })
+}
+
+fn unexpected_end() -> Error {
+    Error::custom("unexpected end of msgpack")
+}
+
+/// The length of the encoding of the msgpack value at the start of `bytes`.
+fn value_len(bytes: &[u8]) -> Result<usize, Error> {
+    nested_value_len(bytes, 0)
+}
+
+/// [value_len] of a value nested in `depth` arrays and maps.
+fn nested_value_len(bytes: &[u8], depth: usize) -> Result<usize, Error> {
+    let (items, mut len) = match header(bytes)? {
+        Header::Scalar(len) => (0, len),
+        Header::Str(len, offset) | Header::Bytes(len, offset) => {
+            (0, offset.checked_add(len).ok_or_else(unexpected_end)?)
+        }
+        Header::Array(items, offset) => (items, offset),
+        Header::Map(entries, offset) => {
+            (entries.checked_mul(2).ok_or_else(unexpected_end)?, offset)
+        }
+    };
+    if items > 0 && depth >= MAX_DEPTH {
+        return Err(Error::custom(format!(
+            "msgpack nested deeper than {MAX_DEPTH} levels"
+        )));
+    }
+    // each item is at least 1 byte and within `bytes`, so `len` doesn't overflow
+    for _ in 0..items {
+        len += nested_value_len(bytes.get(len..).unwrap_or_default(), depth + 1)?;
+    }
+    if len > bytes.len() {
+        return Err(unexpected_end());
+    }
+    Ok(len)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_core::{MicroAlgos, Round, ToMsgPack};
+    use algonaut_model::transaction::StateProofType;
+    use algonaut_transaction::{
+        account::Account, builder::CallApplication, transaction::TransactionSignature, Pay,
+        TransactionType, TxnBuilder,
+    };
+    use serde::Serialize;
+    use serde_json::json;
+
+    /// Encodes a map with the encoded values, in the order of `entries`.
+    fn map(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut bytes = vec![0x80 | entries.len() as u8];
+        for (key, value) in entries {
+            bytes.extend(rmp_serde::to_vec(key).unwrap());
+            bytes.extend(value);
+        }
+        bytes
+    }
+
+    fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+        rmp_serde::to_vec_named(value).unwrap()
+    }
+
+    /// Encodes a string of arbitrary bytes, like go-algorand encodes logs and state keys.
+    fn raw_str(bytes: &[u8]) -> Vec<u8> {
+        [vec![0xa0 | bytes.len() as u8], bytes.to_vec()].concat()
+    }
+
+    /// Encodes an array of the encoded items.
+    fn array(items: &[Vec<u8>]) -> Vec<u8> {
+        [vec![0x90 | items.len() as u8], items.concat()].concat()
+    }
+
+    fn block(genesis_hash: HashDigest, txns: &[Vec<u8>]) -> MsgpackBlock {
+        MsgpackBlock::from_msgpack(&map(&[(
+            "block",
+            map(&[
+                ("gen", encode(&"mainnet-v1.0")),
+                ("gh", encode(&ByteBuf::from(genesis_hash.0.to_vec()))),
+                ("rnd", encode(&101u64)),
+                ("txns", array(txns)),
+            ]),
+        )]))
+        .unwrap()
+    }
+
+    fn signed_payment(genesis_id: &str, genesis_hash: HashDigest) -> SignedTransaction {
+        let account = Account::generate();
+        let payment = TxnBuilder::new(
+            MicroAlgos(1000),
+            Round(100),
+            Round(1100),
+            genesis_hash,
+            Pay::new(account.address(), account.address(), MicroAlgos(5)).build(),
+        )
+        .genesis_id(genesis_id.to_owned())
+        .note(vec![0xff, 0x00, 0xfe])
+        .build()
+        .unwrap();
+        account.sign_transaction(payment).unwrap()
+    }
+
+    #[test]
+    fn test_finds_raw_values() {
+        let nested = map(&[("a", encode(&vec![1u64, 300, 70000])), ("b", encode(&"x"))]);
+        let bytes = map(&[
+            ("bin", encode(&ByteBuf::from(vec![7; 300]))),
+            ("nested", nested.clone()),
+            ("neg", encode(&-100_000i64)),
+        ]);
+
+        assert_eq!(
+            Some(nested.as_slice()),
+            map_value(&bytes, "nested").unwrap()
+        );
+        assert_eq!(None, map_value(&bytes, "missing").unwrap());
+        let items = array_items(map_value(&nested, "a").unwrap().unwrap()).unwrap();
+        assert_eq!(
+            vec![encode(&1u64), encode(&300u64), encode(&70000u64)],
+            items
+        );
+        // truncated values are rejected
+        assert!(map_value(&bytes[..bytes.len() - 1], "neg").is_err());
+    }
+
+    #[test]
+    fn test_decodes_pending_transaction_with_exact_bytes() {
+        let signed = signed_payment("testnet-v1.0", HashDigest([1; 32]));
+        let raw_signed_transaction = signed.to_msg_pack().unwrap();
+        let bytes = map(&[
+            ("confirmed-round", encode(&101u64)),
+            ("logs", encode(&vec![ByteBuf::from(vec![0xff, 0x00])])),
+            ("pool-error", encode(&"")),
+            ("txn", raw_signed_transaction.clone()),
+        ]);
+
+        let pending = PendingTransaction::from_msgpack(&bytes).unwrap();
+
+        assert_eq!(signed, pending.signed_transaction);
+        assert_eq!(raw_signed_transaction, pending.raw_signed_transaction);
+        assert_eq!(Some(101), pending.confirmed_round);
+        assert_eq!(vec![vec![0xff, 0x00]], pending.logs);
+    }
+
+    #[test]
+    fn test_decodes_block_transactions() {
+        let genesis_hash = HashDigest([1; 32]);
+        let signed = signed_payment("mainnet-v1.0", genesis_hash);
+        // blocks don't repeat the genesis id and hash in transactions
+        let mut stripped = signed.clone();
+        stripped.transaction.genesis_id = None;
+        stripped.transaction.genesis_hash = HashDigest::default();
+        let mut txn = stripped.to_msg_pack().unwrap();
+        // append hgi to the map
+        txn[0] += 1;
+        txn.extend(encode(&"hgi"));
+        txn.extend(encode(&true));
+        let bytes = map(&[(
+            "block",
+            map(&[
+                ("gen", encode(&"mainnet-v1.0")),
+                ("gh", encode(&ByteBuf::from(genesis_hash.0.to_vec()))),
+                ("rnd", encode(&101u64)),
+                ("txns", [vec![0x91], txn.clone()].concat()),
+            ]),
+        )]);
+
+        let block = MsgpackBlock::from_msgpack(&bytes).unwrap();
+
+        assert_eq!(101, block.header.round);
+        assert_eq!(vec![txn], block.txns);
+        let txns = block.signed_transactions().unwrap();
+        assert_eq!(1, txns.len());
+        assert_eq!(signed, txns[0].signed_transaction);
+    }
+
+    #[test]
+    fn test_decodes_apply_data_and_inner_transactions_losslessly() {
+        let genesis_hash = HashDigest([1; 32]);
+        let account = Account::generate();
+        let call = TxnBuilder::new(
+            MicroAlgos(1000),
+            Round(100),
+            Round(1100),
+            genesis_hash,
+            CallApplication::new(account.address(), 5).build(),
+        )
+        .build()
+        .unwrap();
+        let signed = account.sign_transaction(call).unwrap();
+        let inner = TxnBuilder::new(
+            MicroAlgos(0),
+            Round(100),
+            Round(1100),
+            HashDigest::default(),
+            Pay::new(account.address(), account.address(), MicroAlgos(1)).build(),
+        )
+        .build()
+        .unwrap();
+
+        let mut stripped = signed.clone();
+        stripped.transaction.genesis_hash = HashDigest::default();
+        let stripped = stripped.to_msg_pack().unwrap();
+        let entry = map(&[
+            (
+                "dt",
+                map(&[
+                    (
+                        "gd",
+                        [
+                            vec![0x81],
+                            raw_str(&[0xff, b'k']),
+                            map(&[("at", encode(&2u64)), ("ui", encode(&7u64))]),
+                        ]
+                        .concat(),
+                    ),
+                    (
+                        "itx",
+                        array(&[map(&[
+                            ("dt", map(&[("lg", array(&[raw_str(&[0x80])]))])),
+                            ("txn", inner.to_msg_pack().unwrap()),
+                        ])]),
+                    ),
+                    ("ld", [vec![0x81, 0x00], map(&[])].concat()),
+                    ("lg", array(&[raw_str(&[0xc3, 0x28]), raw_str(b"ok")])),
+                ]),
+            ),
+            ("rs", encode(&3u64)),
+            (
+                "sig",
+                map_value(&stripped, "sig").unwrap().unwrap().to_vec(),
+            ),
+            (
+                "txn",
+                map_value(&stripped, "txn").unwrap().unwrap().to_vec(),
+            ),
+        ]);
+
+        let txn = block(genesis_hash, &[entry])
+            .signed_transactions()
+            .unwrap()
+            .remove(0);
+
+        assert_eq!(signed, txn.signed_transaction);
+        let apply_data = txn.apply_data;
+        assert_eq!(MicroAlgos(3), apply_data.sender_rewards);
+        let delta = apply_data.eval_delta;
+        assert_eq!(vec![vec![0xc3, 0x28], b"ok".to_vec()], delta.logs);
+        assert_eq!(
+            Some(&ValueDelta::SetUint(7)),
+            delta.global_delta.get([0xff, b'k'].as_slice())
+        );
+        assert_eq!(Some(&StateDelta::new()), delta.local_deltas.get(&0));
+        assert_eq!(1, delta.inner_txns.len());
+        let inner_txn = &delta.inner_txns[0];
+        assert_eq!(inner, inner_txn.transaction);
+        let parent_id = signed.transaction.raw_id().unwrap();
+        assert_eq!(
+            inner.inner_id(&parent_id, 0).unwrap(),
+            inner_txn.transaction_id
+        );
+        assert_eq!(vec![vec![0x80]], inner_txn.apply_data.eval_delta.logs);
+    }
+
+    #[test]
+    fn test_computes_block_transaction_ids_from_exact_bytes() {
+        let genesis_hash = HashDigest([1; 32]);
+        let signed = signed_payment("", genesis_hash);
+        let mut stripped = signed.clone();
+        stripped.transaction.genesis_hash = HashDigest::default();
+        let stripped = stripped.to_msg_pack().unwrap();
+        // a field unknown to this version isn't decoded, but is part of the id
+        let raw_txn = map_value(&stripped, "txn").unwrap().unwrap();
+        let unknown = |raw_txn: &[u8]| {
+            let mut entries = map_entries(raw_txn)
+                .unwrap()
+                .into_iter()
+                .map(|(_, entry)| entry.to_vec())
+                .collect::<Vec<_>>();
+            entries.push([encode(&"zzz"), encode(&1u64)].concat());
+            [vec![0x80 | entries.len() as u8], entries.concat()].concat()
+        };
+        let entry = map(&[
+            (
+                "sig",
+                map_value(&stripped, "sig").unwrap().unwrap().to_vec(),
+            ),
+            ("txn", unknown(raw_txn)),
+        ]);
+
+        let txn = block(genesis_hash, &[entry])
+            .signed_transactions()
+            .unwrap()
+            .remove(0);
+
+        let signed_txn = signed.transaction.to_msg_pack().unwrap();
+        assert_eq!(
+            BASE32_NOPAD.encode(&raw_transaction_id(&unknown(&signed_txn)).0),
+            txn.signed_transaction.transaction_id
+        );
+        assert_ne!(signed.transaction_id, txn.signed_transaction.transaction_id);
+    }
+
+    #[test]
+    fn test_decodes_state_proof_transactions() {
+        let genesis_hash = HashDigest([1; 32]);
+        let bin = |byte: u8, len: usize| encode(&ByteBuf::from(vec![byte; len]));
+        let sumhash = || map(&[("t", encode(&1u64))]);
+        // like go-algorand encodes it: unsigned, zero values (e.g. the basic sptype) omitted,
+        // and integer keys for the reveals
+        let reveal = map(&[(
+            "p",
+            map(&[
+                ("p", map(&[("cmt", bin(5, 64)), ("lf", encode(&256u64))])),
+                ("w", encode(&1000u64)),
+            ]),
+        )]);
+        let state_proof = map(&[
+            (
+                "P",
+                map(&[
+                    ("hsh", sumhash()),
+                    ("pth", array(&[bin(2, 64)])),
+                    ("td", encode(&2u64)),
+                ]),
+            ),
+            ("S", map(&[("hsh", sumhash()), ("td", encode(&1u64))])),
+            ("c", bin(3, 64)),
+            ("pr", encode(&vec![3u64])),
+            ("r", [vec![0x81], encode(&3u64), reveal].concat()),
+            ("w", encode(&2000u64)),
+        ]);
+        let txn = map(&[
+            ("fv", encode(&100u64)),
+            ("lv", encode(&1100u64)),
+            ("snd", bin(4, 32)),
+            ("sp", state_proof),
+            (
+                "spmsg",
+                map(&[
+                    ("b", bin(6, 32)),
+                    ("f", encode(&257u64)),
+                    ("l", encode(&512u64)),
+                ]),
+            ),
+            ("type", encode(&"stpf")),
+        ]);
+        let entry = map(&[("hgi", encode(&true)), ("txn", txn)]);
+
+        let txn = block(genesis_hash, &[entry])
+            .signed_transactions()
+            .unwrap()
+            .remove(0);
+
+        let signed_transaction = &txn.signed_transaction;
+        assert_eq!(TransactionSignature::Unsigned, signed_transaction.sig);
+        let state_proof = match &signed_transaction.transaction.txn_type {
+            TransactionType::StateProofTransaction(state_proof) => state_proof,
+            txn_type => panic!("not a state proof transaction: {:?}", txn_type),
+        };
+        assert_eq!(
+            StateProofType::StateProofBasic,
+            state_proof.state_proof_type
+        );
+        assert_eq!(2000, state_proof.state_proof.signed_weight);
+        let reveal = &state_proof.state_proof.reveals[&3];
+        assert_eq!(vec![5; 64], reveal.part.pk.commitment);
+        assert_eq!(1000, reveal.part.weight);
+        assert_eq!(257, state_proof.message.first_attested_round);
+        // re-encoding the transaction gives the bytes of the block
+        assert_eq!(
+            signed_transaction.transaction.id().unwrap(),
+            signed_transaction.transaction_id
+        );
+    }
+
+    #[test]
+    fn test_decodes_pending_inner_transactions_and_deltas() {
+        let signed = signed_payment("testnet-v1.0", HashDigest([1; 32]));
+        let raw_signed_transaction = signed.to_msg_pack().unwrap();
+        let mut inner = signed_payment("", HashDigest::default()).transaction;
+        inner.genesis_id = None;
+        let raw_inner = inner.to_msg_pack().unwrap();
+        let bytes = map(&[
+            (
+                "global-state-delta",
+                array(&[map(&[
+                    ("key", encode(&"/w==")),
+                    (
+                        "value",
+                        map(&[("action", encode(&2u64)), ("uint", encode(&7u64))]),
+                    ),
+                ])]),
+            ),
+            (
+                "inner-txns",
+                array(&[map(&[
+                    ("logs", array(&[encode(&ByteBuf::from(vec![0xff]))])),
+                    ("pool-error", encode(&"")),
+                    ("txn", map(&[("txn", raw_inner.clone())])),
+                ])]),
+            ),
+            ("pool-error", encode(&"")),
+            ("txn", raw_signed_transaction),
+        ]);
+
+        let pending = PendingTransaction::from_msgpack(&bytes).unwrap();
+
+        let delta = &pending.global_state_delta.unwrap()[0];
+        assert_eq!("/w==", delta.key);
+        assert_eq!(Some(7), delta.value.uint);
+        assert_eq!(1, pending.inner_txns.len());
+        let inner_txn = &pending.inner_txns[0];
+        assert_eq!(inner, inner_txn.transaction);
+        assert_eq!(raw_inner, inner_txn.raw_transaction);
+        let parent_id = signed.transaction.raw_id().unwrap();
+        assert_eq!(
+            inner.inner_id(&parent_id, 0).unwrap(),
+            inner_txn.transaction_id
+        );
+        assert_eq!(vec![vec![0xff]], inner_txn.logs);
+    }
+
+    #[test]
+    fn test_rejects_malformed_nesting() {
+        let nested = [vec![0x91; 1000], vec![0x00]].concat();
+        assert!(value_len(&nested).is_err());
+        let shallow = [vec![0x91; MAX_DEPTH], vec![0x00]].concat();
+        assert_eq!(shallow.len(), value_len(&shallow).unwrap());
+        // entry counts beyond the input are rejected, not allocated or overflowed
+        assert!(value_len(&[0xdf, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(to_json(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(to_json(&[0x91, 0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_converts_to_algod_json() {
+        let address = Account::generate().address();
+        let bytes = map(&[
+            (
+                "apar",
+                map(&[
+                    ("am", encode(&ByteBuf::from(vec![2; 32]))),
+                    ("m", encode(&address)),
+                ]),
+            ),
+            ("ld", [vec![0x81, 0x01], map(&[])].concat()),
+            ("lg", array(&[raw_str(&[0xff]), raw_str(b"ok")])),
+            ("note", encode(&ByteBuf::from(vec![0xff, 0x00]))),
+            ("prev", encode(&ByteBuf::from(vec![1; 32]))),
+            ("snd", encode(&address)),
+            ("x", encode(&-3i64)),
+        ]);
+
+        assert_eq!(
+            json!({
+                "apar": {"am": BASE64.encode(&[2; 32]), "m": address.to_string()},
+                "ld": {"1": {}},
+                "lg": ["\u{fffd}", "ok"],
+                "note": "/wA=",
+                "prev": format!("blk-{}", BASE32_NOPAD.encode(&[1; 32])),
+                "snd": address.to_string(),
+                "x": -3,
+            }),
+            to_json(&bytes).unwrap()
+        );
+    }
+}
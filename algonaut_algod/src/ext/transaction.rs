@@ -1,12 +1,17 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TransactionHeader {
+    /// Whether the genesis id was removed from the transaction, to be restored from the block.
     pub hgi: Option<bool>,
+    /// Whether the genesis hash was removed from the transaction, to be restored from the block.
+    pub hgh: Option<bool>,
     pub sig: Option<String>,
-    pub msig: Option<String>,
-    pub lsig: Option<String>,
+    pub msig: Option<MultisigSig>,
+    pub lsig: Option<LogicSig>,
+    /// The address that signed the transaction, if it isn't the sender.
+    pub sgnr: Option<String>,
 
     #[serde(flatten)]
     pub apply_data: Option<ApplyData>,
@@ -143,7 +148,7 @@ pub enum Transaction {
         asset_sender: Option<String>,
         #[serde(rename = "arcv")]
         asset_receiver: Option<String>,
-        #[serde(rename = "close")]
+        #[serde(rename = "aclose")]
         asset_close_remainder_to: Option<String>,
     },
     #[serde(rename = "afrz")]
@@ -173,7 +178,7 @@ pub enum Transaction {
         freeze_account: Option<String>,
         #[serde(rename = "faid")]
         asset_id: Option<u64>,
-        #[serde(rename = "ffrz")]
+        #[serde(rename = "afrz")]
         frozen: Option<bool>,
     },
     #[serde(rename = "appl")]
@@ -224,12 +229,98 @@ pub enum Transaction {
         #[serde(rename = "apbx")]
         boxes: Option<Vec<BoxReference>>,
     },
+    #[serde(rename = "hb")]
+    Heartbeat {
+        #[serde(rename = "fee")]
+        fee: Option<u64>,
+        #[serde(rename = "fv")]
+        first_valid: Option<u64>,
+        #[serde(rename = "gh")]
+        genesis_hash: Option<String>,
+        #[serde(rename = "lv")]
+        last_valid: Option<u64>,
+        #[serde(rename = "snd")]
+        sender: Option<String>,
+        #[serde(rename = "gen")]
+        genesis_id: Option<String>,
+        #[serde(rename = "grp")]
+        group: Option<String>,
+        #[serde(rename = "lx")]
+        lease: Option<String>,
+        #[serde(rename = "note")]
+        note: Option<String>,
+        #[serde(rename = "rekey")]
+        rekey: Option<String>,
+        // type specific fields
+        #[serde(rename = "hb")]
+        heartbeat: Option<HeartbeatFields>,
+    },
+    #[serde(rename = "stpf")]
+    StateProof {
+        #[serde(rename = "fee")]
+        fee: Option<u64>,
+        #[serde(rename = "fv")]
+        first_valid: Option<u64>,
+        #[serde(rename = "gh")]
+        genesis_hash: Option<String>,
+        #[serde(rename = "lv")]
+        last_valid: Option<u64>,
+        #[serde(rename = "snd")]
+        sender: Option<String>,
+        #[serde(rename = "gen")]
+        genesis_id: Option<String>,
+        #[serde(rename = "grp")]
+        group: Option<String>,
+        #[serde(rename = "lx")]
+        lease: Option<String>,
+        #[serde(rename = "note")]
+        note: Option<String>,
+        #[serde(rename = "rekey")]
+        rekey: Option<String>,
+        // type specific fields
+        #[serde(rename = "sptype")]
+        state_proof_type: Option<u64>,
+        #[serde(rename = "sp")]
+        state_proof: Option<serde_json::Value>,
+        #[serde(rename = "spmsg")]
+        message: Option<serde_json::Value>,
+    },
+}
+
+impl Transaction {
+    /// The sender's address.
+    pub fn sender(&self) -> Option<&str> {
+        match self {
+            Transaction::Payment { sender, .. }
+            | Transaction::KeyRegistration { sender, .. }
+            | Transaction::AssetConfig { sender, .. }
+            | Transaction::AssetTransfer { sender, .. }
+            | Transaction::AssetFreeze { sender, .. }
+            | Transaction::Application { sender, .. }
+            | Transaction::Heartbeat { sender, .. }
+            | Transaction::StateProof { sender, .. } => sender.as_deref(),
+        }
+    }
+
+    /// The note, base64 encoded.
+    pub fn note(&self) -> Option<&str> {
+        match self {
+            Transaction::Payment { note, .. }
+            | Transaction::KeyRegistration { note, .. }
+            | Transaction::AssetConfig { note, .. }
+            | Transaction::AssetTransfer { note, .. }
+            | Transaction::AssetFreeze { note, .. }
+            | Transaction::Application { note, .. }
+            | Transaction::Heartbeat { note, .. }
+            | Transaction::StateProof { note, .. } => note.as_deref(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct AssetParams {
     #[serde(rename = "am")]
-    pub meta_data_hash: Option<Vec<u8>>,
+    pub meta_data_hash: Option<String>,
     #[serde(rename = "an")]
     pub asset_name: Option<String>,
     #[serde(rename = "au")]
@@ -252,10 +343,71 @@ pub struct AssetParams {
     pub unit_name: Option<String>,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct HeartbeatFields {
+    #[serde(rename = "a")]
+    pub address: Option<String>,
+    #[serde(rename = "kd")]
+    pub key_dilution: Option<u64>,
+    #[serde(rename = "prf")]
+    pub proof: Option<HeartbeatProof>,
+    #[serde(rename = "sd")]
+    pub seed: Option<String>,
+    #[serde(rename = "vid")]
+    pub vote_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct HeartbeatProof {
+    #[serde(rename = "p")]
+    pub pk: Option<String>,
+    #[serde(rename = "p1s")]
+    pub pk1_sig: Option<String>,
+    #[serde(rename = "p2")]
+    pub pk2: Option<String>,
+    #[serde(rename = "p2s")]
+    pub pk2_sig: Option<String>,
+    #[serde(rename = "s")]
+    pub sig: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct BoxReference {
+    /// Index of the box's app in the foreign apps, 1 based. None or 0 is the called app.
+    #[serde(rename = "i")]
+    pub index: Option<u64>,
     #[serde(rename = "n")]
-    name: String
+    pub name: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct MultisigSig {
+    #[serde(rename = "subsig")]
+    pub subsigs: Option<Vec<MultisigSubsig>>,
+    #[serde(rename = "thr")]
+    pub threshold: Option<u8>,
+    #[serde(rename = "v")]
+    pub version: Option<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct MultisigSubsig {
+    #[serde(rename = "pk")]
+    pub key: Option<String>,
+    #[serde(rename = "s")]
+    pub sig: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct LogicSig {
+    #[serde(rename = "l")]
+    pub logic: Option<String>,
+    #[serde(rename = "arg")]
+    pub args: Option<Vec<String>>,
+    #[serde(rename = "sig")]
+    pub sig: Option<String>,
+    #[serde(rename = "msig")]
+    pub msig: Option<MultisigSig>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -266,31 +418,65 @@ pub struct StateSchema {
     pub byte_slices: Option<u64>,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+/// Encoded as its number.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum DeltaAction {
     SetBytesAction = 1,
     SetUintAction = 2,
     DeleteAction = 3,
 }
 
+impl Serialize for DeltaAction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeltaAction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(DeltaAction::SetBytesAction),
+            2 => Ok(DeltaAction::SetUintAction),
+            3 => Ok(DeltaAction::DeleteAction),
+            action => Err(serde::de::Error::custom(format!(
+                "Unknown delta action: {action}"
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct ValueDelta {
     #[serde(rename = "at")]
     pub action: Option<DeltaAction>,
+    /// Not base64 encoded: algod encodes the bytes as a string.
     #[serde(rename = "bs")]
     pub bytes: Option<String>,
     #[serde(rename = "ui")]
     pub uint: Option<u64>,
 }
 
-type StateDelta = HashMap<String, ValueDelta>;
+/// Keyed by state key. Like the values' bytes, the keys aren't base64 encoded.
+pub type StateDelta = HashMap<String, ValueDelta>;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EvalDelta {
     #[serde(rename = "gd")]
     pub global_delta: Option<StateDelta>,
+    /// Keyed by the index of the account, see [EvalDelta::shared_accounts].
     #[serde(rename = "ld")]
     pub local_deltas: Option<HashMap<String, StateDelta>>,
+    /// Accounts with local deltas that weren't referenced by the app call,
+    /// their indices follow the ones of the app call's accounts.
+    #[serde(rename = "sa")]
+    pub shared_accounts: Option<Vec<String>>,
+    /// Not base64 encoded: algod encodes the logs as strings.
     #[serde(rename = "lg")]
     pub logs: Option<Vec<String>>,
     #[serde(rename = "itx")]
@@ -304,5 +490,68 @@ pub struct ApplyData {
     #[serde(rename = "ca")]
     pub closing_amount: Option<u64>,
     #[serde(rename = "aca")]
-    pub asset_closing_amount: Option<u64>
+    pub asset_closing_amount: Option<u64>,
+    #[serde(rename = "rs")]
+    pub sender_rewards: Option<u64>,
+    #[serde(rename = "rr")]
+    pub receiver_rewards: Option<u64>,
+    #[serde(rename = "rc")]
+    pub close_rewards: Option<u64>,
+    /// Id of the asset created by the transaction.
+    #[serde(rename = "caid")]
+    pub config_asset: Option<u64>,
+    /// Id of the app created by the transaction.
+    #[serde(rename = "apid")]
+    pub application_id: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_heartbeat() {
+        let json = r#"{
+            "hgi": true,
+            "sig": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==",
+            "txn": {
+                "fv": 100,
+                "hb": {
+                    "a": "GH6HLJDUWNO3UU6GNSGVHUHQL2FPUHGHLNLMYIUVVSPDDPGWBT4JLAIE6I",
+                    "kd": 100,
+                    "prf": {
+                        "p": "BAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQ=",
+                        "p1s": "BQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQUFBQ==",
+                        "p2": "BgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgYGBgY=",
+                        "p2s": "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBw==",
+                        "s": "CAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgICA=="
+                    },
+                    "sd": "CQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQk=",
+                    "vid": "CgoKCgoKCgoKCgoKCgoKCgoKCgoKCgoKCgoKCgoKCgo="
+                },
+                "lv": 110,
+                "snd": "GH6HLJDUWNO3UU6GNSGVHUHQL2FPUHGHLNLMYIUVVSPDDPGWBT4JLAIE6I",
+                "type": "hb"
+            }
+        }"#;
+
+        let header: TransactionHeader = serde_json::from_str(json).unwrap();
+
+        match header.txn {
+            Some(Transaction::Heartbeat {
+                first_valid,
+                heartbeat: Some(heartbeat),
+                ..
+            }) => {
+                assert_eq!(Some(100), first_valid);
+                assert_eq!(Some(100), heartbeat.key_dilution);
+                assert_eq!(
+                    Some("CQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQkJCQk=".to_owned()),
+                    heartbeat.seed
+                );
+                assert!(heartbeat.proof.unwrap().sig.is_some());
+            }
+            other => panic!("Unexpected transaction: {:?}", other),
+        }
+    }
 }
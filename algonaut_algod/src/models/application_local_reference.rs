@@ -0,0 +1,28 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+/// ApplicationLocalReference : References an account's local state for an application.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ApplicationLocalReference {
+    /// Address of the account with the local state.
+    #[serde(rename = "account")]
+    pub account: String,
+    /// Application ID of the local state application.
+    #[serde(rename = "app")]
+    pub app: u64,
+}
+
+impl ApplicationLocalReference {
+    /// References an account's local state for an application.
+    pub fn new(account: String, app: u64) -> ApplicationLocalReference {
+        ApplicationLocalReference { account, app }
+    }
+}
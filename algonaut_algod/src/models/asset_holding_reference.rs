@@ -0,0 +1,28 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+/// AssetHoldingReference : References an asset held by an account.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct AssetHoldingReference {
+    /// Address of the account holding the asset.
+    #[serde(rename = "account")]
+    pub account: String,
+    /// Asset ID of the holding.
+    #[serde(rename = "asset")]
+    pub asset: u64,
+}
+
+impl AssetHoldingReference {
+    /// References an asset held by an account.
+    pub fn new(account: String, asset: u64) -> AssetHoldingReference {
+        AssetHoldingReference { account, asset }
+    }
+}
@@ -0,0 +1,37 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+use algonaut_encoding::Bytes;
+
+/// AvmValue : Represents an AVM value.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct AvmValue {
+    /// bytes value.
+    #[serde(rename = "bytes", skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Bytes>,
+    /// value type. Value `1` refers to **bytes**, value `2` refers to **uint64**
+    #[serde(rename = "type")]
+    pub value_type: u64,
+    /// uint value.
+    #[serde(rename = "uint", skip_serializing_if = "Option::is_none")]
+    pub uint: Option<u64>,
+}
+
+impl AvmValue {
+    /// Represents an AVM value.
+    pub fn new(value_type: u64) -> AvmValue {
+        AvmValue {
+            bytes: None,
+            value_type,
+            uint: None,
+        }
+    }
+}
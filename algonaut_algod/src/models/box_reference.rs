@@ -0,0 +1,30 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+use algonaut_encoding::Bytes;
+
+/// BoxReference : References a box of an application.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct BoxReference {
+    /// Application ID which this box belongs to
+    #[serde(rename = "app")]
+    pub app: u64,
+    /// Base64 encoded box name
+    #[serde(rename = "name")]
+    pub name: Bytes,
+}
+
+impl BoxReference {
+    /// References a box of an application.
+    pub fn new(app: u64, name: Bytes) -> BoxReference {
+        BoxReference { app, name }
+    }
+}
@@ -14,22 +14,32 @@ pub mod add_participation_key_200_response;
 pub use self::add_participation_key_200_response::AddParticipationKey200Response;
 pub mod application;
 pub use self::application::Application;
+pub mod application_local_reference;
+pub use self::application_local_reference::ApplicationLocalReference;
 pub mod application_local_state;
 pub use self::application_local_state::ApplicationLocalState;
 pub mod application_params;
 pub use self::application_params::ApplicationParams;
+pub mod application_state_operation;
+pub use self::application_state_operation::ApplicationStateOperation;
 pub mod application_state_schema;
 pub use self::application_state_schema::ApplicationStateSchema;
 pub mod asset;
 pub use self::asset::Asset;
 pub mod asset_holding;
 pub use self::asset_holding::AssetHolding;
+pub mod asset_holding_reference;
+pub use self::asset_holding_reference::AssetHoldingReference;
 pub mod asset_params;
 pub use self::asset_params::AssetParams;
+pub mod avm_value;
+pub use self::avm_value::AvmValue;
 pub mod model_box;
 pub use self::model_box::Box;
 pub mod box_descriptor;
 pub use self::box_descriptor::BoxDescriptor;
+pub mod box_reference;
+pub use self::box_reference::BoxReference;
 pub mod build_version;
 pub use self::build_version::BuildVersion;
 pub mod dryrun_request;
@@ -72,16 +82,28 @@ pub mod pending_transaction_response;
 pub use self::pending_transaction_response::PendingTransactionResponse;
 pub mod raw_transaction_200_response;
 pub use self::raw_transaction_200_response::RawTransaction200Response;
+pub mod scratch_change;
+pub use self::scratch_change::ScratchChange;
 pub mod simulate_request;
 pub use self::simulate_request::SimulateRequest;
 pub mod simulate_request_transaction_group;
 pub use self::simulate_request_transaction_group::SimulateRequestTransactionGroup;
+pub mod simulate_trace_config;
+pub use self::simulate_trace_config::SimulateTraceConfig;
 pub mod simulate_transaction_200_response;
 pub use self::simulate_transaction_200_response::SimulateTransaction200Response;
 pub mod simulate_transaction_group_result;
 pub use self::simulate_transaction_group_result::SimulateTransactionGroupResult;
 pub mod simulate_transaction_result;
 pub use self::simulate_transaction_result::SimulateTransactionResult;
+pub mod simulate_unnamed_resources_accessed;
+pub use self::simulate_unnamed_resources_accessed::SimulateUnnamedResourcesAccessed;
+pub mod simulation_eval_overrides;
+pub use self::simulation_eval_overrides::SimulationEvalOverrides;
+pub mod simulation_opcode_trace_unit;
+pub use self::simulation_opcode_trace_unit::SimulationOpcodeTraceUnit;
+pub mod simulation_transaction_exec_trace;
+pub use self::simulation_transaction_exec_trace::SimulationTransactionExecTrace;
 pub mod start_catchup_200_response;
 pub use self::start_catchup_200_response::StartCatchup200Response;
 pub mod state_proof;
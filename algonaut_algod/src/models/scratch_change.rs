@@ -0,0 +1,27 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+/// ScratchChange : A write operation into a scratch slot.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ScratchChange {
+    #[serde(rename = "new-value")]
+    pub new_value: crate::models::AvmValue,
+    /// The scratch slot written.
+    #[serde(rename = "slot")]
+    pub slot: u64,
+}
+
+impl ScratchChange {
+    /// A write operation into a scratch slot.
+    pub fn new(new_value: crate::models::AvmValue, slot: u64) -> ScratchChange {
+        ScratchChange { new_value, slot }
+    }
+}
@@ -12,6 +12,32 @@
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct SimulateRequest {
+    /// Allow transactions without signatures to be simulated as if they had correct signatures.
+    #[serde(
+        rename = "allow-empty-signatures",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allow_empty_signatures: Option<bool>,
+    /// Lifts limits on log opcode usage during simulation.
+    #[serde(rename = "allow-more-logging", skip_serializing_if = "Option::is_none")]
+    pub allow_more_logging: Option<bool>,
+    /// Allows access to unnamed resources during simulation.
+    #[serde(
+        rename = "allow-unnamed-resources",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allow_unnamed_resources: Option<bool>,
+    #[serde(rename = "exec-trace-config", skip_serializing_if = "Option::is_none")]
+    pub exec_trace_config: Option<crate::models::SimulateTraceConfig>,
+    /// Applies extra opcode budget during simulation for each transaction group.
+    #[serde(
+        rename = "extra-opcode-budget",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub extra_opcode_budget: Option<u64>,
+    /// If provided, specifies the round preceding the simulation. State changes through this round will be used to run this simulation. Usually only the 4 most recent rounds will be available (controlled by the node config value MaxAcctLookback). If not specified, defaults to the latest available round.
+    #[serde(rename = "round", skip_serializing_if = "Option::is_none")]
+    pub round: Option<u64>,
     /// The transaction groups to simulate.
     #[serde(rename = "txn-groups")]
     pub txn_groups: Vec<crate::models::SimulateRequestTransactionGroup>,
@@ -20,6 +46,14 @@ pub struct SimulateRequest {
 impl SimulateRequest {
     /// Request type for simulation endpoint.
     pub fn new(txn_groups: Vec<crate::models::SimulateRequestTransactionGroup>) -> SimulateRequest {
-        SimulateRequest { txn_groups }
+        SimulateRequest {
+            allow_empty_signatures: None,
+            allow_more_logging: None,
+            allow_unnamed_resources: None,
+            exec_trace_config: None,
+            extra_opcode_budget: None,
+            round: None,
+            txn_groups,
+        }
     }
 }
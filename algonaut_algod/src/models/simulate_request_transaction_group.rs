@@ -8,18 +8,20 @@
  * Generated by: https://openapi-generator.tech
  */
 
+use algonaut_model::transaction::ApiSignedTransaction;
+
 /// SimulateRequestTransactionGroup : A transaction group to simulate.
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct SimulateRequestTransactionGroup {
     /// An atomic transaction group.
     #[serde(rename = "txns")]
-    pub txns: Vec<String>,
+    pub txns: Vec<ApiSignedTransaction>,
 }
 
 impl SimulateRequestTransactionGroup {
     /// A transaction group to simulate.
-    pub fn new(txns: Vec<String>) -> SimulateRequestTransactionGroup {
+    pub fn new(txns: Vec<ApiSignedTransaction>) -> SimulateRequestTransactionGroup {
         SimulateRequestTransactionGroup { txns }
     }
 }
@@ -10,6 +10,10 @@
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct SimulateTransaction200Response {
+    #[serde(rename = "eval-overrides", skip_serializing_if = "Option::is_none")]
+    pub eval_overrides: Option<crate::models::SimulationEvalOverrides>,
+    #[serde(rename = "exec-trace-config", skip_serializing_if = "Option::is_none")]
+    pub exec_trace_config: Option<crate::models::SimulateTraceConfig>,
     /// The round immediately preceding this simulation. State changes through this round were used to run this simulation.
     #[serde(rename = "last-round")]
     pub last_round: u64,
@@ -32,6 +36,8 @@ impl SimulateTransaction200Response {
         would_succeed: bool,
     ) -> SimulateTransaction200Response {
         SimulateTransaction200Response {
+            eval_overrides: None,
+            exec_trace_config: None,
             last_round,
             txn_groups,
             version,
@@ -30,6 +30,11 @@ pub struct SimulateTransactionGroupResult {
     /// Simulation result for individual transactions
     #[serde(rename = "txn-results")]
     pub txn_results: Vec<crate::models::SimulateTransactionResult>,
+    #[serde(
+        rename = "unnamed-resources-accessed",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unnamed_resources_accessed: Option<crate::models::SimulateUnnamedResourcesAccessed>,
 }
 
 impl SimulateTransactionGroupResult {
@@ -43,6 +48,7 @@ impl SimulateTransactionGroupResult {
             failed_at: None,
             failure_message: None,
             txn_results,
+            unnamed_resources_accessed: None,
         }
     }
 }
@@ -18,6 +18,8 @@ pub struct SimulateTransactionResult {
         skip_serializing_if = "Option::is_none"
     )]
     pub app_budget_consumed: Option<u64>,
+    #[serde(rename = "exec-trace", skip_serializing_if = "Option::is_none")]
+    pub exec_trace: Option<crate::models::SimulationTransactionExecTrace>,
     /// Budget used during execution of a logic sig transaction.
     #[serde(
         rename = "logic-sig-budget-consumed",
@@ -29,6 +31,11 @@ pub struct SimulateTransactionResult {
     pub missing_signature: Option<bool>,
     #[serde(rename = "txn-result")]
     pub txn_result: Box<crate::models::PendingTransactionResponse>,
+    #[serde(
+        rename = "unnamed-resources-accessed",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unnamed_resources_accessed: Option<crate::models::SimulateUnnamedResourcesAccessed>,
 }
 
 impl SimulateTransactionResult {
@@ -36,9 +43,11 @@ impl SimulateTransactionResult {
     pub fn new(txn_result: crate::models::PendingTransactionResponse) -> SimulateTransactionResult {
         SimulateTransactionResult {
             app_budget_consumed: None,
+            exec_trace: None,
             logic_sig_budget_consumed: None,
             missing_signature: None,
             txn_result: Box::new(txn_result),
+            unnamed_resources_accessed: None,
         }
     }
 }
@@ -0,0 +1,52 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+/// SimulationEvalOverrides : The set of parameters and limits override during simulation. If this set of parameters is present, then evaluation parameters may differ from standard evaluation in certain ways.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct SimulationEvalOverrides {
+    /// If true, transactions without signatures are allowed and simulated as if they were properly signed.
+    #[serde(
+        rename = "allow-empty-signatures",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allow_empty_signatures: Option<bool>,
+    /// If true, allows access to unnamed resources during simulation.
+    #[serde(
+        rename = "allow-unnamed-resources",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allow_unnamed_resources: Option<bool>,
+    /// The extra opcode budget added to each transaction group during simulation
+    #[serde(
+        rename = "extra-opcode-budget",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub extra_opcode_budget: Option<u64>,
+    /// The maximum log calls one can make during simulation
+    #[serde(rename = "max-log-calls", skip_serializing_if = "Option::is_none")]
+    pub max_log_calls: Option<u64>,
+    /// The maximum byte number to log during simulation
+    #[serde(rename = "max-log-size", skip_serializing_if = "Option::is_none")]
+    pub max_log_size: Option<u64>,
+}
+
+impl SimulationEvalOverrides {
+    /// The set of parameters and limits override during simulation. If this set of parameters is present, then evaluation parameters may differ from standard evaluation in certain ways.
+    pub fn new() -> SimulationEvalOverrides {
+        SimulationEvalOverrides {
+            allow_empty_signatures: None,
+            allow_unnamed_resources: None,
+            extra_opcode_budget: None,
+            max_log_calls: None,
+            max_log_size: None,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+/// SimulationOpcodeTraceUnit : The set of trace information and effect from evaluating a single opcode.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct SimulationOpcodeTraceUnit {
+    /// The program counter of the current opcode being evaluated.
+    #[serde(rename = "pc")]
+    pub pc: u64,
+    /// The writes into scratch slots.
+    #[serde(rename = "scratch-changes", skip_serializing_if = "Option::is_none")]
+    pub scratch_changes: Option<Vec<crate::models::ScratchChange>>,
+    /// The indexes of the traces for inner transactions spawned by this opcode, if any.
+    #[serde(rename = "spawned-inners", skip_serializing_if = "Option::is_none")]
+    pub spawned_inners: Option<Vec<u64>>,
+    /// The values pushed to stack by evaluated opcode.
+    #[serde(rename = "stack-additions", skip_serializing_if = "Option::is_none")]
+    pub stack_additions: Option<Vec<crate::models::AvmValue>>,
+    /// The number of deleted stack values by this opcode.
+    #[serde(rename = "stack-pop-count", skip_serializing_if = "Option::is_none")]
+    pub stack_pop_count: Option<u64>,
+    /// The operations against the current application's states.
+    #[serde(rename = "state-changes", skip_serializing_if = "Option::is_none")]
+    pub state_changes: Option<Vec<crate::models::ApplicationStateOperation>>,
+}
+
+impl SimulationOpcodeTraceUnit {
+    /// The set of trace information and effect from evaluating a single opcode.
+    pub fn new(pc: u64) -> SimulationOpcodeTraceUnit {
+        SimulationOpcodeTraceUnit {
+            pc,
+            scratch_changes: None,
+            spawned_inners: None,
+            stack_additions: None,
+            stack_pop_count: None,
+            state_changes: None,
+        }
+    }
+}
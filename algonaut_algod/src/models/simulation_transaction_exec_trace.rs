@@ -0,0 +1,79 @@
+/*
+ * Algod REST API.
+ *
+ * API endpoint for algod operations.
+ *
+ * The version of the OpenAPI document: 0.0.1
+ * Contact: contact@algorand.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+use algonaut_encoding::Bytes;
+
+/// SimulationTransactionExecTrace : The execution trace of calling an app or a logic sig, containing the inner app call trace in a recursive way.
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct SimulationTransactionExecTrace {
+    /// SHA512_256 hash digest of the approval program executed in transaction.
+    #[serde(
+        rename = "approval-program-hash",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub approval_program_hash: Option<Bytes>,
+    /// Program trace that contains a trace of opcode effects in an approval program.
+    #[serde(
+        rename = "approval-program-trace",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub approval_program_trace: Option<Vec<crate::models::SimulationOpcodeTraceUnit>>,
+    /// SHA512_256 hash digest of the clear state program executed in transaction.
+    #[serde(
+        rename = "clear-state-program-hash",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub clear_state_program_hash: Option<Bytes>,
+    /// Program trace that contains a trace of opcode effects in a clear state program.
+    #[serde(
+        rename = "clear-state-program-trace",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub clear_state_program_trace: Option<Vec<crate::models::SimulationOpcodeTraceUnit>>,
+    /// If true, indicates that the clear state program failed and any persistent state changes it produced should be reverted once the program exits.
+    #[serde(
+        rename = "clear-state-rollback",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub clear_state_rollback: Option<bool>,
+    /// The error message explaining why the clear state program failed. This field will only be populated if clear-state-rollback is true and the failure was due to an execution error.
+    #[serde(
+        rename = "clear-state-rollback-error",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub clear_state_rollback_error: Option<String>,
+    /// An array of SimulationTransactionExecTrace representing the execution trace of any inner transactions executed.
+    #[serde(rename = "inner-trace", skip_serializing_if = "Option::is_none")]
+    pub inner_trace: Option<Vec<crate::models::SimulationTransactionExecTrace>>,
+    /// SHA512_256 hash digest of the logic sig executed in transaction.
+    #[serde(rename = "logic-sig-hash", skip_serializing_if = "Option::is_none")]
+    pub logic_sig_hash: Option<Bytes>,
+    /// Program trace that contains a trace of opcode effects in a logic sig.
+    #[serde(rename = "logic-sig-trace", skip_serializing_if = "Option::is_none")]
+    pub logic_sig_trace: Option<Vec<crate::models::SimulationOpcodeTraceUnit>>,
+}
+
+impl SimulationTransactionExecTrace {
+    /// The execution trace of calling an app or a logic sig, containing the inner app call trace in a recursive way.
+    pub fn new() -> SimulationTransactionExecTrace {
+        SimulationTransactionExecTrace {
+            approval_program_hash: None,
+            approval_program_trace: None,
+            clear_state_program_hash: None,
+            clear_state_program_trace: None,
+            clear_state_rollback: None,
+            clear_state_rollback_error: None,
+            inner_trace: None,
+            logic_sig_hash: None,
+            logic_sig_trace: None,
+        }
+    }
+}
@@ -8,6 +8,7 @@
  * Generated by: https://openapi-generator.tech
  */
 
+use algonaut_core::{MicroAlgos, Round, SuggestedTransactionParams};
 use algonaut_crypto::{deserialize_hash, HashDigest};
 use algonaut_transaction::builder::TransactionParams;
 
@@ -73,3 +74,19 @@ impl TransactionParams for TransactionParams200Response {
         &self.genesis_id
     }
 }
+
+impl From<TransactionParams200Response> for SuggestedTransactionParams {
+    /// The params of transactions valid for 1000 rounds after the last round,
+    /// like [TxnBuilder::with_fee](algonaut_transaction::TxnBuilder::with_fee) builds them.
+    fn from(params: TransactionParams200Response) -> Self {
+        SuggestedTransactionParams {
+            genesis_id: params.genesis_id,
+            genesis_hash: params.genesis_hash,
+            consensus_version: params.consensus_version,
+            fee_per_byte: MicroAlgos(params.fee),
+            min_fee: MicroAlgos(params.min_fee),
+            first_valid: Round(params.last_round),
+            last_valid: Round(params.last_round + 1000),
+        }
+    }
+}
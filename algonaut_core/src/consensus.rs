@@ -0,0 +1,255 @@
+use crate::{error::CoreError, MicroAlgos, SuggestedTransactionParams};
+
+/// Enables inner transactions and TEAL v5.
+pub const CONSENSUS_V30: &str =
+    "https://github.com/algorandfoundation/specs/tree/bc36005dbd776e6d1eaf0c560619bb183215645c";
+/// Enables TEAL v6 and inner app calls.
+pub const CONSENSUS_V31: &str =
+    "https://github.com/algorandfoundation/specs/tree/85e6db1fdbdef00aa232c75199e10dc5fe9498f6";
+/// Enables unlimited assets.
+pub const CONSENSUS_V32: &str =
+    "https://github.com/algorandfoundation/specs/tree/d5ac876d7ede07367dbaa26e149aa42589aac1f7";
+/// Enables large blocks and deeper block history for TEAL.
+pub const CONSENSUS_V33: &str =
+    "https://github.com/algorandfoundation/specs/tree/830a4a1b0ff8d7e85d1c9c4f57b7bb6e03fde3d7";
+/// Enables TEAL v7 and state proofs.
+pub const CONSENSUS_V34: &str =
+    "https://github.com/algorandfoundation/specs/tree/2dd5435993f6f6d65691140f592ebca5ef19ffbd";
+/// Updates the calculation of total stake in state proofs.
+pub const CONSENSUS_V35: &str =
+    "https://github.com/algorandfoundation/specs/tree/433d8e9a7274b6fca703d91213e05c7e6a589e69";
+/// Enables box storage.
+pub const CONSENSUS_V36: &str =
+    "https://github.com/algorandfoundation/specs/tree/44fa607d6051730f5264526bf3c108d51f0eadb6";
+/// Technical upgrade, released together with v38.
+pub const CONSENSUS_V37: &str =
+    "https://github.com/algorandfoundation/specs/tree/1ac4dd1f85470e1fb36c8a65520e1313d7dab9d5";
+/// Enables TEAL v9 and resource sharing.
+pub const CONSENSUS_V38: &str =
+    "https://github.com/algorandfoundation/specs/tree/abd3d4823c6f77349fc04c3af7b1e99fe4df699f";
+/// Enables dynamic filter timeouts and TEAL v10.
+pub const CONSENSUS_V39: &str =
+    "https://github.com/algorandfoundation/specs/tree/925a46433742afb0b51bb939354bd907fa88bf95";
+/// Enables staking incentives and TEAL v11.
+pub const CONSENSUS_V40: &str =
+    "https://github.com/algorandfoundation/specs/tree/236dcc18c9c507d794813ab768e467ea42d1b4d9";
+
+/// The latest known protocol version.
+pub const CONSENSUS_CURRENT_VERSION: &str = CONSENSUS_V40;
+
+/// Parameters of the Algorand consensus protocol relevant to clients.
+///
+/// The names follow the consensus parameters of go-algorand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsensusParams {
+    /// Min. fee of a transaction. Fees are pooled in a group.
+    pub min_txn_fee: MicroAlgos,
+    /// Max. number of rounds between the first and last valid round of a transaction.
+    pub max_txn_life: u64,
+    /// Max. length of a transaction note, in bytes.
+    pub max_txn_note_bytes: u64,
+    /// Max. number of transactions in a group.
+    pub max_tx_group_size: u64,
+    /// Max. number of inner transactions an app call can issue.
+    pub max_inner_transactions: u64,
+    /// Max. number of arguments of an app call.
+    pub max_app_args: u64,
+    /// Max. total length of the arguments of an app call, in bytes.
+    pub max_app_total_arg_len: u64,
+    /// Max. number of accounts an app call can reference.
+    pub max_app_txn_accounts: u64,
+    /// Max. number of foreign apps an app call can reference.
+    pub max_app_txn_foreign_apps: u64,
+    /// Max. number of foreign assets an app call can reference.
+    pub max_app_txn_foreign_assets: u64,
+    /// Max. number of accounts, foreign apps, foreign assets and boxes an app call can reference in total.
+    pub max_app_total_txn_references: u64,
+    /// Max. number of boxes an app call can reference.
+    pub max_app_box_references: u64,
+    /// Max. number of extra program pages of an app.
+    pub max_extra_app_program_pages: u64,
+    /// Max. total length of the approval and clear state programs without extra pages, in bytes.
+    /// Each extra page adds this length.
+    pub max_app_program_len: u64,
+    /// Max. number of global state entries of an app.
+    pub max_global_schema_entries: u64,
+    /// Max. number of local state entries of an app.
+    pub max_local_schema_entries: u64,
+    /// Max. length of a state key, in bytes.
+    pub max_app_key_len: u64,
+    /// Max. length of a state byte slice value, in bytes.
+    pub max_app_bytes_value_len: u64,
+    /// Max. length of a state key and byte slice value together, in bytes.
+    pub max_app_sum_key_value_lens: u64,
+    /// Max. size of a box, in bytes.
+    pub max_box_size: u64,
+    /// Max. length of an asset name, in bytes.
+    pub max_asset_name_bytes: u64,
+    /// Max. length of an asset unit name, in bytes.
+    pub max_asset_unit_name_bytes: u64,
+    /// Max. length of an asset url, in bytes.
+    pub max_asset_url_bytes: u64,
+    /// Max. number of decimals of an asset.
+    pub max_asset_decimals: u32,
+    /// Min. balance of an account, also added for each asset the account holds.
+    pub min_balance: MicroAlgos,
+    /// Min. balance added for each app created by an account.
+    pub app_flat_params_min_balance: MicroAlgos,
+    /// Min. balance added for each app an account opted into.
+    pub app_flat_opt_in_min_balance: MicroAlgos,
+    /// Min. balance added for each state entry.
+    pub schema_min_balance_per_entry: MicroAlgos,
+    /// Min. balance added for each uint state entry, on top of the per entry balance.
+    pub schema_uint_min_balance: MicroAlgos,
+    /// Min. balance added for each byte slice state entry, on top of the per entry balance.
+    pub schema_bytes_min_balance: MicroAlgos,
+    /// Min. balance added to an app account for each box.
+    pub box_flat_min_balance: MicroAlgos,
+    /// Min. balance added to an app account for each byte of box names and contents.
+    pub box_byte_min_balance: MicroAlgos,
+}
+
+/// A protocol version and its parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConsensusVersion {
+    /// The version, as returned by algod in e.g. the suggested transaction params.
+    /// It's the url of the protocol's specification.
+    pub version: &'static str,
+    /// The versions this version can be upgraded to.
+    pub approved_upgrades: Vec<&'static str>,
+    pub params: ConsensusParams,
+}
+
+impl ConsensusParams {
+    /// Returns the parameters of the given protocol version.
+    pub fn for_version(version: &str) -> Result<ConsensusParams, CoreError> {
+        consensus_versions()
+            .into_iter()
+            .find(|v| v.version == version)
+            .map(|v| v.params)
+            .ok_or_else(|| CoreError::General(format!("Unknown consensus version: `{version}`")))
+    }
+}
+
+impl Default for ConsensusParams {
+    /// The parameters of the latest known protocol version.
+    fn default() -> Self {
+        ConsensusParams::for_version(CONSENSUS_CURRENT_VERSION)
+            .expect("current consensus version is known")
+    }
+}
+
+impl SuggestedTransactionParams {
+    /// Returns the parameters of the protocol version of these params.
+    pub fn consensus_params(&self) -> Result<ConsensusParams, CoreError> {
+        ConsensusParams::for_version(&self.consensus_version)
+    }
+}
+
+/// Returns the known protocol versions, from oldest to newest.
+///
+/// Older versions aren't included, as they aren't used by any network anymore.
+pub fn consensus_versions() -> Vec<ConsensusVersion> {
+    let v30 = ConsensusParams {
+        min_txn_fee: MicroAlgos(1000),
+        max_txn_life: 1000,
+        max_txn_note_bytes: 1024,
+        max_tx_group_size: 16,
+        max_inner_transactions: 16,
+        max_app_args: 16,
+        max_app_total_arg_len: 2048,
+        max_app_txn_accounts: 4,
+        max_app_txn_foreign_apps: 8,
+        max_app_txn_foreign_assets: 8,
+        max_app_total_txn_references: 8,
+        max_app_box_references: 0,
+        max_extra_app_program_pages: 3,
+        max_app_program_len: 2048,
+        max_global_schema_entries: 64,
+        max_local_schema_entries: 16,
+        max_app_key_len: 64,
+        max_app_bytes_value_len: 128,
+        max_app_sum_key_value_lens: 128,
+        max_box_size: 0,
+        max_asset_name_bytes: 32,
+        max_asset_unit_name_bytes: 8,
+        max_asset_url_bytes: 96,
+        max_asset_decimals: 19,
+        min_balance: MicroAlgos(100_000),
+        app_flat_params_min_balance: MicroAlgos(100_000),
+        app_flat_opt_in_min_balance: MicroAlgos(100_000),
+        schema_min_balance_per_entry: MicroAlgos(25_000),
+        schema_uint_min_balance: MicroAlgos(3_500),
+        schema_bytes_min_balance: MicroAlgos(25_000),
+        box_flat_min_balance: MicroAlgos(0),
+        box_byte_min_balance: MicroAlgos(0),
+    };
+
+    let v36 = ConsensusParams {
+        max_app_box_references: 8,
+        max_box_size: 32_768,
+        box_flat_min_balance: MicroAlgos(2_500),
+        box_byte_min_balance: MicroAlgos(400),
+        ..v30.clone()
+    };
+
+    let versions = [
+        (CONSENSUS_V30, &v30),
+        (CONSENSUS_V31, &v30),
+        (CONSENSUS_V32, &v30),
+        (CONSENSUS_V33, &v30),
+        (CONSENSUS_V34, &v30),
+        (CONSENSUS_V35, &v30),
+        (CONSENSUS_V36, &v36),
+        (CONSENSUS_V37, &v36),
+        (CONSENSUS_V38, &v36),
+        (CONSENSUS_V39, &v36),
+        (CONSENSUS_V40, &v36),
+    ];
+
+    versions
+        .iter()
+        .enumerate()
+        .map(|(i, (version, params))| ConsensusVersion {
+            version,
+            // each version can be upgraded to the next one
+            approved_upgrades: versions.get(i + 1).map(|v| v.0).into_iter().collect(),
+            params: (*params).clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_for_version() {
+        let v35 = ConsensusParams::for_version(CONSENSUS_V35).unwrap();
+        let v36 = ConsensusParams::for_version(CONSENSUS_V36).unwrap();
+
+        assert_eq!(0, v35.max_app_box_references);
+        assert_eq!(8, v36.max_app_box_references);
+        assert_eq!(
+            ConsensusParams::for_version(CONSENSUS_CURRENT_VERSION).unwrap(),
+            ConsensusParams::default()
+        );
+    }
+
+    #[test]
+    fn test_unknown_version_fails() {
+        assert!(ConsensusParams::for_version("future").is_err());
+    }
+
+    #[test]
+    fn test_versions_upgrade_to_next_version() {
+        let versions = consensus_versions();
+
+        for pair in versions.windows(2) {
+            assert_eq!(vec![pair[1].version], pair[0].approved_upgrades);
+        }
+        let current = versions.last().unwrap();
+        assert_eq!(CONSENSUS_CURRENT_VERSION, current.version);
+        assert!(current.approved_upgrades.is_empty());
+    }
+}
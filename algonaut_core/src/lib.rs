@@ -1,6 +1,6 @@
 use algonaut_crypto::HashDigest;
 use algonaut_crypto::Signature;
-use algonaut_encoding::U8_32Visitor;
+use algonaut_encoding::{SignatureVisitor, U8_32Visitor};
 use data_encoding::BASE64;
 use derive_more::{Add, Display, Sub};
 use error::CoreError;
@@ -17,6 +17,7 @@ pub use address::MultisigAddress;
 pub use multisig::MultisigSubsig;
 
 mod address;
+pub mod consensus;
 pub mod error;
 mod multisig;
 
@@ -24,7 +25,19 @@ pub const MICRO_ALGO_CONVERSION_FACTOR: f64 = 1e6;
 
 /// MicroAlgos are the base unit of currency in Algorand
 #[derive(
-    Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize, Display, Add, Sub,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Ord,
+    PartialOrd,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Display,
+    Add,
+    Sub,
 )]
 pub struct MicroAlgos(pub u64);
 
@@ -178,6 +191,46 @@ impl VrfPk {
     }
 }
 
+/// State proof public key used in key registration transactions
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct StateProofPk(pub [u8; 64]);
+
+impl Serialize for StateProofPk {
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for StateProofPk {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(StateProofPk(
+            deserializer.deserialize_bytes(SignatureVisitor)?,
+        ))
+    }
+}
+
+impl Debug for StateProofPk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base64_str())
+    }
+}
+
+impl StateProofPk {
+    pub fn from_base64_str(base64_str: &str) -> Result<StateProofPk, CoreError> {
+        Ok(StateProofPk(base64_str_to_u8_array(base64_str)?))
+    }
+
+    pub fn to_base64_str(self) -> String {
+        BASE64.encode(&self.0)
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct CompiledTeal(pub Vec<u8>);
 
@@ -239,6 +292,7 @@ pub enum TransactionTypeEnum {
     AssetFreeze,
     ApplicationCall,
     StateProof,
+    Heartbeat,
 }
 
 impl TransactionTypeEnum {
@@ -251,6 +305,7 @@ impl TransactionTypeEnum {
             TransactionTypeEnum::AssetFreeze => "afrz",
             TransactionTypeEnum::ApplicationCall => "appl",
             TransactionTypeEnum::StateProof => "stpf",
+            TransactionTypeEnum::Heartbeat => "hb",
         }
     }
 
@@ -263,6 +318,7 @@ impl TransactionTypeEnum {
             "afrz" => Ok(TransactionTypeEnum::AssetFreeze),
             "appl" => Ok(TransactionTypeEnum::ApplicationCall),
             "stpf" => Ok(TransactionTypeEnum::StateProof),
+            "hb" => Ok(TransactionTypeEnum::Heartbeat),
             _ => Err(CoreError::General(format!(
                 "Couldn't convert tx type str: `{s}` to tx type"
             ))),
@@ -13,4 +13,13 @@ pub enum CryptoError {
     InvalidWordsInMnemonic,
     #[display(fmt = "Invalid checksum.")]
     InvalidChecksum,
+    #[display(fmt = "Invalid proof: {}", _0)]
+    #[from(ignore)]
+    InvalidProof(String),
+    #[display(fmt = "Unsupported hash type: {}", _0)]
+    #[from(ignore)]
+    UnsupportedHashType(String),
+    #[display(fmt = "Encoding error: {}", _0)]
+    #[from(ignore)]
+    Encoding(String),
 }
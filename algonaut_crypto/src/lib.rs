@@ -12,6 +12,10 @@ pub mod mnemonic;
 ///
 pub mod error;
 
+pub mod merkle;
+
+pub mod light_block_header;
+
 #[derive(Copy, Clone, Eq, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HashType {
     Sha512_256,
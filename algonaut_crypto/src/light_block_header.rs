@@ -0,0 +1,233 @@
+//! Verification of light block headers against trusted state proof messages.
+//!
+//! A state proof message attests to the light block headers of the rounds
+//! `first_attested_round..=last_attested_round` with a SHA256 vector commitment,
+//! so a client trusting a message can verify any of these headers with a [merkle] proof.
+//!
+//! State proofs themselves aren't verified: checking the Falcon signatures of a state proof,
+//! the Sumhash512 commitments to its signers and participants and its weight threshold
+//! isn't implemented. A message is only as trustworthy as its source, e.g. a node operated
+//! by the client, so this isn't a trust-minimized light client.
+
+use crate::error::CryptoError;
+use crate::{merkle, HashDigest, HashType};
+use serde::Serialize;
+use std::convert::TryInto;
+
+/// Domain separation prefix of state proof messages.
+const MESSAGE_PREFIX: &[u8] = b"spm";
+
+/// Domain separation prefix of the light block headers' leaves.
+const LIGHT_BLOCK_HEADER_PREFIX: &[u8] = b"B256";
+
+/// The message a state proof attests to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StateProofMessage {
+    /// Natural log of the proven weight, with 16 bits of precision.
+    #[serde(rename = "P", skip_serializing_if = "is_zero")]
+    pub ln_proven_weight: u64,
+    /// Root of the vector commitment of the light block headers in the interval.
+    #[serde(
+        rename = "b",
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub block_headers_commitment: Vec<u8>,
+    #[serde(rename = "f", skip_serializing_if = "is_zero")]
+    pub first_attested_round: u64,
+    #[serde(rename = "l", skip_serializing_if = "is_zero")]
+    pub last_attested_round: u64,
+    /// Root of the vector commitment of the accounts that sign the next state proof.
+    #[serde(
+        rename = "v",
+        with = "serde_bytes",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub voters_commitment: Vec<u8>,
+}
+
+impl StateProofMessage {
+    /// The hash the state proof's participants sign.
+    pub fn hash(&self) -> Result<HashDigest, CryptoError> {
+        // fields are declared sorted by name, like algod's canonical encoding
+        let bytes =
+            rmp_serde::to_vec_named(self).map_err(|e| CryptoError::Encoding(e.to_string()))?;
+        merkle::hash(HashType::Sha256, &[MESSAGE_PREFIX, &bytes].concat())
+    }
+}
+
+/// The fields of a block header committed to by state proof messages.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LightBlockHeader {
+    #[serde(rename = "0", skip_serializing_if = "is_zero_hash")]
+    pub seed: HashDigest,
+    /// Hash of the block. Committed to only once the consensus protocol enables it:
+    /// leave it zero for earlier rounds.
+    #[serde(rename = "1", skip_serializing_if = "is_zero_hash")]
+    pub block_hash: HashDigest,
+    #[serde(rename = "gh", skip_serializing_if = "is_zero_hash")]
+    pub genesis_hash: HashDigest,
+    #[serde(rename = "r", skip_serializing_if = "is_zero")]
+    pub round: u64,
+    /// Root of the SHA256 vector commitment of the block's transactions.
+    #[serde(rename = "tc", skip_serializing_if = "is_zero_hash")]
+    pub txn_commitment_sha256: HashDigest,
+}
+
+impl LightBlockHeader {
+    /// The header's leaf in the block headers commitment.
+    pub fn leaf(&self) -> Result<HashDigest, CryptoError> {
+        let bytes =
+            rmp_serde::to_vec_named(self).map_err(|e| CryptoError::Encoding(e.to_string()))?;
+        merkle::hash(
+            HashType::Sha256,
+            &[LIGHT_BLOCK_HEADER_PREFIX, &bytes].concat(),
+        )
+    }
+}
+
+/// Verifies that `header` is attested to by `trusted_message`, with a proof as returned by algod's
+/// light block header proof endpoint.
+///
+/// `trusted_message` isn't verified, see the module docs.
+pub fn verify_light_block_header(
+    trusted_message: &StateProofMessage,
+    header: &LightBlockHeader,
+    index: u64,
+    depth: u64,
+    proof: &[u8],
+) -> Result<(), CryptoError> {
+    if header.round < trusted_message.first_attested_round
+        || header.round > trusted_message.last_attested_round
+    {
+        return Err(CryptoError::InvalidProof(format!(
+            "round: {} isn't attested to by the message, which covers rounds: {}..={}",
+            header.round, trusted_message.first_attested_round, trusted_message.last_attested_round
+        )));
+    }
+    if index != header.round - trusted_message.first_attested_round {
+        return Err(CryptoError::InvalidProof(format!(
+            "index: {index} doesn't match round: {}",
+            header.round
+        )));
+    }
+    let root = HashDigest(
+        trusted_message
+            .block_headers_commitment
+            .as_slice()
+            .try_into()
+            .map_err(|_| {
+                CryptoError::InvalidProof(format!(
+                    "invalid block headers commitment length: {}",
+                    trusted_message.block_headers_commitment.len()
+                ))
+            })?,
+    );
+    merkle::verify_vector_commitment(
+        HashType::Sha256,
+        &root,
+        &header.leaf()?,
+        index,
+        depth,
+        proof,
+    )
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+fn is_zero_hash(hash: &HashDigest) -> bool {
+    hash.0 == [0; 32]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(left: &HashDigest, right: &HashDigest) -> HashDigest {
+        merkle::hash(
+            HashType::Sha256,
+            &[b"MA".as_slice(), &left.0, &right.0].concat(),
+        )
+        .unwrap()
+    }
+
+    fn header(round: u64) -> LightBlockHeader {
+        LightBlockHeader {
+            seed: HashDigest([round as u8; 32]),
+            block_hash: HashDigest::default(),
+            genesis_hash: HashDigest([1; 32]),
+            round,
+            txn_commitment_sha256: HashDigest([2; 32]),
+        }
+    }
+
+    #[test]
+    fn test_encodes_light_block_header_canonically() {
+        let mut header = header(0);
+        header.seed = HashDigest::default();
+
+        let bytes = rmp_serde::to_vec_named(&header).unwrap();
+
+        // zero values are omitted, keys are sorted
+        let mut expected = vec![0x82, 0xa2, b'g', b'h', 0xc4, 32];
+        expected.extend([1; 32]);
+        expected.extend([0xa2, b't', b'c', 0xc4, 32]);
+        expected.extend([2; 32]);
+        assert_eq!(expected, bytes);
+    }
+
+    #[test]
+    fn test_verifies_light_block_headers() {
+        // an interval of 3 rounds, padded to 4 leaves. Leaf i is at position reverse_bits(i, 2).
+        let headers: Vec<LightBlockHeader> = (100..103).map(header).collect();
+        let leaves: Vec<HashDigest> = headers.iter().map(|h| h.leaf().unwrap()).collect();
+        let zero = HashDigest::default();
+        let left = node(&leaves[0], &leaves[2]);
+        let right = node(&leaves[1], &zero);
+        let message = StateProofMessage {
+            block_headers_commitment: node(&left, &right).0.to_vec(),
+            first_attested_round: 100,
+            last_attested_round: 102,
+            ..StateProofMessage::default()
+        };
+        let proofs = [
+            [leaves[2].0, right.0].concat(),
+            [zero.0, left.0].concat(),
+            [leaves[0].0, right.0].concat(),
+        ];
+
+        for (index, proof) in proofs.iter().enumerate() {
+            verify_light_block_header(&message, &headers[index], index as u64, 2, proof).unwrap();
+        }
+
+        let mut tampered = headers[0].clone();
+        tampered.txn_commitment_sha256 = HashDigest([3; 32]);
+        assert!(verify_light_block_header(&message, &tampered, 0, 2, &proofs[0]).is_err());
+        // the index must match the round
+        assert!(verify_light_block_header(&message, &headers[1], 0, 2, &proofs[1]).is_err());
+        // rounds outside of the interval aren't attested to
+        let res = verify_light_block_header(&message, &header(103), 3, 2, &proofs[0]);
+        assert!(matches!(res, Err(CryptoError::InvalidProof(_))));
+    }
+
+    #[test]
+    fn test_message_hash_commits_to_all_fields() {
+        let message = StateProofMessage {
+            ln_proven_weight: 2_000_000,
+            block_headers_commitment: vec![1; 32],
+            first_attested_round: 257,
+            last_attested_round: 512,
+            voters_commitment: vec![2; 32],
+        };
+        let hash = message.hash().unwrap();
+
+        let mut other = message.clone();
+        other.voters_commitment = vec![3; 32];
+        assert_ne!(hash, other.hash().unwrap());
+        let mut other = message;
+        other.ln_proven_weight += 1;
+        assert_ne!(hash, other.hash().unwrap());
+    }
+}
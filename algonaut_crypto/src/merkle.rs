@@ -0,0 +1,141 @@
+//! Verification of Merkle vector commitments, as used for transactions in blocks and light block headers.
+
+use crate::error::CryptoError;
+use crate::{HashDigest, HashType};
+use sha2::{Digest, Sha256, Sha512_256};
+
+/// Domain separation prefix of internal tree nodes.
+const NODE_PREFIX: &[u8] = b"MA";
+
+/// Hashes `bytes` with the hash function.
+pub fn hash(hash_type: HashType, bytes: &[u8]) -> Result<HashDigest, CryptoError> {
+    match hash_type {
+        HashType::Sha512_256 => Ok(HashDigest(Sha512_256::digest(bytes).into())),
+        HashType::Sha256 => Ok(HashDigest(Sha256::digest(bytes).into())),
+        HashType::Sumhash => Err(CryptoError::UnsupportedHashType(format!("{hash_type:?}"))),
+    }
+}
+
+/// Computes the root of a vector commitment from a leaf and its proof.
+///
+/// `proof` is the concatenation of the `depth` sibling hashes, from the leaf's level up.
+/// Missing siblings are zeros. Vector commitments store the leaf at `index` at the position
+/// with the `depth` lowest bits of `index` reversed.
+pub fn vector_commitment_root(
+    hash_type: HashType,
+    leaf: &HashDigest,
+    index: u64,
+    depth: u64,
+    proof: &[u8],
+) -> Result<HashDigest, CryptoError> {
+    if depth > 64 {
+        return Err(CryptoError::InvalidProof(format!(
+            "tree depth: {depth} exceeds 64"
+        )));
+    }
+    if proof.len() as u64 != depth * 32 {
+        return Err(CryptoError::InvalidProof(format!(
+            "expected {} bytes for tree depth: {depth}, got: {}",
+            depth * 32,
+            proof.len()
+        )));
+    }
+    if depth < 64 && index >> depth != 0 {
+        return Err(CryptoError::InvalidProof(format!(
+            "index: {index} out of range for tree depth: {depth}"
+        )));
+    }
+
+    let mut position = reverse_bits(index, depth);
+    let mut node = *leaf;
+    for sibling in proof.chunks(32) {
+        let (left, right) = if position & 1 == 0 {
+            (&node.0[..], sibling)
+        } else {
+            (sibling, &node.0[..])
+        };
+        node = hash(hash_type, &[NODE_PREFIX, left, right].concat())?;
+        position >>= 1;
+    }
+    Ok(node)
+}
+
+/// Verifies that the leaf at `index` is committed to by `root`, see [vector_commitment_root].
+pub fn verify_vector_commitment(
+    hash_type: HashType,
+    root: &HashDigest,
+    leaf: &HashDigest,
+    index: u64,
+    depth: u64,
+    proof: &[u8],
+) -> Result<(), CryptoError> {
+    if vector_commitment_root(hash_type, leaf, index, depth, proof)? == *root {
+        Ok(())
+    } else {
+        Err(CryptoError::InvalidProof(
+            "computed root doesn't match the commitment".to_owned(),
+        ))
+    }
+}
+
+/// Reverses the `bits` lowest bits of `index`.
+fn reverse_bits(index: u64, bits: u64) -> u64 {
+    if bits == 0 {
+        0
+    } else {
+        index.reverse_bits() >> (64 - bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(hash_type: HashType, left: &HashDigest, right: &HashDigest) -> HashDigest {
+        hash(hash_type, &[NODE_PREFIX, &left.0, &right.0].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_verifies_every_leaf_of_a_tree() {
+        for hash_type in [HashType::Sha512_256, HashType::Sha256] {
+            // 3 leaves, padded to 4. Leaf i is at position reverse_bits(i, 2): 0, 2, 1, (3).
+            let leaves: Vec<HashDigest> =
+                (0..3u8).map(|i| hash(hash_type, &[i]).unwrap()).collect();
+            let zero = HashDigest::default();
+            let left = node(hash_type, &leaves[0], &leaves[2]);
+            let right = node(hash_type, &leaves[1], &zero);
+            let root = node(hash_type, &left, &right);
+
+            let proofs = [
+                [leaves[2].0, right.0].concat(),
+                [zero.0, left.0].concat(),
+                [leaves[0].0, right.0].concat(),
+            ];
+            for (index, proof) in proofs.iter().enumerate() {
+                verify_vector_commitment(hash_type, &root, &leaves[index], index as u64, 2, proof)
+                    .unwrap();
+            }
+
+            assert!(
+                verify_vector_commitment(hash_type, &root, &leaves[1], 0, 2, &proofs[0]).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_leaf_tree() {
+        let leaf = hash(HashType::Sha256, b"leaf").unwrap();
+        verify_vector_commitment(HashType::Sha256, &leaf, &leaf, 0, 0, &[]).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_malformed_proofs() {
+        let leaf = HashDigest::default();
+        let res = vector_commitment_root(HashType::Sha256, &leaf, 0, 2, &[0; 32]);
+        assert!(matches!(res, Err(CryptoError::InvalidProof(_))));
+        let res = vector_commitment_root(HashType::Sha256, &leaf, 4, 2, &[0; 64]);
+        assert!(matches!(res, Err(CryptoError::InvalidProof(_))));
+        let res = vector_commitment_root(HashType::Sumhash, &leaf, 0, 1, &[0; 32]);
+        assert!(matches!(res, Err(CryptoError::UnsupportedHashType(_))));
+    }
+}
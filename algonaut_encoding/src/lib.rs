@@ -132,3 +132,42 @@ where
     let s: Option<Vec<String>> = Deserialize::deserialize(deserializer)?;
     Ok(s.unwrap_or_default())
 }
+
+/// Deserializes bytes encoded as a msgpack binary, or as a base64 string like in algod's JSON.
+pub fn deserialize_bytes_or_base64<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(BytesOrBase64Visitor)
+}
+
+struct BytesOrBase64Visitor;
+
+impl<'de> Visitor<'de> for BytesOrBase64Visitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("bytes or a base64 string")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        BASE64.decode(v.as_bytes()).map_err(E::custom)
+    }
+}
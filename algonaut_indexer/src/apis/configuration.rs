@@ -17,6 +17,8 @@ pub struct Configuration {
     pub oauth_access_token: Option<String>,
     pub bearer_access_token: Option<String>,
     pub api_key: Option<ApiKey>,
+    /// Max. time to wait for each read of a response body, see [read_body](super::read_body).
+    pub read_timeout: Option<std::time::Duration>,
     // TODO: take an oauth2 token source, similar to the go one
 }
 
@@ -44,6 +46,7 @@ impl Default for Configuration {
             oauth_access_token: None,
             bearer_access_token: None,
             api_key: None,
+            read_timeout: None,
         }
     }
 }
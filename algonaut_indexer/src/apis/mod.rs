@@ -1,5 +1,10 @@
+#[cfg(not(target_arch = "wasm32"))]
+use futures::future::{self, Either};
 use std::error;
 use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct ResponseContent<T> {
@@ -57,6 +62,63 @@ impl<T> From<std::io::Error> for Error<T> {
     }
 }
 
+/// Reads the body of `response`, failing if a read of the body doesn't complete
+/// within the configuration's read timeout.
+///
+/// The read timeout isn't supported on wasm, where the browser's timeouts apply.
+pub(crate) async fn read_body<T>(
+    configuration: &configuration::Configuration,
+    mut response: reqwest::Response,
+) -> Result<Vec<u8>, Error<T>> {
+    let mut body = vec![];
+    while read_chunk(&mut response, &mut body, configuration.read_timeout).await? {}
+    Ok(body)
+}
+
+/// Like [read_body], decoding the body as UTF-8.
+pub(crate) async fn read_text<T>(
+    configuration: &configuration::Configuration,
+    response: reqwest::Response,
+) -> Result<String, Error<T>> {
+    let body = read_body(configuration, response).await?;
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Appends the next chunk of the body to `body`, returning if there was one.
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_chunk<T>(
+    response: &mut reqwest::Response,
+    body: &mut Vec<u8>,
+    read_timeout: Option<Duration>,
+) -> Result<bool, Error<T>> {
+    let chunk = match read_timeout {
+        None => response.chunk().await?,
+        Some(read_timeout) => {
+            let delay = futures_timer::Delay::new(read_timeout);
+            match future::select(Box::pin(response.chunk()), delay).await {
+                Either::Left((chunk, _)) => chunk?,
+                Either::Right(_) => {
+                    return Err(Error::Io(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "timeout reading the response",
+                    )))
+                }
+            }
+        }
+    };
+    Ok(chunk.map(|chunk| body.extend_from_slice(&chunk)).is_some())
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn read_chunk<T>(
+    response: &mut reqwest::Response,
+    body: &mut Vec<u8>,
+    _read_timeout: Option<Duration>,
+) -> Result<bool, Error<T>> {
+    let chunk = response.chunk().await?;
+    Ok(chunk.map(|chunk| body.extend_from_slice(&chunk)).is_some())
+}
+
 pub fn urlencode<T: AsRef<str>>(s: T) -> String {
     ::url::form_urlencoded::byte_serialize(s.as_ref().as_bytes()).collect()
 }
@@ -36,13 +36,17 @@ impl RequestError {
 }
 
 #[derive(Error, Debug, Clone)]
+#[non_exhaustive]
 pub enum RequestErrorDetails {
     /// Http call error with optional message (returned by remote API)
     #[error("Http error: {}, {}", status, message)]
     Http { status: u16, message: String },
-    /// Timeout
-    #[error("Timeout connecting to the server.")]
+    /// The connection, the whole request or a read of the response timed out
+    #[error("Timeout waiting for the server.")]
     Timeout,
+    /// Connecting to the server or sending the request failed, e.g. the connection was refused or reset
+    #[error("Connection error: {}", description)]
+    Connection { description: String },
     /// Client generated errors (while e.g. building request or decoding response)
     #[error("Client error: {}", description)]
     Client { description: String },
@@ -71,20 +75,40 @@ impl From<reqwest::Error> for ClientError {
                     message: "".to_owned(),
                 },
             )
-        } else if error.is_timeout() {
-            RequestError::new(url_str, RequestErrorDetails::Timeout)
         } else {
-            RequestError::new(
-                url_str,
-                RequestErrorDetails::Client {
-                    description: error.to_string(),
-                },
-            )
+            RequestError::new(url_str, RequestErrorDetails::from(&error))
         };
         ClientError::Request(request_error)
     }
 }
 
+impl From<&reqwest::Error> for RequestErrorDetails {
+    /// Details of a request that didn't get a response.
+    fn from(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            RequestErrorDetails::Timeout
+        } else if is_connection_error(error) {
+            RequestErrorDetails::Connection {
+                description: error.to_string(),
+            }
+        } else {
+            RequestErrorDetails::Client {
+                description: error.to_string(),
+            }
+        }
+    }
+}
+
+/// Returns if connecting to the server or sending the request failed.
+fn is_connection_error(error: &reqwest::Error) -> bool {
+    // connect errors aren't told apart on wasm, where failed fetches are request errors
+    #[cfg(not(target_arch = "wasm32"))]
+    let is_connect = error.is_connect();
+    #[cfg(target_arch = "wasm32")]
+    let is_connect = false;
+    is_connect || error.is_request()
+}
+
 impl From<InvalidHeaderName> for ClientError {
     fn from(error: InvalidHeaderName) -> Self {
         ClientError::BadHeader(error.to_string())
@@ -29,9 +29,18 @@ pub struct Client {
 
 impl Client {
     pub fn new(address: &str, headers: Headers) -> Result<Client, ClientError> {
+        Self::with_http_client(address, headers, reqwest::Client::new())
+    }
+
+    /// Creates a client sending the requests with `http_client`, e.g. configured with timeouts.
+    pub fn with_http_client(
+        address: &str,
+        headers: Headers,
+        http_client: reqwest::Client,
+    ) -> Result<Client, ClientError> {
         Ok(Client {
             address: Url::parse(address)?.as_ref().into(),
-            http_client: reqwest::Client::new(),
+            http_client,
             headers: to_header_map(headers)?,
         })
     }
@@ -358,10 +367,39 @@ impl Client {
         wallet_handle: &str,
         wallet_password: &str,
         transaction_bytes: Vec<u8>,
+    ) -> Result<SignTransactionResponse, ClientError> {
+        self.sign_transaction_with(wallet_handle, wallet_password, transaction_bytes, None)
+            .await
+    }
+
+    /// Signs the transaction with the key of `public_key`, e.g. the auth address of a rekeyed sender.
+    pub async fn sign_transaction_with_public_key(
+        &self,
+        wallet_handle: &str,
+        wallet_password: &str,
+        transaction_bytes: Vec<u8>,
+        public_key: Ed25519PublicKey,
+    ) -> Result<SignTransactionResponse, ClientError> {
+        self.sign_transaction_with(
+            wallet_handle,
+            wallet_password,
+            transaction_bytes,
+            Some(public_key),
+        )
+        .await
+    }
+
+    async fn sign_transaction_with(
+        &self,
+        wallet_handle: &str,
+        wallet_password: &str,
+        transaction_bytes: Vec<u8>,
+        public_key: Option<Ed25519PublicKey>,
     ) -> Result<SignTransactionResponse, ClientError> {
         let req = SignTransactionRequest {
             wallet_handle_token: wallet_handle.to_string(),
             transaction: transaction_bytes,
+            public_key,
             wallet_password: wallet_password.to_string(),
         };
         let response = self
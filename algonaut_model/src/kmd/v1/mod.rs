@@ -213,6 +213,9 @@ pub struct SignTransactionRequest {
     pub wallet_handle_token: String,
     #[serde(serialize_with = "serialize_bytes")]
     pub transaction: Vec<u8>,
+    /// The key to sign with, if it isn't the sender's, e.g. of a rekeyed account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<Ed25519PublicKey>,
     pub wallet_password: String,
 }
 
@@ -1,8 +1,11 @@
-use algonaut_core::{Address, MicroAlgos, MultisigSignature, Round, ToMsgPack, VotePk, VrfPk};
-use algonaut_crypto::{HashDigest, HashType, Signature};
-use algonaut_encoding::{deserialize_bytes64, serialize_bytes};
+use algonaut_core::{
+    Address, MicroAlgos, MultisigSignature, Round, StateProofPk, ToMsgPack, VotePk, VrfPk,
+};
+use algonaut_crypto::{Ed25519PublicKey, HashDigest, HashType, Signature};
+use algonaut_encoding::deserialize_bytes_or_base64;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 
 /// IMPORTANT:
 /// When serializing:
@@ -107,12 +110,17 @@ pub struct ApiTransaction {
     #[serde(rename = "gen", skip_serializing_if = "Option::is_none")]
     pub genesis_id: Option<String>,
 
-    #[serde(rename = "gh")]
+    // Inner transactions don't have a genesis hash.
+    // This is the only non optional field that is skipped if zero, to encode them canonically.
+    #[serde(rename = "gh", default, skip_serializing_if = "is_zero_hash")]
     pub genesis_hash: HashDigest,
 
     #[serde(rename = "grp", skip_serializing_if = "Option::is_none")]
     pub group: Option<HashDigest>,
 
+    #[serde(rename = "hb", skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<ApiHeartbeat>,
+
     #[serde(rename = "lv", skip_serializing_if = "Option::is_none")]
     pub last_valid: Option<Round>, // optional for serialization zero value (technically possible) omission
 
@@ -148,6 +156,9 @@ pub struct ApiTransaction {
     #[serde(rename = "spmsg", skip_serializing_if = "Option::is_none")]
     pub state_proof_message: Option<StateProofMessage>,
 
+    #[serde(rename = "sprfkey", skip_serializing_if = "Option::is_none")]
+    pub state_proof_pk: Option<StateProofPk>,
+
     #[serde(rename = "sptype", skip_serializing_if = "Option::is_none")]
     pub state_proof_type: Option<StateProofType>,
 
@@ -211,7 +222,12 @@ pub struct AppArgument(#[serde(with = "serde_bytes")] pub Vec<u8>);
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct ApiAssetParams {
-    #[serde(rename = "am", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        rename = "am",
+        with = "serde_bytes",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub meta_data_hash: Option<Vec<u8>>,
 
     #[serde(rename = "an", skip_serializing_if = "Option::is_none")]
@@ -226,7 +242,7 @@ pub struct ApiAssetParams {
     #[serde(rename = "dc", skip_serializing_if = "Option::is_none")]
     pub decimals: Option<u32>,
 
-    #[serde(rename = "df", skip_serializing)]
+    #[serde(rename = "df", skip_serializing_if = "Option::is_none")]
     pub default_frozen: Option<bool>,
 
     #[serde(rename = "f", skip_serializing_if = "Option::is_none")]
@@ -263,125 +279,400 @@ pub struct ApiBoxReference {
     pub name: Vec<u8>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// A state proof, encoded like go-algorand's `stateproof.StateProof`.
+///
+/// Like in go-algorand, zero values are omitted when encoding, and missing fields are decoded as
+/// zero values. Bytes are decoded from msgpack binaries, or from base64 strings in algod's JSON.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StateProof {
-    #[serde(rename = "c")]
-    pub sig_commit: HashDigest,
-
-    #[serde(rename = "w")]
-    pub signed_weight: u64,
+    #[serde(rename = "P", skip_serializing_if = "is_default")]
+    pub part_proofs: MerkleArrayProof,
 
-    #[serde(rename = "S")]
+    #[serde(rename = "S", skip_serializing_if = "is_default")]
     pub sig_proofs: MerkleArrayProof,
 
-    #[serde(rename = "P")]
-    pub part_proofs: MerkleArrayProof,
+    #[serde(rename = "c", skip_serializing_if = "GenericDigest::is_empty")]
+    pub sig_commit: GenericDigest,
 
-    #[serde(rename = "v")]
-    pub merkle_signature_salt_version: u8,
+    #[serde(rename = "pr", skip_serializing_if = "Vec::is_empty")]
+    pub positions_to_reveal: Vec<u64>,
 
     /// Reveals is a sparse map from the position being revealed
     /// to the corresponding elements from the sigs and participants
     /// arrays.
-    #[serde(rename = "r")]
-    pub reveals: HashMap<u64, Reveal>,
+    #[serde(rename = "r", skip_serializing_if = "BTreeMap::is_empty")]
+    pub reveals: BTreeMap<u64, Reveal>,
 
-    #[serde(rename = "pr")]
-    pub positions_to_reveal: Vec<u64>,
+    #[serde(rename = "v", skip_serializing_if = "is_default")]
+    pub merkle_signature_salt_version: u8,
+
+    #[serde(rename = "w", skip_serializing_if = "is_default")]
+    pub signed_weight: u64,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SigSlotCommit {
-    /// Sig is a signature by the participant on the expected message.
-    #[serde(rename = "s")]
-    pub sig: Signature,
-
     /// l is the total weight of signatures in lower-numbered slots.
     /// This is initialized once the builder has collected a sufficient
     /// number of signatures.
-    #[serde(rename = "l")]
+    #[serde(rename = "l", skip_serializing_if = "is_default")]
     pub l: u64,
+
+    /// Sig is a signature by the participant on the expected message.
+    #[serde(rename = "s", skip_serializing_if = "is_default")]
+    pub sig: MerkleSignature,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// A Falcon signature of a participant, with the proof that its key is in the participant's
+/// commitment.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MerkleSignature {
+    #[serde(rename = "idx", skip_serializing_if = "is_default")]
+    pub vector_commitment_index: u64,
+
+    #[serde(rename = "prf", skip_serializing_if = "is_default")]
+    pub proof: MerkleArrayProof,
+
+    #[serde(
+        rename = "sig",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_or_base64"
+    )]
+    pub signature: Vec<u8>,
+
+    #[serde(rename = "vkey", skip_serializing_if = "is_default")]
+    pub verifying_key: FalconVerifier,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FalconVerifier {
+    /// The Falcon public key.
+    #[serde(
+        rename = "k",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_or_base64"
+    )]
+    pub public_key: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Verifier {
+    /// The commitment to the participant's Falcon keys, 64 bytes.
     #[serde(
         rename = "cmt",
-        serialize_with = "serialize_bytes",
-        deserialize_with = "deserialize_bytes64"
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_or_base64"
     )]
-    pub commitment: [u8; 64],
+    pub commitment: Vec<u8>,
 
-    #[serde(rename = "lf")]
+    #[serde(rename = "lf", skip_serializing_if = "is_default")]
     pub key_lifetime: u64,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Participant {
     /// PK is the identifier used to verify the signature for a specific participant
-    #[serde(rename = "p")]
+    #[serde(rename = "p", skip_serializing_if = "is_default")]
     pub pk: Verifier,
 
     /// Weight is AccountData.MicroAlgos.
-    #[serde(rename = "w")]
+    #[serde(rename = "w", skip_serializing_if = "is_default")]
     pub weight: u64,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Reveal {
-    #[serde(rename = "s")]
-    pub sig_slot: SigSlotCommit,
+    #[serde(rename = "p", skip_serializing_if = "is_default")]
     pub part: Participant,
+
+    #[serde(rename = "s", skip_serializing_if = "is_default")]
+    pub sig_slot: SigSlotCommit,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// A proof of elements of a merkle array. go-algorand's single leaf proofs have the same encoding.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MerkleArrayProof {
+    #[serde(rename = "hsh", skip_serializing_if = "is_default")]
+    pub hash_factory: HashFactory,
+
     /// Path is bounded by MaxNumLeavesOnEncodedTree since there could be multiple reveals, and
     /// given the distribution of the elt positions and the depth of the tree,
     /// the path length can increase up to 2^MaxEncodedTreeDepth / 2
-    #[serde(rename = "pth")]
-    pub path: Vec<HashDigest>,
-
-    #[serde(rename = "hsh")]
-    pub hash_factory: HashFactory,
+    #[serde(rename = "pth", skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<GenericDigest>,
 
     /// TreeDepth represents the depth of the tree that is being proven.
     /// It is the number of edges from the root to a leaf.
-    #[serde(rename = "td")]
+    #[serde(rename = "td", skip_serializing_if = "is_default")]
     pub tree_depth: u8,
 }
 
 #[derive(Clone, Eq, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HashFactory {
-    #[serde(rename = "t")]
+    #[serde(
+        rename = "t",
+        with = "hash_type",
+        skip_serializing_if = "is_sha512_256"
+    )]
     pub hash_type: HashType,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
-pub struct StateProofMessage {
-    /// BlockHeadersCommitment contains a commitment on all light block headers within a state proof interval.
-    #[serde(rename = "b")]
-    pub block_headers_commitment: Vec<u8>,
+impl Default for HashFactory {
+    fn default() -> Self {
+        HashFactory {
+            hash_type: HashType::Sha512_256,
+        }
+    }
+}
 
-    #[serde(rename = "v")]
-    pub voters_commitment: Vec<u8>,
+fn is_sha512_256(hash_type: &HashType) -> bool {
+    *hash_type == HashType::Sha512_256
+}
+
+/// Encodes hash types as go-algorand's integer ids.
+mod hash_type {
+    use algonaut_crypto::HashType;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        hash_type: &HashType,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(match hash_type {
+            HashType::Sha512_256 => 0,
+            HashType::Sumhash => 1,
+            HashType::Sha256 => 2,
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashType, D::Error> {
+        match u16::deserialize(deserializer)? {
+            0 => Ok(HashType::Sha512_256),
+            1 => Ok(HashType::Sumhash),
+            2 => Ok(HashType::Sha256),
+            t => Err(D::Error::custom(format!("unknown hash type: {}", t))),
+        }
+    }
+}
+
+/// A digest of any length, e.g. of a Sumhash512 merkle tree node.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GenericDigest(
+    #[serde(
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_or_base64"
+    )]
+    pub Vec<u8>,
+);
+
+impl GenericDigest {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
 
-    #[serde(rename = "P")]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateProofMessage {
+    #[serde(rename = "P", skip_serializing_if = "is_default")]
     pub ln_proven_weight: u64,
 
-    #[serde(rename = "f")]
+    /// BlockHeadersCommitment contains a commitment on all light block headers within a state proof interval.
+    #[serde(
+        rename = "b",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_or_base64"
+    )]
+    pub block_headers_commitment: Vec<u8>,
+
+    #[serde(rename = "f", skip_serializing_if = "is_default")]
     pub first_attested_round: u64,
 
-    #[serde(rename = "l")]
+    #[serde(rename = "l", skip_serializing_if = "is_default")]
     pub last_attested_round: u64,
+
+    #[serde(
+        rename = "v",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serde_bytes::serialize",
+        deserialize_with = "deserialize_bytes_or_base64"
+    )]
+    pub voters_commitment: Vec<u8>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// The type of a state proof, encoded as an integer.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "u64", into = "u64")]
 pub enum StateProofType {
     /// StateProofBasic is our initial state proof setup.
     /// using falcon keys and subset-sum hash
+    #[default]
     StateProofBasic,
 }
 
+impl From<StateProofType> for u64 {
+    fn from(state_proof_type: StateProofType) -> Self {
+        match state_proof_type {
+            StateProofType::StateProofBasic => 0,
+        }
+    }
+}
+
+impl TryFrom<u64> for StateProofType {
+    type Error = String;
+
+    fn try_from(state_proof_type: u64) -> Result<Self, Self::Error> {
+        match state_proof_type {
+            0 => Ok(StateProofType::StateProofBasic),
+            t => Err(format!("unknown state proof type: {}", t)),
+        }
+    }
+}
+
+/// Fields of a heartbeat transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ApiHeartbeat {
+    /// The account this heartbeat is for.
+    #[serde(
+        rename = "a",
+        default = "zero_address",
+        skip_serializing_if = "is_zero_address"
+    )]
+    pub address: Address,
+
+    #[serde(rename = "kd", skip_serializing_if = "Option::is_none")]
+    pub key_dilution: Option<u64>,
+
+    #[serde(
+        rename = "prf",
+        default = "HeartbeatProof::zero",
+        skip_serializing_if = "HeartbeatProof::is_zero"
+    )]
+    pub proof: HeartbeatProof,
+
+    /// The block seed for the last valid round of the transaction.
+    #[serde(rename = "sd", default, skip_serializing_if = "is_zero_hash")]
+    pub seed: HashDigest,
+
+    /// The vote id of the account.
+    #[serde(
+        rename = "vid",
+        default = "zero_vote_pk",
+        skip_serializing_if = "is_zero_vote_pk"
+    )]
+    pub vote_id: VotePk,
+}
+
+/// Proof that an account's participation key is live: a signature of the block seed
+/// with the account's one-time signature keys.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HeartbeatProof {
+    #[serde(
+        rename = "p",
+        default = "zero_public_key",
+        skip_serializing_if = "is_zero_public_key"
+    )]
+    pub pk: Ed25519PublicKey,
+
+    #[serde(
+        rename = "p1s",
+        default = "zero_signature",
+        skip_serializing_if = "is_zero_signature"
+    )]
+    pub pk1_sig: Signature,
+
+    #[serde(
+        rename = "p2",
+        default = "zero_public_key",
+        skip_serializing_if = "is_zero_public_key"
+    )]
+    pub pk2: Ed25519PublicKey,
+
+    #[serde(
+        rename = "p2s",
+        default = "zero_signature",
+        skip_serializing_if = "is_zero_signature"
+    )]
+    pub pk2_sig: Signature,
+
+    #[serde(
+        rename = "s",
+        default = "zero_signature",
+        skip_serializing_if = "is_zero_signature"
+    )]
+    pub sig: Signature,
+}
+
+impl HeartbeatProof {
+    fn zero() -> Self {
+        HeartbeatProof {
+            pk: zero_public_key(),
+            pk1_sig: zero_signature(),
+            pk2: zero_public_key(),
+            pk2_sig: zero_signature(),
+            sig: zero_signature(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == HeartbeatProof::zero()
+    }
+}
+
+fn is_zero_hash(hash: &HashDigest) -> bool {
+    *hash == HashDigest::default()
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+// Zero values of the heartbeat's fields, which go-algorand omits like other zero values.
+
+fn zero_address() -> Address {
+    Address([0; 32])
+}
+
+fn is_zero_address(address: &Address) -> bool {
+    *address == zero_address()
+}
+
+fn zero_vote_pk() -> VotePk {
+    VotePk([0; 32])
+}
+
+fn is_zero_vote_pk(vote_pk: &VotePk) -> bool {
+    *vote_pk == zero_vote_pk()
+}
+
+fn zero_public_key() -> Ed25519PublicKey {
+    Ed25519PublicKey([0; 32])
+}
+
+fn is_zero_public_key(public_key: &Ed25519PublicKey) -> bool {
+    *public_key == zero_public_key()
+}
+
+fn zero_signature() -> Signature {
+    Signature([0; 64])
+}
+
+fn is_zero_signature(signature: &Signature) -> bool {
+    *signature == zero_signature()
+}
+
 impl ToMsgPack for ApiTransaction {}
 impl ToMsgPack for ApiSignedTransaction {}
@@ -5,7 +5,7 @@ use crate::{
     transaction::{
         to_tx_type_enum, ApplicationCallOnComplete, ApplicationCallTransaction,
         AssetAcceptTransaction, AssetClawbackTransaction, AssetConfigurationTransaction,
-        AssetFreezeTransaction, AssetParams, AssetTransferTransaction, BoxReference,
+        AssetFreezeTransaction, AssetParams, AssetTransferTransaction, BoxReference, Heartbeat,
         KeyRegistration, Payment, SignedLogic, StateProofTransaction, StateSchema,
         TransactionSignature,
     },
@@ -14,8 +14,9 @@ use crate::{
 };
 use algonaut_core::{CompiledTeal, LogicSignature, MicroAlgos, Round, ToMsgPack};
 use algonaut_model::transaction::{
-    ApiAssetParams, ApiBoxReference, ApiSignedLogic, ApiSignedLogicArg, ApiSignedTransaction,
-    ApiStateSchema, ApiTransaction, AppArgument,
+    ApiAssetParams, ApiBoxReference, ApiHeartbeat, ApiSignedLogic, ApiSignedLogicArg,
+    ApiSignedTransaction, ApiStateSchema, ApiTransaction, AppArgument, StateProof,
+    StateProofMessage, StateProofType,
 };
 use num_traits::Num;
 use serde::{Deserialize, Serialize};
@@ -61,6 +62,7 @@ impl TryFrom<Transaction> for ApiTransaction {
             asset_id: None,
             receiver: None,
             selection_pk: None,
+            state_proof_pk: None,
             vote_first: None,
             vote_key_dilution: None,
             vote_pk: None,
@@ -72,6 +74,7 @@ impl TryFrom<Transaction> for ApiTransaction {
             state_proof_type: None,
             state_proof: None,
             state_proof_message: None,
+            heartbeat: None,
         };
 
         match &t.txn_type {
@@ -83,6 +86,7 @@ impl TryFrom<Transaction> for ApiTransaction {
             TransactionType::KeyRegistration(reg) => {
                 api_t.vote_pk = reg.vote_pk;
                 api_t.selection_pk = reg.selection_pk;
+                api_t.state_proof_pk = reg.state_proof_pk;
                 api_t.vote_first = reg.vote_first;
                 api_t.vote_last = reg.vote_last;
                 api_t.vote_key_dilution = reg.vote_key_dilution.and_then(num_as_api_option);
@@ -147,7 +151,23 @@ impl TryFrom<Transaction> for ApiTransaction {
                 })?
                 .and_then(vec_as_api_option);
             }
-            TransactionType::StateProofTransaction(_) => todo!(),
+            TransactionType::StateProofTransaction(state_proof) => {
+                api_t.state_proof_type = Some(state_proof.state_proof_type.clone())
+                    .filter(|t| *t != StateProofType::default());
+                api_t.state_proof =
+                    Some(state_proof.state_proof.clone()).filter(|p| *p != StateProof::default());
+                api_t.state_proof_message = Some(state_proof.message.clone())
+                    .filter(|m| *m != StateProofMessage::default());
+            }
+            TransactionType::Heartbeat(heartbeat) => {
+                api_t.heartbeat = Some(ApiHeartbeat {
+                    address: heartbeat.address,
+                    key_dilution: num_as_api_option(heartbeat.key_dilution),
+                    proof: heartbeat.proof.clone(),
+                    seed: heartbeat.seed,
+                    vote_id: heartbeat.vote_id,
+                });
+            }
         }
         Ok(api_t)
     }
@@ -170,6 +190,7 @@ impl TryFrom<ApiTransaction> for Transaction {
                 sender: api_t.sender,
                 vote_pk: api_t.vote_pk,
                 selection_pk: api_t.selection_pk,
+                state_proof_pk: api_t.state_proof_pk,
                 vote_first: api_t.vote_first,
                 vote_last: api_t.vote_last,
                 vote_key_dilution: Some(num_from_api_option(api_t.vote_key_dilution)),
@@ -228,6 +249,19 @@ impl TryFrom<ApiTransaction> for Transaction {
                 })
             }
             "stpf" => parse_state_proof_transaction(&api_t)?,
+            "hb" => {
+                let heartbeat = api_t.heartbeat.clone().ok_or_else(|| {
+                    TransactionError::Deserialization("heartbeat fields missing".to_owned())
+                })?;
+                TransactionType::Heartbeat(Heartbeat {
+                    sender: api_t.sender,
+                    address: heartbeat.address,
+                    proof: heartbeat.proof,
+                    seed: heartbeat.seed,
+                    vote_id: heartbeat.vote_id,
+                    key_dilution: num_from_api_option(heartbeat.key_dilution),
+                })
+            }
             unsupported_type => {
                 return Err(TransactionError::Deserialization(format!(
                     "Not supported transaction type: {}",
@@ -272,27 +306,18 @@ fn parse_state_schema(
     }
 }
 
+/// Missing fields are zero values, e.g. `sptype` is omitted for basic state proofs.
 fn parse_state_proof_transaction(
     api_t: &ApiTransaction,
 ) -> Result<TransactionType, TransactionError> {
-    match (
-        &api_t.state_proof_type,
-        &api_t.state_proof,
-        &api_t.state_proof_message,
-    ) {
-        (Some(state_proof_type), Some(state_proof), Some(state_proof_message)) => Ok(
-            TransactionType::StateProofTransaction(StateProofTransaction {
-                sender: api_t.sender,
-                state_proof_type: state_proof_type.clone(),
-                state_proof: state_proof.clone(),
-                message: state_proof_message.clone(),
-            }),
-        ),
-        _ => Err(TransactionError::Deserialization(format!(
-            "Invalid api state proof  transaction: {:?}",
-            api_t
-        ))),
-    }
+    Ok(TransactionType::StateProofTransaction(
+        StateProofTransaction {
+            sender: api_t.sender,
+            state_proof_type: api_t.state_proof_type.clone().unwrap_or_default(),
+            state_proof: api_t.state_proof.clone().unwrap_or_default(),
+            message: api_t.state_proof_message.clone().unwrap_or_default(),
+        },
+    ))
 }
 
 fn parse_asset_transfer_transaction(
@@ -314,12 +339,17 @@ fn parse_asset_transfer_transaction(
                 asset_close_to: api_t.asset_close_to,
             }),
         ),
-        (Some(xfer), None, Some(asset_receiver), None) if asset_receiver == api_t.sender => Ok(
-            TransactionType::AssetAcceptTransaction(AssetAcceptTransaction {
-                sender: api_t.sender,
-                xfer,
-            }),
-        ),
+        // Sending 0 to oneself without closing out is an opt-in.
+        (Some(xfer), None, Some(asset_receiver), None)
+            if asset_receiver == api_t.sender && api_t.asset_close_to.is_none() =>
+        {
+            Ok(TransactionType::AssetAcceptTransaction(
+                AssetAcceptTransaction {
+                    sender: api_t.sender,
+                    xfer,
+                },
+            ))
+        }
         (Some(xfer), None, Some(asset_receiver), asset_amount) => Ok(
             TransactionType::AssetTransferTransaction(AssetTransferTransaction {
                 sender: api_t.sender,
@@ -344,7 +374,7 @@ impl TryFrom<ApiSignedTransaction> for SignedTransaction {
             transaction: api_t.transaction.clone().try_into()?,
             transaction_id: api_t.transaction_id.clone(),
             sig: transaction_signature(&api_t)?,
-            auth_address: None,
+            auth_address: api_t.auth_address,
         })
     }
 }
@@ -356,6 +386,7 @@ fn transaction_signature(
         (Some(sig), None, None) => Ok(TransactionSignature::Single(*sig)),
         (None, Some(lsig), None) => Ok(TransactionSignature::Logic(lsig.clone().try_into()?)),
         (None, None, Some(msig)) => Ok(TransactionSignature::Multi(msig.clone())),
+        (None, None, None) => Ok(TransactionSignature::Unsigned),
         _ => Err(TransactionError::Deserialization(format!(
             "Invalid sig combination: {:?}",
             api_t
@@ -406,6 +437,7 @@ impl TryFrom<SignedTransaction> for ApiSignedTransaction {
             TransactionSignature::Single(sig) => (Some(sig), None, None),
             TransactionSignature::Multi(msig) => (None, Some(msig), None),
             TransactionSignature::Logic(lsig) => (None, None, Some(lsig)),
+            TransactionSignature::Unsigned => (None, None, None),
         };
         Ok(ApiSignedTransaction {
             sig,
@@ -861,4 +893,342 @@ mod tests {
             .is_err()
         );
     }
-}
\ No newline at end of file
+
+    /// The keys of a map, in their encoded order.
+    struct MapKeys(Vec<String>);
+
+    impl<'de> Deserialize<'de> for MapKeys {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct KeysVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for KeysVisitor {
+                type Value = MapKeys;
+
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                fn visit_map<A: serde::de::MapAccess<'de>>(
+                    self,
+                    mut map: A,
+                ) -> Result<MapKeys, A::Error> {
+                    let mut keys = vec![];
+                    while let Some((key, _)) = map.next_entry::<String, serde::de::IgnoredAny>()? {
+                        keys.push(key);
+                    }
+                    Ok(MapKeys(keys))
+                }
+            }
+
+            deserializer.deserialize_map(KeysVisitor)
+        }
+    }
+
+    #[test]
+    fn test_state_proof_round_trip() {
+        use algonaut_core::Address;
+        use algonaut_crypto::{HashDigest, HashType};
+        use algonaut_model::transaction::{
+            GenericDigest, HashFactory, MerkleArrayProof, Participant, Reveal,
+        };
+
+        let state_proof = StateProof {
+            sig_commit: GenericDigest(vec![1; 64]),
+            signed_weight: 5,
+            // hashed with SHA512/256, the zero hash type
+            sig_proofs: MerkleArrayProof {
+                tree_depth: 1,
+                ..MerkleArrayProof::default()
+            },
+            part_proofs: MerkleArrayProof {
+                path: vec![GenericDigest(vec![2; 64])],
+                hash_factory: HashFactory {
+                    hash_type: HashType::Sumhash,
+                },
+                tree_depth: 2,
+            },
+            reveals: vec![(
+                0,
+                Reveal {
+                    part: Participant {
+                        weight: 1,
+                        ..Participant::default()
+                    },
+                    ..Reveal::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            positions_to_reveal: vec![0],
+            ..StateProof::default()
+        };
+        let transaction = Transaction {
+            fee: MicroAlgos(0),
+            first_valid: Round(100),
+            genesis_hash: HashDigest([1; 32]),
+            last_valid: Round(1100),
+            txn_type: TransactionType::StateProofTransaction(StateProofTransaction {
+                sender: Address([2; 32]),
+                state_proof_type: StateProofType::StateProofBasic,
+                state_proof,
+                message: StateProofMessage {
+                    first_attested_round: 257,
+                    ..StateProofMessage::default()
+                },
+            }),
+            genesis_id: None,
+            group: None,
+            lease: None,
+            note: None,
+            rekey_to: None,
+        };
+
+        let encoded = transaction.to_msg_pack().unwrap();
+
+        // like in go-algorand's canonical msgpack, keys are sorted and zero values are omitted
+        #[derive(Deserialize)]
+        struct EncodedKeys {
+            sp: MapKeys,
+            spmsg: MapKeys,
+        }
+        #[derive(Deserialize)]
+        struct EncodedProofs {
+            sp: Proofs,
+        }
+        #[derive(Deserialize)]
+        struct Proofs {
+            #[serde(rename = "S")]
+            sig_proofs: MapKeys,
+            #[serde(rename = "P")]
+            part_proofs: MapKeys,
+        }
+        let fields: MapKeys = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(
+            vec!["fv", "gh", "lv", "snd", "sp", "spmsg", "type"],
+            fields.0
+        );
+        let keys: EncodedKeys = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(vec!["P", "S", "c", "pr", "r", "w"], keys.sp.0);
+        assert_eq!(vec!["f"], keys.spmsg.0);
+        let proofs: EncodedProofs = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(vec!["td"], proofs.sp.sig_proofs.0);
+        assert_eq!(vec!["hsh", "pth", "td"], proofs.sp.part_proofs.0);
+
+        let api_transaction: ApiTransaction = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(transaction, api_transaction.try_into().unwrap());
+    }
+
+    #[test]
+    fn test_heartbeat_round_trip() {
+        use crate::builder::SendHeartbeat;
+        use algonaut_core::{Address, VotePk};
+        use algonaut_crypto::{Ed25519PublicKey, HashDigest, Signature};
+        use algonaut_model::transaction::HeartbeatProof;
+
+        // The heartbeat as go-algorand encodes it, spelled out from the `codec` tags of
+        // `transactions.HeartbeatTxnFields` and `crypto.HeartbeatProof`: canonical msgpack, with
+        // sorted keys, zero values omitted and byte arrays as bin.
+        fn key(name: &str) -> Vec<u8> {
+            [vec![0xa0 | name.len() as u8], name.as_bytes().to_vec()].concat()
+        }
+        fn bin(byte: u8, len: usize) -> Vec<u8> {
+            [vec![0xc4, len as u8], vec![byte; len]].concat()
+        }
+        let expected = [
+            vec![0x86],
+            key("fv"),
+            vec![100],
+            key("gh"),
+            bin(1, 32),
+            key("hb"),
+            vec![0x85],
+            key("a"), // HbAddress
+            bin(3, 32),
+            key("kd"), // HbKeyDilution
+            vec![100],
+            key("prf"), // HbProof
+            vec![0x85],
+            key("p"), // PK
+            bin(4, 32),
+            key("p1s"), // PK1Sig
+            bin(5, 64),
+            key("p2"), // PK2
+            bin(6, 32),
+            key("p2s"), // PK2Sig
+            bin(7, 64),
+            key("s"), // Sig
+            bin(8, 64),
+            key("sd"), // HbSeed
+            bin(9, 32),
+            key("vid"), // HbVoteID
+            bin(10, 32),
+            key("lv"),
+            vec![110],
+            key("snd"),
+            bin(2, 32),
+            key("type"),
+            key("hb"),
+        ]
+        .concat();
+
+        let tx = Transaction {
+            fee: MicroAlgos(0),
+            first_valid: Round(100),
+            genesis_hash: HashDigest([1; 32]),
+            last_valid: Round(110),
+            txn_type: SendHeartbeat::new(
+                Address([2; 32]),
+                Address([3; 32]),
+                HeartbeatProof {
+                    pk: Ed25519PublicKey([4; 32]),
+                    pk1_sig: Signature([5; 64]),
+                    pk2: Ed25519PublicKey([6; 32]),
+                    pk2_sig: Signature([7; 64]),
+                    sig: Signature([8; 64]),
+                },
+                HashDigest([9; 32]),
+                VotePk([10; 32]),
+                100,
+            )
+            .build(),
+            genesis_id: None,
+            group: None,
+            lease: None,
+            note: None,
+            rekey_to: None,
+        };
+
+        let encoded = ApiTransaction::try_from(tx.clone())
+            .unwrap()
+            .to_msg_pack()
+            .unwrap();
+        assert_eq!(expected, encoded);
+
+        let decoded: ApiTransaction = rmp_serde::from_slice(&expected).unwrap();
+        assert_eq!(tx, Transaction::try_from(decoded).unwrap());
+    }
+
+    #[test]
+    fn test_heartbeat_omits_zero_values() {
+        use crate::builder::SendHeartbeat;
+        use algonaut_core::{Address, VotePk};
+        use algonaut_crypto::{Ed25519PublicKey, HashDigest, Signature};
+        use algonaut_model::transaction::HeartbeatProof;
+
+        let tx = Transaction {
+            fee: MicroAlgos(0),
+            first_valid: Round(100),
+            genesis_hash: HashDigest([1; 32]),
+            last_valid: Round(110),
+            txn_type: SendHeartbeat::new(
+                Address([2; 32]),
+                Address([0; 32]),
+                HeartbeatProof {
+                    pk: Ed25519PublicKey([0; 32]),
+                    pk1_sig: Signature([0; 64]),
+                    pk2: Ed25519PublicKey([0; 32]),
+                    pk2_sig: Signature([0; 64]),
+                    sig: Signature([8; 64]),
+                },
+                HashDigest([0; 32]),
+                VotePk([10; 32]),
+                0,
+            )
+            .build(),
+            genesis_id: None,
+            group: None,
+            lease: None,
+            note: None,
+            rekey_to: None,
+        };
+
+        let encoded = ApiTransaction::try_from(tx.clone())
+            .unwrap()
+            .to_msg_pack()
+            .unwrap();
+
+        // {"fv": 100, "gh": [1; 32], "hb": {"prf": {"s": [8; 64]}, "vid": [10; 32]},
+        //  "lv": 110, "snd": [2; 32], "type": "hb"}
+        let expected = [
+            b"\x86\xa2fv\x64\xa2gh\xc4\x20".as_slice(),
+            &[1; 32],
+            b"\xa2hb\x82\xa3prf\x81\xa1s\xc4\x40",
+            &[8; 64],
+            b"\xa3vid\xc4\x20",
+            &[10; 32],
+            b"\xa2lv\x6e\xa3snd\xc4\x20",
+            &[2; 32],
+            b"\xa4type\xa2hb",
+        ]
+        .concat();
+        assert_eq!(expected, encoded);
+        let decoded: ApiTransaction = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(tx, Transaction::try_from(decoded).unwrap());
+    }
+
+    #[test]
+    fn test_heartbeat_without_fields_fails() {
+        let api_t = ApiTransaction {
+            type_: "hb".to_owned(),
+            heartbeat: None,
+            ..ApiTransaction::try_from(
+                crate::TxnBuilder::new(
+                    MicroAlgos(0),
+                    Round(0),
+                    Round(0),
+                    algonaut_crypto::HashDigest([0; 32]),
+                    crate::Pay::new(
+                        algonaut_core::Address([0; 32]),
+                        algonaut_core::Address([0; 32]),
+                        MicroAlgos(1),
+                    )
+                    .build(),
+                )
+                .build()
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert!(Transaction::try_from(api_t).is_err());
+    }
+
+    fn msg_pack_round_trip(txn_type: TransactionType) {
+        let tx = crate::TxnBuilder::new(
+            MicroAlgos(1000),
+            Round(1),
+            Round(2),
+            algonaut_crypto::HashDigest([1; 32]),
+            txn_type,
+        )
+        .build()
+        .unwrap();
+
+        let encoded = ApiTransaction::try_from(tx.clone())
+            .unwrap()
+            .to_msg_pack()
+            .unwrap();
+        let decoded: ApiTransaction = rmp_serde::from_slice(&encoded).unwrap();
+
+        assert_eq!(tx, Transaction::try_from(decoded).unwrap());
+    }
+
+    #[test]
+    fn test_asset_opt_out_to_self_round_trip() {
+        let sender = algonaut_core::Address([1; 32]);
+        msg_pack_round_trip(
+            crate::TransferAsset::new(sender, 10, 0, sender)
+                .close_to(algonaut_core::Address([2; 32]))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_asset_params_round_trip() {
+        msg_pack_round_trip(
+            crate::CreateAsset::new(algonaut_core::Address([1; 32]), 100, 2, true)
+                .meta_data_hash(vec![3; 32])
+                .build(),
+        );
+    }
+}
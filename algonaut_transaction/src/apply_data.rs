@@ -0,0 +1,62 @@
+use crate::{SignedTransaction, Transaction};
+use algonaut_core::{Address, MicroAlgos};
+use std::collections::HashMap;
+
+/// A transaction of a block, with the effects of its evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedTransactionWithApplyData {
+    pub signed_transaction: SignedTransaction,
+    pub apply_data: ApplyData,
+}
+
+/// A transaction issued by an app call. Inner transactions aren't signed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerTransaction {
+    /// Inner transactions have no genesis id and hash.
+    pub transaction: Transaction,
+    /// See [Transaction::inner_id].
+    pub transaction_id: String,
+    pub apply_data: ApplyData,
+}
+
+/// The effects of evaluating a transaction, that can't be derived from the transaction itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApplyData {
+    /// Amount sent to the close remainder to account, if the transaction closed the sender's account.
+    pub closing_amount: MicroAlgos,
+    /// Amount of the asset sent to the asset close to account, if the transaction closed out the asset.
+    pub asset_closing_amount: u64,
+    pub sender_rewards: MicroAlgos,
+    pub receiver_rewards: MicroAlgos,
+    pub close_rewards: MicroAlgos,
+    /// Id of the asset created by the transaction.
+    pub config_asset: Option<u64>,
+    /// Id of the app created by the transaction.
+    pub application_id: Option<u64>,
+    /// Set only for app calls.
+    pub eval_delta: EvalDelta,
+}
+
+/// The changes made by an app call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalDelta {
+    pub global_delta: StateDelta,
+    /// Local state changes, keyed by account index: 0 is the sender,
+    /// followed by the app call's accounts and then [EvalDelta::shared_accounts].
+    pub local_deltas: HashMap<u64, StateDelta>,
+    /// Accounts whose local state was changed without being referenced by the app call.
+    pub shared_accounts: Vec<Address>,
+    pub logs: Vec<Vec<u8>>,
+    pub inner_txns: Vec<InnerTransaction>,
+}
+
+/// State changes, keyed by state key.
+pub type StateDelta = HashMap<Vec<u8>, ValueDelta>;
+
+/// The change of a state value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueDelta {
+    SetBytes(Vec<u8>),
+    SetUint(u64),
+    Delete,
+}
@@ -3,12 +3,13 @@ use crate::{
     transaction::{
         ApplicationCallOnComplete, ApplicationCallTransaction, AssetAcceptTransaction,
         AssetClawbackTransaction, AssetConfigurationTransaction, AssetFreezeTransaction,
-        AssetParams, AssetTransferTransaction, BoxReference, KeyRegistration, Payment, StateSchema,
-        Transaction, TransactionType,
+        AssetParams, AssetTransferTransaction, BoxReference, Heartbeat, KeyRegistration, Payment,
+        StateSchema, Transaction, TransactionType,
     },
 };
-use algonaut_core::{Address, CompiledTeal, MicroAlgos, Round, VotePk, VrfPk};
+use algonaut_core::{Address, CompiledTeal, MicroAlgos, Round, StateProofPk, VotePk, VrfPk};
 use algonaut_crypto::HashDigest;
+use algonaut_model::transaction::HeartbeatProof;
 
 pub trait TransactionParams {
     fn last_round(&self) -> u64;
@@ -161,6 +162,7 @@ pub struct RegisterKey {
     sender: Address,
     vote_pk: Option<VotePk>,
     selection_pk: Option<VrfPk>,
+    state_proof_pk: Option<StateProofPk>,
     vote_first: Option<Round>,
     vote_last: Option<Round>,
     vote_key_dilution: Option<u64>,
@@ -180,6 +182,7 @@ impl RegisterKey {
             sender,
             vote_pk: Some(vote_pk),
             selection_pk: Some(selection_pk),
+            state_proof_pk: None,
             vote_first: Some(vote_first),
             vote_last: Some(vote_last),
             vote_key_dilution: Some(vote_key_dilution),
@@ -192,6 +195,7 @@ impl RegisterKey {
             sender,
             vote_pk: None,
             selection_pk: None,
+            state_proof_pk: None,
             vote_first: None,
             vote_last: None,
             vote_key_dilution: None,
@@ -204,6 +208,7 @@ impl RegisterKey {
             sender,
             vote_pk: None,
             selection_pk: None,
+            state_proof_pk: None,
             vote_first: None,
             vote_last: None,
             vote_key_dilution: None,
@@ -211,11 +216,18 @@ impl RegisterKey {
         }
     }
 
+    /// Sets the state proof public key, required to go online since state proofs are enabled.
+    pub fn state_proof_pk(mut self, state_proof_pk: StateProofPk) -> Self {
+        self.state_proof_pk = Some(state_proof_pk);
+        self
+    }
+
     pub fn build(self) -> TransactionType {
         TransactionType::KeyRegistration(KeyRegistration {
             sender: self.sender,
             vote_pk: self.vote_pk,
             selection_pk: self.selection_pk,
+            state_proof_pk: self.state_proof_pk,
             vote_first: self.vote_first,
             vote_last: self.vote_last,
             vote_key_dilution: self.vote_key_dilution,
@@ -224,6 +236,47 @@ impl RegisterKey {
     }
 }
 
+/// A builder for [Heartbeat].
+pub struct SendHeartbeat {
+    sender: Address,
+    address: Address,
+    proof: HeartbeatProof,
+    seed: HashDigest,
+    vote_id: VotePk,
+    key_dilution: u64,
+}
+
+impl SendHeartbeat {
+    pub fn new(
+        sender: Address,
+        address: Address,
+        proof: HeartbeatProof,
+        seed: HashDigest,
+        vote_id: VotePk,
+        key_dilution: u64,
+    ) -> Self {
+        SendHeartbeat {
+            sender,
+            address,
+            proof,
+            seed,
+            vote_id,
+            key_dilution,
+        }
+    }
+
+    pub fn build(self) -> TransactionType {
+        TransactionType::Heartbeat(Heartbeat {
+            sender: self.sender,
+            address: self.address,
+            proof: self.proof,
+            seed: self.seed,
+            vote_id: self.vote_id,
+            key_dilution: self.key_dilution,
+        })
+    }
+}
+
 /// A builder for [AssetConfigurationTransaction].
 pub struct CreateAsset {
     sender: Address,
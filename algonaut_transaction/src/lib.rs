@@ -1,5 +1,6 @@
 pub mod account;
 mod api_model;
+pub mod apply_data;
 pub mod auction;
 pub mod builder;
 pub mod contract_account;
@@ -7,9 +8,10 @@ pub mod error;
 pub mod transaction;
 pub mod tx_group;
 pub mod url;
+pub mod validation;
 
 pub use builder::{
     AcceptAsset, ClawbackAsset, CreateApplication, CreateAsset, FreezeAsset, Pay, RegisterKey,
-    TransferAsset, TxnBuilder,
+    SendHeartbeat, TransferAsset, TxnBuilder,
 };
 pub use transaction::{SignedTransaction, Transaction, TransactionType};
@@ -5,10 +5,11 @@ use algonaut_core::SuggestedTransactionParams;
 use algonaut_core::ToMsgPack;
 use algonaut_core::TransactionTypeEnum;
 use algonaut_core::{Address, MultisigSignature};
-use algonaut_core::{MicroAlgos, Round, VotePk, VrfPk};
+use algonaut_core::{MicroAlgos, Round, StateProofPk, VotePk, VrfPk};
 use algonaut_crypto::HashDigest;
 use algonaut_crypto::Signature;
 use algonaut_model::transaction::ApiSignedLogic;
+use algonaut_model::transaction::HeartbeatProof;
 use algonaut_model::transaction::StateProof;
 use algonaut_model::transaction::StateProofMessage;
 use algonaut_model::transaction::StateProofType;
@@ -32,6 +33,7 @@ pub enum TransactionType {
     AssetFreezeTransaction(AssetFreezeTransaction),
     ApplicationCallTransaction(ApplicationCallTransaction),
     StateProofTransaction(StateProofTransaction),
+    Heartbeat(Heartbeat),
 }
 
 /// A transaction that can appear in a block
@@ -103,6 +105,28 @@ impl Transaction {
         Ok(BASE32_NOPAD.encode(&self.raw_id()?.0))
     }
 
+    /// The id of an inner transaction, issued by the transaction with id `parent_id`,
+    /// at position `index` of the parent's inner transactions.
+    ///
+    /// Inner transactions can be identical, so, like go-algorand's `InnerID`,
+    /// the parent's id and the index are hashed together with the transaction.
+    pub fn inner_raw_id(
+        &self,
+        parent_id: &HashDigest,
+        index: u64,
+    ) -> Result<HashDigest, TransactionError> {
+        let mut bytes = b"TX".to_vec();
+        bytes.extend_from_slice(&parent_id.0);
+        bytes.extend_from_slice(&index.to_be_bytes());
+        bytes.extend_from_slice(&self.to_owned().to_msg_pack()?);
+        Ok(HashDigest(sha2::Sha512_256::digest(bytes).into()))
+    }
+
+    /// See [Transaction::inner_raw_id].
+    pub fn inner_id(&self, parent_id: &HashDigest, index: u64) -> Result<String, TransactionError> {
+        Ok(BASE32_NOPAD.encode(&self.inner_raw_id(parent_id, index)?.0))
+    }
+
     pub fn assign_group_id(&mut self, group_id: HashDigest) {
         self.group = Some(group_id)
     }
@@ -143,6 +167,7 @@ impl Transaction {
             TransactionType::AssetFreezeTransaction(t) => t.sender,
             TransactionType::ApplicationCallTransaction(t) => t.sender,
             TransactionType::StateProofTransaction(t) => t.sender,
+            TransactionType::Heartbeat(t) => t.sender,
         }
     }
 }
@@ -177,6 +202,9 @@ pub struct KeyRegistration {
     /// The VRF public key.
     pub selection_pk: Option<VrfPk>,
 
+    /// The state proof public key.
+    pub state_proof_pk: Option<StateProofPk>,
+
     /// The first round that the participation key is valid. Not to be confused with the FirstValid
     /// round of the keyreg transaction.
     pub vote_first: Option<Round>,
@@ -395,6 +423,29 @@ pub struct StateProofTransaction {
     pub message: StateProofMessage,
 }
 
+/// Heartbeat transactions are issued on behalf of online accounts, proving that their
+/// participation keys are live, so they keep being eligible for staking incentives.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Heartbeat {
+    /// The address of the account that signs and pays the fee.
+    pub sender: Address,
+
+    /// The account this heartbeat is for.
+    pub address: Address,
+
+    /// Signature of the seed with the account's participation keys.
+    pub proof: HeartbeatProof,
+
+    /// The block seed for the last valid round of the transaction.
+    pub seed: HashDigest,
+
+    /// The vote id of the account, must match the account's registered vote key.
+    pub vote_id: VotePk,
+
+    /// The key dilution of the account, must match the account's registered key dilution.
+    pub key_dilution: u64,
+}
+
 /// An application transaction must indicate the action to be taken following the execution of its approvalProgram or clearStateProgram. The variants below describe the available actions.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ApplicationCallOnComplete {
@@ -441,6 +492,8 @@ pub enum TransactionSignature {
     Single(Signature),
     Multi(MultisigSignature),
     Logic(SignedLogic),
+    /// No signature, like state proof transactions, which are authorized by their state proof.
+    Unsigned,
 }
 
 pub fn to_tx_type_enum(type_: &TransactionType) -> TransactionTypeEnum {
@@ -456,6 +509,7 @@ pub fn to_tx_type_enum(type_: &TransactionType) -> TransactionTypeEnum {
         TransactionType::AssetFreezeTransaction(_) => TransactionTypeEnum::AssetFreeze,
         TransactionType::ApplicationCallTransaction(_) => TransactionTypeEnum::ApplicationCall,
         TransactionType::StateProofTransaction(_) => TransactionTypeEnum::StateProof,
+        TransactionType::Heartbeat(_) => TransactionTypeEnum::Heartbeat,
     }
 }
 
@@ -0,0 +1,523 @@
+use crate::{
+    transaction::{ApplicationCallTransaction, AssetParams},
+    tx_group::TxGroup,
+    Transaction, TransactionType,
+};
+use algonaut_core::{consensus::ConsensusParams, MicroAlgos, Round};
+use thiserror::Error;
+
+/// A consensus rule broken by a transaction or group.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Violation {
+    #[error("Fee: {fee} is below the min fee: {min_fee}.")]
+    FeeBelowMin {
+        fee: MicroAlgos,
+        min_fee: MicroAlgos,
+    },
+    #[error("Last valid round: {last_valid} is before the first valid round: {first_valid}.")]
+    LastValidBeforeFirstValid {
+        first_valid: Round,
+        last_valid: Round,
+    },
+    #[error("Validity window of {len} rounds exceeds the max of {max}.")]
+    ValidityWindowTooLong { len: u64, max: u64 },
+    #[error("Note of {len} bytes exceeds the max of {max}.")]
+    NoteTooLong { len: u64, max: u64 },
+    #[error("{count} app arguments exceed the max of {max}.")]
+    TooManyAppArgs { count: u64, max: u64 },
+    #[error("App arguments of {len} bytes exceed the max of {max}.")]
+    AppArgsTooLong { len: u64, max: u64 },
+    #[error("{count} accounts exceed the max of {max}.")]
+    TooManyAccounts { count: u64, max: u64 },
+    #[error("{count} foreign apps exceed the max of {max}.")]
+    TooManyForeignApps { count: u64, max: u64 },
+    #[error("{count} foreign assets exceed the max of {max}.")]
+    TooManyForeignAssets { count: u64, max: u64 },
+    #[error("{count} box references exceed the max of {max}.")]
+    TooManyBoxReferences { count: u64, max: u64 },
+    #[error("{count} references (accounts, foreign apps, foreign assets and boxes) exceed the max of {max}.")]
+    TooManyReferences { count: u64, max: u64 },
+    #[error("Box reference to app: {app_id}, which isn't the called app or a foreign app.")]
+    BoxAppNotReferenced { app_id: u64 },
+    #[error("{count} extra program pages exceed the max of {max}.")]
+    TooManyExtraPages { count: u64, max: u64 },
+    #[error("Programs of {len} bytes exceed the max of {max}.")]
+    ProgramsTooLong { len: u64, max: u64 },
+    #[error("Asset name of {len} bytes exceeds the max of {max}.")]
+    AssetNameTooLong { len: u64, max: u64 },
+    #[error("Asset unit name of {len} bytes exceeds the max of {max}.")]
+    AssetUnitNameTooLong { len: u64, max: u64 },
+    #[error("Asset url of {len} bytes exceeds the max of {max}.")]
+    AssetUrlTooLong { len: u64, max: u64 },
+    #[error("{decimals} asset decimals exceed the max of {max}.")]
+    TooManyAssetDecimals { decimals: u32, max: u32 },
+    #[error("Empty transaction group.")]
+    EmptyGroup,
+    #[error("Group of {size} transactions exceeds the max of {max}.")]
+    GroupTooLarge { size: u64, max: u64 },
+    #[error("Group fee: {fee} is below the min fee: {min_fee}.")]
+    GroupFeeBelowMin {
+        fee: MicroAlgos,
+        min_fee: MicroAlgos,
+    },
+    #[error("Transaction has no group id.")]
+    MissingGroupId,
+    #[error("Transaction's group id doesn't match the group.")]
+    GroupIdMismatch,
+}
+
+/// A violation found when validating a transaction group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupViolation {
+    /// The index of the violating transaction, or None if the violation concerns the whole group.
+    pub tx_index: Option<usize>,
+    pub violation: Violation,
+}
+
+impl Transaction {
+    /// Checks the transaction against the consensus limits, without contacting a node.
+    ///
+    /// Returns all the violations found.
+    pub fn validate(&self, params: &ConsensusParams) -> Result<(), Vec<Violation>> {
+        to_result(self.violations(params, true))
+    }
+
+    fn violations(&self, params: &ConsensusParams, check_fee: bool) -> Vec<Violation> {
+        let mut violations = vec![];
+
+        if check_fee && self.fee < params.min_txn_fee {
+            violations.push(Violation::FeeBelowMin {
+                fee: self.fee,
+                min_fee: params.min_txn_fee,
+            });
+        }
+        if self.last_valid.0 < self.first_valid.0 {
+            violations.push(Violation::LastValidBeforeFirstValid {
+                first_valid: self.first_valid,
+                last_valid: self.last_valid,
+            });
+        } else if self.last_valid.0 - self.first_valid.0 > params.max_txn_life {
+            violations.push(Violation::ValidityWindowTooLong {
+                len: self.last_valid.0 - self.first_valid.0,
+                max: params.max_txn_life,
+            });
+        }
+        let note_len = self.note.as_ref().map_or(0, |n| n.len() as u64);
+        if note_len > params.max_txn_note_bytes {
+            violations.push(Violation::NoteTooLong {
+                len: note_len,
+                max: params.max_txn_note_bytes,
+            });
+        }
+
+        match &self.txn_type {
+            TransactionType::ApplicationCallTransaction(call) => {
+                app_call_violations(call, params, &mut violations)
+            }
+            TransactionType::AssetConfigurationTransaction(config) => {
+                if let Some(asset_params) = &config.params {
+                    asset_params_violations(asset_params, params, &mut violations)
+                }
+            }
+            _ => {}
+        }
+
+        violations
+    }
+}
+
+impl TxGroup {
+    /// Checks the transactions of a group against the consensus limits, without contacting a node.
+    ///
+    /// Besides the checks of [Transaction::validate] (with fees pooled across the group),
+    /// this checks the group size and that all the transactions have the group's id.
+    /// Returns all the violations found.
+    pub fn validate(
+        txns: &[Transaction],
+        params: &ConsensusParams,
+    ) -> Result<(), Vec<GroupViolation>> {
+        let mut violations = vec![];
+        let group_violation = |violation| GroupViolation {
+            tx_index: None,
+            violation,
+        };
+
+        if txns.is_empty() {
+            violations.push(group_violation(Violation::EmptyGroup));
+            return Err(violations);
+        }
+        if txns.len() as u64 > params.max_tx_group_size {
+            violations.push(group_violation(Violation::GroupTooLarge {
+                size: txns.len() as u64,
+                max: params.max_tx_group_size,
+            }));
+        }
+
+        for (i, tx) in txns.iter().enumerate() {
+            violations.extend(tx.violations(params, false).into_iter().map(|violation| {
+                GroupViolation {
+                    tx_index: Some(i),
+                    violation,
+                }
+            }));
+        }
+
+        let fee = txns.iter().fold(MicroAlgos(0), |sum, tx| sum + tx.fee);
+        let min_fee = params.min_txn_fee * txns.len() as u64;
+        if fee < min_fee {
+            violations.push(group_violation(Violation::GroupFeeBelowMin {
+                fee,
+                min_fee,
+            }));
+        }
+
+        // a single transaction doesn't need a group id, but if it has one it has to be valid
+        if txns.len() > 1 || txns[0].group.is_some() {
+            let mut ungrouped_txns = txns.to_vec();
+            for tx in ungrouped_txns.iter_mut() {
+                tx.group = None;
+            }
+            // the group id can't be computed if the group is too large, which was reported above
+            if let Ok(group_id) =
+                TxGroup::compute_group_id(&ungrouped_txns.iter_mut().collect::<Vec<_>>())
+            {
+                for (i, tx) in txns.iter().enumerate() {
+                    let violation = match tx.group {
+                        None => Violation::MissingGroupId,
+                        Some(id) if id != group_id => Violation::GroupIdMismatch,
+                        Some(_) => continue,
+                    };
+                    violations.push(GroupViolation {
+                        tx_index: Some(i),
+                        violation,
+                    });
+                }
+            }
+        }
+
+        to_result(violations)
+    }
+}
+
+fn app_call_violations(
+    call: &ApplicationCallTransaction,
+    params: &ConsensusParams,
+    violations: &mut Vec<Violation>,
+) {
+    let args = call.app_arguments.as_deref().unwrap_or_default();
+    if args.len() as u64 > params.max_app_args {
+        violations.push(Violation::TooManyAppArgs {
+            count: args.len() as u64,
+            max: params.max_app_args,
+        });
+    }
+    let args_len = args.iter().map(|a| a.len() as u64).sum();
+    if args_len > params.max_app_total_arg_len {
+        violations.push(Violation::AppArgsTooLong {
+            len: args_len,
+            max: params.max_app_total_arg_len,
+        });
+    }
+
+    let accounts = len(&call.accounts);
+    let apps = len(&call.foreign_apps);
+    let assets = len(&call.foreign_assets);
+    let boxes = len(&call.boxes);
+    if accounts > params.max_app_txn_accounts {
+        violations.push(Violation::TooManyAccounts {
+            count: accounts,
+            max: params.max_app_txn_accounts,
+        });
+    }
+    if apps > params.max_app_txn_foreign_apps {
+        violations.push(Violation::TooManyForeignApps {
+            count: apps,
+            max: params.max_app_txn_foreign_apps,
+        });
+    }
+    if assets > params.max_app_txn_foreign_assets {
+        violations.push(Violation::TooManyForeignAssets {
+            count: assets,
+            max: params.max_app_txn_foreign_assets,
+        });
+    }
+    if boxes > params.max_app_box_references {
+        violations.push(Violation::TooManyBoxReferences {
+            count: boxes,
+            max: params.max_app_box_references,
+        });
+    }
+    if accounts + apps + assets + boxes > params.max_app_total_txn_references {
+        violations.push(Violation::TooManyReferences {
+            count: accounts + apps + assets + boxes,
+            max: params.max_app_total_txn_references,
+        });
+    }
+    for box_ in call.boxes.iter().flatten() {
+        match box_.app_id {
+            None | Some(0) => {}
+            Some(app_id) if call.app_id == Some(app_id) => {}
+            Some(app_id) if call.foreign_apps.iter().flatten().any(|a| *a == app_id) => {}
+            Some(app_id) => violations.push(Violation::BoxAppNotReferenced { app_id }),
+        }
+    }
+
+    let extra_pages = call.extra_pages as u64;
+    if extra_pages > params.max_extra_app_program_pages {
+        violations.push(Violation::TooManyExtraPages {
+            count: extra_pages,
+            max: params.max_extra_app_program_pages,
+        });
+    }
+    let programs_len = call
+        .approval_program
+        .as_ref()
+        .map_or(0, |p| p.0.len() as u64)
+        + call
+            .clear_state_program
+            .as_ref()
+            .map_or(0, |p| p.0.len() as u64);
+    let max_programs_len = (1 + extra_pages) * params.max_app_program_len;
+    if programs_len > max_programs_len {
+        violations.push(Violation::ProgramsTooLong {
+            len: programs_len,
+            max: max_programs_len,
+        });
+    }
+}
+
+fn asset_params_violations(
+    asset_params: &AssetParams,
+    params: &ConsensusParams,
+    violations: &mut Vec<Violation>,
+) {
+    let str_len = |s: &Option<String>| s.as_ref().map_or(0, |s| s.len() as u64);
+
+    let name_len = str_len(&asset_params.asset_name);
+    if name_len > params.max_asset_name_bytes {
+        violations.push(Violation::AssetNameTooLong {
+            len: name_len,
+            max: params.max_asset_name_bytes,
+        });
+    }
+    let unit_name_len = str_len(&asset_params.unit_name);
+    if unit_name_len > params.max_asset_unit_name_bytes {
+        violations.push(Violation::AssetUnitNameTooLong {
+            len: unit_name_len,
+            max: params.max_asset_unit_name_bytes,
+        });
+    }
+    let url_len = str_len(&asset_params.url);
+    if url_len > params.max_asset_url_bytes {
+        violations.push(Violation::AssetUrlTooLong {
+            len: url_len,
+            max: params.max_asset_url_bytes,
+        });
+    }
+    if let Some(decimals) = asset_params.decimals {
+        if decimals > params.max_asset_decimals {
+            violations.push(Violation::TooManyAssetDecimals {
+                decimals,
+                max: params.max_asset_decimals,
+            });
+        }
+    }
+}
+
+fn len<T>(v: &Option<Vec<T>>) -> u64 {
+    v.as_ref().map_or(0, |v| v.len() as u64)
+}
+
+fn to_result<T>(violations: Vec<T>) -> Result<(), Vec<T>> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::CallApplication, transaction::BoxReference, CreateAsset, Pay, TxnBuilder,
+    };
+    use algonaut_core::Address;
+    use algonaut_crypto::HashDigest;
+
+    fn tx(fee: u64, txn_type: TransactionType) -> Transaction {
+        TxnBuilder::new(
+            MicroAlgos(fee),
+            Round(100),
+            Round(200),
+            HashDigest([0; 32]),
+            txn_type,
+        )
+        .build()
+        .unwrap()
+    }
+
+    fn payment(fee: u64) -> Transaction {
+        tx(
+            fee,
+            Pay::new(Address([0; 32]), Address([1; 32]), MicroAlgos(1)).build(),
+        )
+    }
+
+    #[test]
+    fn test_valid_transaction() {
+        assert_eq!(Ok(()), payment(1000).validate(&ConsensusParams::default()));
+    }
+
+    #[test]
+    fn test_transaction_violations() {
+        let mut tx = payment(999);
+        tx.last_valid = Round(1101);
+        tx.note = Some(vec![0; 1025]);
+
+        let violations = tx.validate(&ConsensusParams::default()).unwrap_err();
+
+        assert_eq!(
+            vec![
+                Violation::FeeBelowMin {
+                    fee: MicroAlgos(999),
+                    min_fee: MicroAlgos(1000)
+                },
+                Violation::ValidityWindowTooLong {
+                    len: 1001,
+                    max: 1000
+                },
+                Violation::NoteTooLong {
+                    len: 1025,
+                    max: 1024
+                },
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_app_call_violations() {
+        let tx = tx(
+            1000,
+            CallApplication::new(Address([0; 32]), 5)
+                .app_arguments(vec![vec![0; 1024]; 3])
+                .accounts(vec![Address([1; 32]); 5])
+                .foreign_apps(vec![6])
+                .boxes(vec![
+                    BoxReference {
+                        app_id: Some(5),
+                        name: vec![1],
+                    },
+                    BoxReference {
+                        app_id: Some(6),
+                        name: vec![1],
+                    },
+                    BoxReference {
+                        app_id: Some(7),
+                        name: vec![1],
+                    },
+                ])
+                .build(),
+        );
+
+        let violations = tx.validate(&ConsensusParams::default()).unwrap_err();
+
+        assert_eq!(
+            vec![
+                Violation::AppArgsTooLong {
+                    len: 3072,
+                    max: 2048
+                },
+                Violation::TooManyAccounts { count: 5, max: 4 },
+                Violation::TooManyReferences { count: 9, max: 8 },
+                Violation::BoxAppNotReferenced { app_id: 7 },
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_asset_config_violations() {
+        let tx = tx(
+            1000,
+            CreateAsset::new(Address([0; 32]), 1, 20, false)
+                .asset_name("a".repeat(33))
+                .unit_name("a".repeat(9))
+                .url("a".repeat(97))
+                .build(),
+        );
+
+        let violations = tx.validate(&ConsensusParams::default()).unwrap_err();
+
+        assert_eq!(
+            vec![
+                Violation::AssetNameTooLong { len: 33, max: 32 },
+                Violation::AssetUnitNameTooLong { len: 9, max: 8 },
+                Violation::AssetUrlTooLong { len: 97, max: 96 },
+                Violation::TooManyAssetDecimals {
+                    decimals: 20,
+                    max: 19
+                },
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_valid_group_with_pooled_fee() {
+        let mut txns = vec![payment(2000), payment(0)];
+        TxGroup::assign_group_id(&mut txns.iter_mut().collect::<Vec<_>>()).unwrap();
+
+        assert_eq!(
+            Ok(()),
+            TxGroup::validate(&txns, &ConsensusParams::default())
+        );
+    }
+
+    #[test]
+    fn test_group_violations() {
+        let mut txns = vec![payment(1000), payment(0), payment(500)];
+        TxGroup::assign_group_id(&mut txns.iter_mut().collect::<Vec<_>>()).unwrap();
+        txns[1].group = None;
+        txns[2].group = Some(HashDigest([1; 32]));
+
+        let violations = TxGroup::validate(&txns, &ConsensusParams::default()).unwrap_err();
+
+        assert_eq!(
+            vec![
+                GroupViolation {
+                    tx_index: None,
+                    violation: Violation::GroupFeeBelowMin {
+                        fee: MicroAlgos(1500),
+                        min_fee: MicroAlgos(3000)
+                    }
+                },
+                GroupViolation {
+                    tx_index: Some(1),
+                    violation: Violation::MissingGroupId
+                },
+                GroupViolation {
+                    tx_index: Some(2),
+                    violation: Violation::GroupIdMismatch
+                },
+            ],
+            violations
+        );
+    }
+
+    #[test]
+    fn test_group_too_large() {
+        let txns = vec![payment(1000); 17];
+
+        let violations = TxGroup::validate(&txns, &ConsensusParams::default()).unwrap_err();
+
+        assert_eq!(
+            GroupViolation {
+                tx_index: None,
+                violation: Violation::GroupTooLarge { size: 17, max: 16 }
+            },
+            violations[0]
+        );
+    }
+}
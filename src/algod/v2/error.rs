@@ -1,5 +1,7 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, io};
 
+use super::rejection::AlgodRejection;
+use crate::error::{response_error_message, RequestError, RequestErrorDetails};
 use algonaut_algod::apis;
 use thiserror::Error;
 
@@ -8,10 +10,29 @@ pub enum AlgodError {
     /// General text-only errors. Dedicated error variants can be created, if needed.
     #[error("Msg: {0}")]
     Msg(String),
+    /// The request didn't get a response.
+    #[error("{0}")]
+    Request(RequestError),
+    /// Algod returned an error response.
+    #[error("{0}")]
+    Rejected(AlgodRejection),
 }
 
 impl<T: Debug> From<apis::Error<T>> for AlgodError {
     fn from(error: apis::Error<T>) -> Self {
-        AlgodError::Msg(format!("{:?}", error))
+        match error {
+            apis::Error::ResponseError(response) => AlgodError::Rejected(AlgodRejection::new(
+                response.status.as_u16(),
+                response_error_message(&response.content),
+            )),
+            apis::Error::Reqwest(e) => AlgodError::Request(RequestError::new(
+                e.url().map(|u| u.to_string()),
+                RequestErrorDetails::from(&e),
+            )),
+            apis::Error::Io(e) if e.kind() == io::ErrorKind::TimedOut => {
+                AlgodError::Request(RequestError::new(None, RequestErrorDetails::Timeout))
+            }
+            error => AlgodError::Msg(format!("{:?}", error)),
+        }
     }
 }
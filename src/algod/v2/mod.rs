@@ -1,8 +1,18 @@
 use self::error::AlgodError;
-use crate::Error;
+use crate::{
+    client::{ClientBuilder, RetryPolicy},
+    Error,
+};
 use algonaut_algod::{
-    apis::configuration::{ApiKey, Configuration},
-    ext::block::BlockResponse,
+    apis::{
+        self,
+        configuration::{ApiKey, Configuration},
+    },
+    ext::{
+        block::BlockResponse,
+        ledger_state_delta::LedgerStateDelta,
+        msgpack::{MsgpackBlock, PendingTransaction},
+    },
     models::{
         self, Account, AccountApplicationInformation200Response, Application, Asset, DryrunRequest,
         GetApplicationBoxes200Response, GetBlockHash200Response,
@@ -16,22 +26,68 @@ use algonaut_algod::{
 use algonaut_core::{CompiledTeal, ToMsgPack};
 use algonaut_encoding::decode_base64;
 use algonaut_transaction::SignedTransaction;
+use std::{fmt::Debug, future::Future, time::Duration};
+
+use self::simulate::SimulateBuilder;
 
 /// Error class wrapping errors from algonaut_algod
 pub(crate) mod error;
 
+/// Failover across several algod nodes
+pub mod pool;
+
+/// Typed causes of algod error responses
+pub mod rejection;
+
+/// Builder for simulate requests
+pub mod simulate;
+
 #[derive(Debug, Clone)]
 pub struct Algod {
     pub(crate) configuration: Configuration,
+    retry_policy: RetryPolicy,
+    format: Format,
+}
+
+/// Encoding requested for responses that algod can return as msgpack, see [Algod::format].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    /// Decoded into the same models as JSON: binary values are transferred losslessly,
+    /// but strings that aren't valid UTF-8, e.g. logs in blocks, are still lossy in the models.
+    /// Use [Algod::block_msgpack] and [Algod::pending_txn_msgpack] to decode them losslessly.
+    Msgpack,
+}
+
+impl Format {
+    fn query(self) -> Option<&'static str> {
+        match self {
+            Format::Json => None,
+            Format::Msgpack => Some("msgpack"),
+        }
+    }
 }
 
 impl Algod {
     /// Build a v2 client for Algorand protocol daemon.
+    ///
+    /// For timeouts, custom headers or retries, use [ClientBuilder](crate::client::ClientBuilder).
     pub fn new(url: &str, token: &str) -> Result<Self, Error> {
+        ClientBuilder::new().algod(url, token)
+    }
+
+    pub(crate) fn with_client(
+        url: &str,
+        token: &str,
+        client: reqwest::Client,
+        user_agent: String,
+        retry_policy: RetryPolicy,
+        read_timeout: Option<Duration>,
+    ) -> Algod {
         let conf = Configuration {
             base_path: url.to_owned(),
-            user_agent: Some("algonaut".to_owned()),
-            client: reqwest::Client::new(),
+            user_agent: Some(user_agent),
+            client,
             basic_auth: None,
             oauth_access_token: None,
             bearer_access_token: None,
@@ -39,11 +95,32 @@ impl Algod {
                 prefix: None,
                 key: token.to_owned(),
             }),
+            read_timeout,
         };
 
-        Ok(Self {
+        Algod {
             configuration: conf,
-        })
+            retry_policy,
+            format: Format::Json,
+        }
+    }
+
+    /// Sets the encoding requested for [Algod::block] and [Algod::pending_txn], JSON by default.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sends an idempotent request, retrying it according to the retry policy.
+    async fn get<T, E, F, Fut>(&self, request: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, apis::Error<E>>>,
+        E: Debug,
+    {
+        self.retry_policy
+            .retry(|| async { Ok(request().await.map_err(Into::<AlgodError>::into)?) })
+            .await
     }
 
     /// Given a specific account public key and application ID, this call returns the account's application local state and global state (AppLocalState and AppParams, if either exists). Global state will only be returned if the provided address is the application's creator.
@@ -52,50 +129,46 @@ impl Algod {
         address: &str,
         application_id: u64,
     ) -> Result<AccountApplicationInformation200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_algod::apis::public_api::account_application_information(
                 &self.configuration,
                 address,
                 application_id,
                 None,
             )
-            .await
-            .map_err(Into::<AlgodError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Given a specific account public key, this call returns the accounts status, balance and spendable amounts
     pub async fn account(&self, address: &str) -> Result<Account, Error> {
-        Ok(algonaut_algod::apis::public_api::account_information(
-            &self.configuration,
-            address,
-            None,
-            None,
-        )
+        self.get(|| {
+            algonaut_algod::apis::public_api::account_information(
+                &self.configuration,
+                address,
+                None,
+                None,
+            )
+        })
         .await
-        .map_err(Into::<AlgodError>::into)?)
     }
 
     /// Returns wether the experimental API are enabled
     pub async fn experimental(&self) -> Result<(), Error> {
-        Ok(
-            algonaut_algod::apis::public_api::experimental_check(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::experimental_check(&self.configuration))
+            .await
     }
 
     /// Given an application ID and box name, it returns the box name and value (each base64 encoded). Box names must be in the goal app call arg encoding form 'encoding:value'. For ints, use the form 'int:1234'. For raw bytes, use the form 'b64:A=='. For printable strings, use the form 'str:hello'. For addresses, use the form 'addr:XYZ...'.
     pub async fn app_box(&self, application_id: u64, name: &str) -> Result<models::Box, Error> {
-        Ok(
+        self.get(|| {
             algonaut_algod::apis::public_api::get_application_box_by_name(
                 &self.configuration,
                 application_id,
                 name,
             )
-            .await
-            .map_err(Into::<AlgodError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Given an application ID, return all Box names. No particular ordering is guaranteed. Request fails when client or server-side configured limits prevent returning all Box names.
@@ -104,70 +177,77 @@ impl Algod {
         application_id: u64,
         max: Option<u64>,
     ) -> Result<GetApplicationBoxes200Response, Error> {
-        Ok(algonaut_algod::apis::public_api::get_application_boxes(
-            &self.configuration,
-            application_id,
-            max,
-        )
+        self.get(|| {
+            algonaut_algod::apis::public_api::get_application_boxes(
+                &self.configuration,
+                application_id,
+                max,
+            )
+        })
         .await
-        .map_err(Into::<AlgodError>::into)?)
     }
 
     /// Given a application ID, it returns application information including creator, approval and clear programs, global and local schemas, and global state.
     pub async fn app(&self, application_id: u64) -> Result<Application, Error> {
-        Ok(algonaut_algod::apis::public_api::get_application_by_id(
-            &self.configuration,
-            application_id,
-        )
+        self.get(|| {
+            algonaut_algod::apis::public_api::get_application_by_id(
+                &self.configuration,
+                application_id,
+            )
+        })
         .await
-        .map_err(Into::<AlgodError>::into)?)
     }
 
     /// Given a asset ID, it returns asset information including creator, name, total supply and special addresses.
     pub async fn asset(&self, asset_id: u64) -> Result<Asset, Error> {
-        Ok(
+        self.get(|| {
             algonaut_algod::apis::public_api::get_asset_by_id(&self.configuration, asset_id)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Get the block for the given round.
     pub async fn block(&self, round: u64) -> Result<BlockResponse, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_block(&self.configuration, round, None)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| {
+            algonaut_algod::apis::public_api::get_block(
+                &self.configuration,
+                round,
+                self.format.query(),
+            )
+        })
+        .await
+    }
+
+    /// Get the block for the given round, requested as msgpack, with its transactions' exact encoding.
+    pub async fn block_msgpack(&self, round: u64) -> Result<MsgpackBlock, Error> {
+        self.get(|| algonaut_algod::apis::public_api::get_block_msgpack(&self.configuration, round))
+            .await
     }
 
     /// Get the block hash for the block on the given round.
     pub async fn block_hash(&self, round: u64) -> Result<GetBlockHash200Response, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_block_hash(&self.configuration, round)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_block_hash(&self.configuration, round))
+            .await
     }
 
     /// Returns the entire genesis file in json.
     pub async fn genesis(&self) -> Result<String, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_genesis(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_genesis(&self.configuration))
+            .await
     }
 
     /// Get ledger deltas for a round.
-    pub async fn state_delta(&self, round: u64) -> Result<serde_json::Value, Error> {
-        Ok(algonaut_algod::apis::public_api::get_ledger_state_delta(
-            &self.configuration,
-            round,
-            None,
-        )
+    ///
+    /// Fetched as msgpack, since the JSON encoding of state keys and values is lossy.
+    pub async fn state_delta(&self, round: u64) -> Result<LedgerStateDelta, Error> {
+        self.get(|| {
+            algonaut_algod::apis::public_api::get_ledger_state_delta(
+                &self.configuration,
+                round,
+                Some("msgpack"),
+            )
+        })
         .await
-        .map_err(Into::<AlgodError>::into)?)
     }
 
     /// Gets a proof for a given light block header inside a state proof commitment.
@@ -175,14 +255,13 @@ impl Algod {
         &self,
         round: u64,
     ) -> Result<LightBlockHeaderProof, Error> {
-        Ok(
+        self.get(|| {
             algonaut_algod::apis::public_api::get_light_block_header_proof(
                 &self.configuration,
                 round,
             )
-            .await
-            .map_err(Into::<AlgodError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Get the list of pending transactions, sorted by priority, in decreasing order, truncated at the end at MAX. If MAX = 0, returns all pending transactions.
@@ -190,13 +269,14 @@ impl Algod {
         &self,
         max: Option<u64>,
     ) -> Result<GetPendingTransactionsByAddress200Response, Error> {
-        Ok(algonaut_algod::apis::public_api::get_pending_transactions(
-            &self.configuration,
-            max,
-            None,
-        )
+        self.get(|| {
+            algonaut_algod::apis::public_api::get_pending_transactions(
+                &self.configuration,
+                max,
+                None,
+            )
+        })
         .await
-        .map_err(Into::<AlgodError>::into)?)
     }
 
     /// Get the list of pending transactions by address, sorted by priority, in decreasing order, truncated at the end at MAX. If MAX = 0, returns all pending transactions.
@@ -205,61 +285,45 @@ impl Algod {
         address: &str,
         max: Option<u64>,
     ) -> Result<GetPendingTransactionsByAddress200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_algod::apis::public_api::get_pending_transactions_by_address(
                 &self.configuration,
                 address,
                 max,
                 None,
             )
-            .await
-            .map_err(Into::<AlgodError>::into)?,
-        )
+        })
+        .await
     }
 
     /// TODO
     pub async fn ready(&self) -> Result<(), Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_ready(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_ready(&self.configuration))
+            .await
     }
 
     /// Get a state proof that covers a given round.
     pub async fn state_proof(&self, round: u64) -> Result<StateProof, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_state_proof(&self.configuration, round)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_state_proof(&self.configuration, round))
+            .await
     }
 
     /// Gets the current node status.
     pub async fn status(&self) -> Result<GetStatus200Response, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_status(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_status(&self.configuration))
+            .await
     }
 
     /// Get the current supply reported by the ledger.
     pub async fn supply(&self) -> Result<GetSupply200Response, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_supply(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_supply(&self.configuration))
+            .await
     }
 
     /// Gets the minimum sync round for the ledger.
     pub async fn sync_round(&self) -> Result<GetSyncRound200Response, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_sync_round(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_sync_round(&self.configuration))
+            .await
     }
 
     /// Get a proof for a transaction in a block.
@@ -268,55 +332,58 @@ impl Algod {
         round: u64,
         txid: &str,
     ) -> Result<GetTransactionProof200Response, Error> {
-        Ok(algonaut_algod::apis::public_api::get_transaction_proof(
-            &self.configuration,
-            round,
-            txid,
-            None,
-            None,
-        )
+        self.get(|| {
+            algonaut_algod::apis::public_api::get_transaction_proof(
+                &self.configuration,
+                round,
+                txid,
+                None,
+                None,
+            )
+        })
         .await
-        .map_err(Into::<AlgodError>::into)?)
     }
 
     /// Retrieves the supported API versions, binary build versions, and genesis information.
     pub async fn version(&self) -> Result<Version, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::get_version(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::get_version(&self.configuration))
+            .await
     }
 
     /// Returns Ok if healthy
     pub async fn health(&self) -> Result<(), Error> {
-        Ok(
-            algonaut_algod::apis::public_api::health_check(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::health_check(&self.configuration))
+            .await
     }
 
     /// Return metrics about algod functioning.
     pub async fn metrics(&self) -> Result<(), Error> {
-        Ok(
-            algonaut_algod::apis::public_api::metrics(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::metrics(&self.configuration))
+            .await
     }
 
     /// Given a transaction ID of a recently submitted transaction, it returns information about it.  There are several cases when this might succeed: - transaction committed (committed round > 0) - transaction still in the pool (committed round = 0, pool error = \"\") - transaction removed from pool due to error (committed round = 0, pool error != \"\") Or the transaction may have happened sufficiently long ago that the node no longer remembers it, and this will return an error.
     pub async fn pending_txn(&self, txid: &str) -> Result<PendingTransactionResponse, Error> {
-        Ok(
+        self.get(|| {
             algonaut_algod::apis::public_api::pending_transaction_information(
                 &self.configuration,
                 txid,
-                None,
+                self.format.query(),
             )
-            .await
-            .map_err(Into::<AlgodError>::into)?,
-        )
+        })
+        .await
+    }
+
+    /// Like [Algod::pending_txn], requested as msgpack: decodes the transaction losslessly and
+    /// computes its id from the exact bytes returned by algod.
+    pub async fn pending_txn_msgpack(&self, txid: &str) -> Result<PendingTransaction, Error> {
+        self.get(|| {
+            algonaut_algod::apis::public_api::pending_transaction_information_msgpack(
+                &self.configuration,
+                txid,
+            )
+        })
+        .await
     }
 
     /// Broadcasts a raw transaction or transaction group to the network.
@@ -373,13 +440,16 @@ impl Algod {
         .map_err(Into::<AlgodError>::into)?)
     }
 
+    /// Creates a builder to simulate transaction groups with simulation options,
+    /// e.g. to allow empty signatures or to return an execution trace.
+    pub fn simulate(&self) -> SimulateBuilder<'_> {
+        SimulateBuilder::new(self)
+    }
+
     /// Returns the entire swagger spec in json.
     pub async fn swagger_json(&self) -> Result<String, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::swagger_json(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::swagger_json(&self.configuration))
+            .await
     }
 
     /// Given TEAL source code in plain text, return base64 encoded program bytes and base32 SHA512_256 hash of program bytes (Address style). This endpoint is only enabled when a node's configuration file sets EnableDeveloperAPI to true.
@@ -427,11 +497,8 @@ impl Algod {
 
     /// Get parameters for constructing a new transaction.
     pub async fn txn_params(&self) -> Result<TransactionParams200Response, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::transaction_params(&self.configuration)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::transaction_params(&self.configuration))
+            .await
     }
 
     /// Unset the ledger sync round.
@@ -445,17 +512,15 @@ impl Algod {
 
     /// Waits for a block to appear after round {round} and returns the node's status at the time.
     pub async fn status_after_block(&self, round: u64) -> Result<GetStatus200Response, Error> {
-        Ok(
-            algonaut_algod::apis::public_api::wait_for_block(&self.configuration, round)
-                .await
-                .map_err(Into::<AlgodError>::into)?,
-        )
+        self.get(|| algonaut_algod::apis::public_api::wait_for_block(&self.configuration, round))
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_server::{TestResponse, TestServer};
 
     #[test]
     fn test_valid_client_builder() {
@@ -465,4 +530,53 @@ mod tests {
         );
         assert!(res.ok().is_some());
     }
+
+    #[tokio::test]
+    async fn test_requests_msgpack() {
+        let node = TestServer::start(|_| {
+            TestResponse::bytes(
+                [
+                    &[0x81, 0xa5][..],
+                    b"block",
+                    &[0x82, 0xa3],
+                    b"rnd",
+                    &[0x0a, 0xa4],
+                    b"txns",
+                    &[0x90],
+                ]
+                .concat(),
+            )
+        });
+        let algod = Algod::new(&node.url, "").unwrap();
+
+        let block = algod.block_msgpack(10).await.unwrap();
+
+        assert_eq!(10, block.header.round);
+        assert!(block.txns.is_empty());
+        assert_eq!(vec!["/v2/blocks/10?format=msgpack"], node.paths());
+    }
+
+    #[tokio::test]
+    async fn test_decodes_msgpack_format_into_json_models() {
+        let node = TestServer::start(|_| {
+            TestResponse::bytes(
+                [
+                    &[0x81, 0xa5][..],
+                    b"block",
+                    &[0x82, 0xa3],
+                    b"rnd",
+                    &[0x0a, 0xa4],
+                    b"txns",
+                    &[0x90],
+                ]
+                .concat(),
+            )
+        });
+        let algod = Algod::new(&node.url, "").unwrap().format(Format::Msgpack);
+
+        let block = algod.block(10).await.unwrap().block;
+
+        assert_eq!(Some(10), block.round);
+        assert_eq!(vec!["/v2/blocks/10?format=msgpack"], node.paths());
+    }
 }
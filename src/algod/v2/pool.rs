@@ -0,0 +1,629 @@
+use super::{simulate::SimulateBuilder, Algod};
+use crate::Error;
+use algonaut_algod::{
+    ext::{
+        block::BlockResponse,
+        ledger_state_delta::LedgerStateDelta,
+        msgpack::{MsgpackBlock, PendingTransaction},
+    },
+    models::{
+        self, Account, AccountApplicationInformation200Response, Application, Asset, DryrunRequest,
+        GetApplicationBoxes200Response, GetBlockHash200Response,
+        GetPendingTransactionsByAddress200Response, GetStatus200Response, GetSupply200Response,
+        GetSyncRound200Response, GetTransactionProof200Response, LightBlockHeaderProof,
+        PendingTransactionResponse, RawTransaction200Response, SimulateRequest,
+        SimulateTransaction200Response, StateProof, TealDisassemble200Response,
+        TealDryrun200Response, TransactionParams200Response, Version,
+    },
+};
+use algonaut_core::{CompiledTeal, ToMsgPack};
+use algonaut_transaction::SignedTransaction;
+use futures::future::join_all;
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+/// Several algod nodes of the same network, used like a single [Algod].
+///
+/// Reads go to the most caught-up healthy node, as determined by the last [health check](AlgodPool::health_check),
+/// and fail over to the next node on transient errors (see [Error::is_transient]).
+/// Nodes that failed are only used again once no other node is left, or after the next health check.
+///
+/// Transactions are broadcast to several nodes at once, see [AlgodPool::send_raw_txn].
+///
+/// ```no_run
+/// # use algonaut::algod::v2::{pool::AlgodPool, Algod};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), algonaut::Error> {
+/// let pool = AlgodPool::new(vec![
+///     Algod::new("https://node1.example.com", "")?,
+///     Algod::new("https://node2.example.com", "")?,
+/// ])?;
+/// let params = pool.txn_params().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AlgodPool {
+    nodes: Vec<Algod>,
+    broadcast_to: usize,
+    state: Arc<Mutex<PoolState>>,
+}
+
+#[derive(Debug, Clone)]
+struct PoolState {
+    /// If the nodes were health-checked. Until then, they're used in the order they were passed.
+    checked: bool,
+    nodes: Vec<NodeState>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct NodeState {
+    healthy: bool,
+    last_round: u64,
+}
+
+impl AlgodPool {
+    /// Creates a pool of the nodes. Fails if there are none.
+    pub fn new(nodes: Vec<Algod>) -> Result<AlgodPool, Error> {
+        if nodes.is_empty() {
+            return Err(Error::Msg(
+                "An algod pool needs at least one node".to_owned(),
+            ));
+        }
+        let state = PoolState {
+            checked: false,
+            nodes: vec![
+                NodeState {
+                    healthy: true,
+                    last_round: 0
+                };
+                nodes.len()
+            ],
+        };
+        Ok(AlgodPool {
+            broadcast_to: nodes.len(),
+            nodes,
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Sets to how many nodes transactions are broadcast. Defaults to all nodes.
+    pub fn broadcast_to(mut self, nodes: usize) -> Self {
+        self.broadcast_to = nodes.max(1);
+        self
+    }
+
+    /// The nodes of the pool, in the order they were passed.
+    pub fn nodes(&self) -> &[Algod] {
+        &self.nodes
+    }
+
+    /// Checks the [health](Algod::health) and [status](Algod::status) of all nodes concurrently,
+    /// and ranks them by their last round.
+    ///
+    /// Returns the last round of each node, in the order they were passed, or the error that failed the check.
+    ///
+    /// Done before the first request. Call it periodically (e.g. every few rounds) to keep routing to the
+    /// most caught-up nodes and to use nodes again which recovered from failures.
+    pub async fn health_check(&self) -> Vec<Result<u64, Error>> {
+        let results = join_all(self.nodes.iter().map(|node| async move {
+            node.health().await?;
+            Ok(node.status().await?.last_round)
+        }))
+        .await;
+
+        let mut state = self.state.lock().unwrap();
+        state.checked = true;
+        for (node, result) in state.nodes.iter_mut().zip(&results) {
+            *node = match result {
+                Ok(last_round) => NodeState {
+                    healthy: true,
+                    last_round: *last_round,
+                },
+                Err(e) => {
+                    log::debug!("Algod node failed the health check: {e}");
+                    NodeState::default()
+                }
+            };
+        }
+        results
+    }
+
+    /// Indices of the nodes, by preference: healthy before unhealthy nodes, then the most caught-up first.
+    async fn ranked(&self) -> Vec<usize> {
+        let checked = self.state.lock().unwrap().checked;
+        if !checked {
+            self.health_check().await;
+        }
+        self.ranked_by_last_check()
+    }
+
+    fn ranked_by_last_check(&self) -> Vec<usize> {
+        let state = self.state.lock().unwrap();
+        let mut indices: Vec<usize> = (0..self.nodes.len()).collect();
+        // stable, so nodes with the same round keep their order
+        indices.sort_by_key(|i| {
+            let node = &state.nodes[*i];
+            (!node.healthy, std::cmp::Reverse(node.last_round))
+        });
+        indices
+    }
+
+    fn mark_unhealthy(&self, index: usize) {
+        self.state.lock().unwrap().nodes[index].healthy = false;
+    }
+
+    /// Sends the request to the preferred node, failing over to the next one on transient errors.
+    async fn read<'a, T, F, Fut>(&'a self, request: F) -> Result<T, Error>
+    where
+        F: Fn(&'a Algod) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut last_error = None;
+        for index in self.ranked().await {
+            match request(&self.nodes[index]).await {
+                Err(e) if e.is_transient() => {
+                    log::debug!(
+                        "Request to algod node: {} failed, failing over: {e}",
+                        self.nodes[index].configuration.base_path
+                    );
+                    self.mark_unhealthy(index);
+                    last_error = Some(e);
+                }
+                res => return res,
+            }
+        }
+        // the pool has at least one node, so there was a request
+        Err(last_error.expect("no algod node was requested"))
+    }
+
+    /// Sends the request to all nodes, failing if any of them fails.
+    async fn all<'a, F, Fut>(&'a self, request: F) -> Result<(), Error>
+    where
+        F: Fn(&'a Algod) -> Fut,
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        join_all(self.nodes.iter().map(request))
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    /// See [Algod::account_app].
+    pub async fn account_app(
+        &self,
+        address: &str,
+        application_id: u64,
+    ) -> Result<AccountApplicationInformation200Response, Error> {
+        self.read(|node| node.clone().account_app(address, application_id))
+            .await
+    }
+
+    /// See [Algod::account].
+    pub async fn account(&self, address: &str) -> Result<Account, Error> {
+        self.read(|node| node.account(address)).await
+    }
+
+    /// See [Algod::experimental].
+    pub async fn experimental(&self) -> Result<(), Error> {
+        self.read(|node| node.experimental()).await
+    }
+
+    /// See [Algod::app_box].
+    pub async fn app_box(&self, application_id: u64, name: &str) -> Result<models::Box, Error> {
+        self.read(|node| node.app_box(application_id, name)).await
+    }
+
+    /// See [Algod::app_boxes].
+    pub async fn app_boxes(
+        &self,
+        application_id: u64,
+        max: Option<u64>,
+    ) -> Result<GetApplicationBoxes200Response, Error> {
+        self.read(|node| node.app_boxes(application_id, max)).await
+    }
+
+    /// See [Algod::app].
+    pub async fn app(&self, application_id: u64) -> Result<Application, Error> {
+        self.read(|node| node.app(application_id)).await
+    }
+
+    /// See [Algod::asset].
+    pub async fn asset(&self, asset_id: u64) -> Result<Asset, Error> {
+        self.read(|node| node.asset(asset_id)).await
+    }
+
+    /// See [Algod::block].
+    pub async fn block(&self, round: u64) -> Result<BlockResponse, Error> {
+        self.read(|node| node.block(round)).await
+    }
+
+    /// See [Algod::block_msgpack].
+    pub async fn block_msgpack(&self, round: u64) -> Result<MsgpackBlock, Error> {
+        self.read(|node| node.block_msgpack(round)).await
+    }
+
+    /// See [Algod::block_hash].
+    pub async fn block_hash(&self, round: u64) -> Result<GetBlockHash200Response, Error> {
+        self.read(|node| node.block_hash(round)).await
+    }
+
+    /// See [Algod::genesis].
+    pub async fn genesis(&self) -> Result<String, Error> {
+        self.read(|node| node.genesis()).await
+    }
+
+    /// See [Algod::state_delta].
+    pub async fn state_delta(&self, round: u64) -> Result<LedgerStateDelta, Error> {
+        self.read(|node| node.state_delta(round)).await
+    }
+
+    /// See [Algod::light_block_header_proof].
+    pub async fn light_block_header_proof(
+        &self,
+        round: u64,
+    ) -> Result<LightBlockHeaderProof, Error> {
+        self.read(|node| node.light_block_header_proof(round)).await
+    }
+
+    /// See [Algod::pending_txns].
+    ///
+    /// Note that each node has its own transaction pool.
+    pub async fn pending_txns(
+        &self,
+        max: Option<u64>,
+    ) -> Result<GetPendingTransactionsByAddress200Response, Error> {
+        self.read(|node| node.pending_txns(max)).await
+    }
+
+    /// See [Algod::address_pending_txns].
+    ///
+    /// Note that each node has its own transaction pool.
+    pub async fn address_pending_txns(
+        &self,
+        address: &str,
+        max: Option<u64>,
+    ) -> Result<GetPendingTransactionsByAddress200Response, Error> {
+        self.read(|node| node.address_pending_txns(address, max))
+            .await
+    }
+
+    /// See [Algod::ready].
+    pub async fn ready(&self) -> Result<(), Error> {
+        self.read(|node| node.ready()).await
+    }
+
+    /// See [Algod::state_proof].
+    pub async fn state_proof(&self, round: u64) -> Result<StateProof, Error> {
+        self.read(|node| node.state_proof(round)).await
+    }
+
+    /// See [Algod::status].
+    pub async fn status(&self) -> Result<GetStatus200Response, Error> {
+        self.read(|node| node.status()).await
+    }
+
+    /// See [Algod::supply].
+    pub async fn supply(&self) -> Result<GetSupply200Response, Error> {
+        self.read(|node| node.supply()).await
+    }
+
+    /// See [Algod::sync_round].
+    pub async fn sync_round(&self) -> Result<GetSyncRound200Response, Error> {
+        self.read(|node| node.sync_round()).await
+    }
+
+    /// See [Algod::txn_proof].
+    pub async fn txn_proof(
+        &self,
+        round: u64,
+        txid: &str,
+    ) -> Result<GetTransactionProof200Response, Error> {
+        self.read(|node| node.txn_proof(round, txid)).await
+    }
+
+    /// See [Algod::version].
+    pub async fn version(&self) -> Result<Version, Error> {
+        self.read(|node| node.version()).await
+    }
+
+    /// Returns Ok if any node is healthy.
+    pub async fn health(&self) -> Result<(), Error> {
+        self.read(|node| node.health()).await
+    }
+
+    /// See [Algod::metrics].
+    pub async fn metrics(&self) -> Result<(), Error> {
+        self.read(|node| node.metrics()).await
+    }
+
+    /// See [Algod::pending_txn].
+    ///
+    /// Note that each node has its own transaction pool: the transaction may be unknown
+    /// to the node the request is routed to, until it's committed.
+    pub async fn pending_txn(&self, txid: &str) -> Result<PendingTransactionResponse, Error> {
+        self.read(|node| node.pending_txn(txid)).await
+    }
+
+    /// See [Algod::pending_txn_msgpack].
+    pub async fn pending_txn_msgpack(&self, txid: &str) -> Result<PendingTransaction, Error> {
+        self.read(|node| node.pending_txn_msgpack(txid)).await
+    }
+
+    /// Broadcasts a raw transaction or transaction group to several nodes concurrently,
+    /// the most caught-up first, see [AlgodPool::broadcast_to].
+    ///
+    /// Succeeds if any node accepted the transaction. Nodes relay transactions to each other,
+    /// so a transaction accepted by several nodes is still committed once: their responses
+    /// are deduplicated by txid.
+    ///
+    /// If no node accepted it, returns the rejection of a node, or a transport error if no node responded.
+    pub async fn send_raw_txn(&self, rawtxn: &[u8]) -> Result<RawTransaction200Response, Error> {
+        let nodes: Vec<usize> = self
+            .ranked()
+            .await
+            .into_iter()
+            .take(self.broadcast_to)
+            .collect();
+        let results = join_all(nodes.iter().map(|i| self.nodes[*i].send_raw_txn(rawtxn))).await;
+
+        let mut accepted: Vec<RawTransaction200Response> = vec![];
+        let mut rejection = None;
+        let mut transport_error = None;
+        for (index, result) in nodes.into_iter().zip(results) {
+            match result {
+                Ok(res) => {
+                    if !accepted.iter().any(|a| a.tx_id == res.tx_id) {
+                        accepted.push(res);
+                    }
+                }
+                Err(e) if e.is_transient() => {
+                    self.mark_unhealthy(index);
+                    transport_error = Some(e);
+                }
+                Err(e) => rejection = Some(e),
+            }
+        }
+
+        if accepted.len() > 1 {
+            log::warn!("Algod nodes returned different txids for a transaction: {accepted:?}");
+        }
+        match (accepted.into_iter().next(), rejection, transport_error) {
+            (Some(res), _, _) => Ok(res),
+            (None, Some(e), _) | (None, None, Some(e)) => Err(e),
+            (None, None, None) => Err(Error::Msg(
+                "The transaction wasn't sent to any algod node".to_owned(),
+            )),
+        }
+    }
+
+    /// Broadcasts a transaction, see [AlgodPool::send_raw_txn].
+    pub async fn send_txn(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<RawTransaction200Response, Error> {
+        self.send_raw_txn(&txn.to_msg_pack()?).await
+    }
+
+    /// Broadcasts a transaction group, see [AlgodPool::send_raw_txn].
+    ///
+    /// Atomic if the transactions share a [group](algonaut_transaction::transaction::Transaction::group)
+    pub async fn send_txns(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<RawTransaction200Response, Error> {
+        let mut bytes = vec![];
+        for t in txns {
+            bytes.push(t.to_msg_pack()?);
+        }
+        self.send_raw_txn(&bytes.concat()).await
+    }
+
+    /// Sets the minimum sync round on the ledger of every node.
+    pub async fn sync(&self, round: u64) -> Result<(), Error> {
+        self.all(|node| node.sync(round)).await
+    }
+
+    /// See [Algod::simulate_txns].
+    pub async fn simulate_txns(
+        &self,
+        request: SimulateRequest,
+    ) -> Result<SimulateTransaction200Response, Error> {
+        self.read(|node| node.simulate_txns(request.clone())).await
+    }
+
+    /// Creates a builder to simulate transaction groups on the preferred node, see [Algod::simulate].
+    ///
+    /// Doesn't fail over to other nodes, and uses the ranking of the last health check.
+    pub fn simulate(&self) -> SimulateBuilder<'_> {
+        self.nodes[self.ranked_by_last_check()[0]].simulate()
+    }
+
+    /// See [Algod::swagger_json].
+    pub async fn swagger_json(&self) -> Result<String, Error> {
+        self.read(|node| node.swagger_json()).await
+    }
+
+    /// See [Algod::teal_compile].
+    pub async fn teal_compile(
+        &self,
+        source: &[u8],
+        sourcemap: Option<bool>,
+    ) -> Result<CompiledTeal, Error> {
+        self.read(|node| node.teal_compile(source, sourcemap)).await
+    }
+
+    /// See [Algod::teal_disassemble].
+    pub async fn teal_disassemble(
+        &self,
+        source: &[u8],
+    ) -> Result<TealDisassemble200Response, Error> {
+        self.read(|node| node.teal_disassemble(source)).await
+    }
+
+    /// See [Algod::teal_dryrun].
+    pub async fn teal_dryrun(
+        &self,
+        request: Option<DryrunRequest>,
+    ) -> Result<TealDryrun200Response, Error> {
+        self.read(|node| node.teal_dryrun(request.clone())).await
+    }
+
+    /// See [Algod::txn_params].
+    pub async fn txn_params(&self) -> Result<TransactionParams200Response, Error> {
+        self.read(|node| node.txn_params()).await
+    }
+
+    /// Unsets the ledger sync round of every node.
+    pub async fn unsync(&self) -> Result<(), Error> {
+        self.all(|node| node.unsync()).await
+    }
+
+    /// See [Algod::status_after_block].
+    pub async fn status_after_block(&self, round: u64) -> Result<GetStatus200Response, Error> {
+        self.read(|node| node.status_after_block(round)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{
+        test_fixtures::status,
+        test_server::{TestResponse, TestServer},
+    };
+    use std::net::TcpListener;
+
+    /// A healthy node at `round`, accepting transactions with `send` (e.g. returning a txid).
+    fn node(round: u64, send: TestResponse) -> TestServer {
+        TestServer::start(move |request| match request.path.as_str() {
+            "/health" => TestResponse::json(""),
+            "/v2/transactions" => send.clone(),
+            path if path.starts_with("/v2/status") => TestResponse::json(&status(round)),
+            _ => TestResponse::error(404, "not found"),
+        })
+    }
+
+    fn accepts(tx_id: &str) -> TestResponse {
+        TestResponse::json(&format!(r#"{{"txId":"{tx_id}"}}"#))
+    }
+
+    /// The url of a port nothing listens on.
+    fn unreachable_url() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    fn pool(urls: &[&str]) -> AlgodPool {
+        AlgodPool::new(
+            urls.iter()
+                .map(|url| Algod::new(url, "").unwrap())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn requested(node: &TestServer, path: &str) -> usize {
+        node.paths().iter().filter(|p| *p == path).count()
+    }
+
+    #[tokio::test]
+    async fn test_routes_reads_to_most_caught_up_node() {
+        let behind = node(100, accepts("TX"));
+        let ahead = node(102, accepts("TX"));
+        let down = unreachable_url();
+        let pool = pool(&[&behind.url, &down, &ahead.url]);
+
+        let rounds = pool.health_check().await;
+        assert_eq!(Some(&100), rounds[0].as_ref().ok());
+        assert!(rounds[1].as_ref().unwrap_err().is_transient());
+        assert_eq!(Some(&102), rounds[2].as_ref().ok());
+
+        assert_eq!(102, pool.status().await.unwrap().last_round);
+        assert_eq!(1, requested(&behind, "/v2/status"));
+        assert_eq!(2, requested(&ahead, "/v2/status"));
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_on_transport_errors() {
+        let down = unreachable_url();
+        let up = node(100, accepts("TX"));
+        let pool = pool(&[&down, &up.url]);
+
+        // the first request ranks the nodes
+        assert_eq!(100, pool.status().await.unwrap().last_round);
+
+        // a node failing after the health check is skipped until the next one
+        let failing = TestServer::start(|request| match request.path.as_str() {
+            "/health" => TestResponse::json(""),
+            "/v2/status" => TestResponse::json(&status(200)),
+            _ => TestResponse::error(503, "unavailable"),
+        });
+        let up = node(100, accepts("TX"));
+        let pool = self::pool(&[&failing.url, &up.url]);
+        pool.health_check().await;
+
+        let wait = "/v2/status/wait-for-block-after/100";
+        assert_eq!(100, pool.status_after_block(100).await.unwrap().last_round);
+        assert_eq!(100, pool.status_after_block(100).await.unwrap().last_round);
+        assert_eq!(1, requested(&failing, wait));
+        assert_eq!(2, requested(&up, wait));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_fail_over_on_rejections() {
+        let first = TestServer::start(|request| match request.path.as_str() {
+            "/health" => TestResponse::json(""),
+            "/v2/status" => TestResponse::json(&status(100)),
+            _ => TestResponse::error(404, "application does not exist"),
+        });
+        let second = node(100, accepts("TX"));
+        let pool = pool(&[&first.url, &second.url]);
+
+        let err = pool.app(1).await.unwrap_err();
+
+        assert!(err.is_404());
+        assert_eq!(0, requested(&second, "/v2/applications/1"));
+    }
+
+    #[tokio::test]
+    async fn test_broadcasts_transactions() {
+        let nodes = [
+            node(100, accepts("TX")),
+            node(101, accepts("TX")),
+            node(
+                101,
+                TestResponse::error(400, "transaction already in ledger: TX"),
+            ),
+        ];
+        let down = unreachable_url();
+        let pool = pool(&[&nodes[0].url, &nodes[1].url, &nodes[2].url, &down]);
+
+        let res = pool.send_raw_txn(&[1, 2, 3]).await.unwrap();
+
+        assert_eq!("TX", res.tx_id);
+        for node in &nodes {
+            assert_eq!(1, requested(node, "/v2/transactions"));
+        }
+
+        // only to the most caught-up nodes
+        let pool = pool.broadcast_to(2);
+        pool.send_raw_txn(&[1, 2, 3]).await.unwrap();
+        assert_eq!(1, requested(&nodes[0], "/v2/transactions"));
+        assert_eq!(2, requested(&nodes[1], "/v2/transactions"));
+        assert_eq!(2, requested(&nodes[2], "/v2/transactions"));
+    }
+
+    #[tokio::test]
+    async fn test_reports_rejection_if_no_node_accepts() {
+        let rejecting = node(100, TestResponse::error(400, "overspend"));
+        let down = unreachable_url();
+        let pool = pool(&[&down, &rejecting.url]);
+
+        let err = pool.send_raw_txn(&[1, 2, 3]).await.unwrap_err();
+
+        assert!(matches!(err, Error::Algod(rejection) if rejection.status == 400));
+    }
+}
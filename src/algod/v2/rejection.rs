@@ -0,0 +1,384 @@
+use std::fmt::{self, Display, Formatter};
+
+/// An error response of algod, e.g. a rejected transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlgodRejection {
+    /// HTTP status of the response, 200 for pool errors of pending transactions.
+    pub status: u16,
+    /// The message returned by algod.
+    pub message: String,
+    /// The cause, parsed from the message.
+    pub cause: AlgodErrorCause,
+}
+
+impl AlgodRejection {
+    pub fn new(status: u16, message: String) -> AlgodRejection {
+        AlgodRejection {
+            status,
+            cause: AlgodErrorCause::parse(&message),
+            message,
+        }
+    }
+}
+
+impl Display for AlgodRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.status, self.message)
+    }
+}
+
+/// Why algod rejected a request.
+///
+/// Algod returns causes only as text: fields that aren't found in the message are `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlgodErrorCause {
+    /// An app call or logic signature failed.
+    LogicEval {
+        tx_id: Option<String>,
+        /// Index of the failed transaction in its group. Set by
+        /// [AtomicTransactionComposer](crate::atomic_transaction_composer::AtomicTransactionComposer),
+        /// which knows the group.
+        txn_index: Option<usize>,
+        /// The app, `None` for logic signatures.
+        app_id: Option<u64>,
+        pc: Option<u64>,
+        /// The error of the failing opcode, e.g. `assert failed pc=12`.
+        message: String,
+    },
+    /// The sender's balance doesn't cover the amount and fee.
+    Overspend {
+        tx_id: Option<String>,
+        account: Option<String>,
+        /// Amount (including the fee) the transaction tried to spend, in microalgos.
+        tried_to_spend: Option<u64>,
+    },
+    /// The transaction would leave an account below its minimum balance.
+    BelowMinBalance {
+        tx_id: Option<String>,
+        account: Option<String>,
+        balance: Option<u64>,
+        min_balance: Option<u64>,
+    },
+    /// The transaction's last valid round has passed.
+    TxnDead {
+        round: u64,
+        first_valid: u64,
+        last_valid: u64,
+    },
+    /// The transaction's first valid round hasn't been reached yet.
+    TxnNotYetValid {
+        round: u64,
+        first_valid: u64,
+        last_valid: u64,
+    },
+    /// The transaction was already committed.
+    AlreadyInLedger { tx_id: Option<String> },
+    /// The fee (or the group's total fee) is too small.
+    FeeTooSmall {
+        tx_id: Option<String>,
+        fee: Option<u64>,
+        min_fee: Option<u64>,
+    },
+    /// Another valid transaction of the sender uses the same lease.
+    LeaseInUse {
+        tx_id: Option<String>,
+        sender: Option<String>,
+    },
+    /// Any other error.
+    Other,
+}
+
+impl AlgodErrorCause {
+    /// Parses the cause from an algod error message.
+    pub fn parse(message: &str) -> AlgodErrorCause {
+        // the same text is returned for transactions evaluated before and after their validity range
+        if let Some(dead) = after(message, "txn dead: round ") {
+            if let (Some(round), Some(first_valid), Some(last_valid)) = (
+                number(dead),
+                number_after(dead, "outside of "),
+                number_after(dead, "--"),
+            ) {
+                return if round < first_valid {
+                    AlgodErrorCause::TxnNotYetValid {
+                        round,
+                        first_valid,
+                        last_valid,
+                    }
+                } else {
+                    AlgodErrorCause::TxnDead {
+                        round,
+                        first_valid,
+                        last_valid,
+                    }
+                };
+            }
+        }
+        if let Some(tx_id) = after(message, "transaction already in ledger") {
+            return AlgodErrorCause::AlreadyInLedger {
+                tx_id: word_after(tx_id, ": "),
+            };
+        }
+
+        let tx_id = word_after(message, "transaction ");
+
+        if let Some(error) = after(message, "logic eval error: ")
+            .or_else(|| after(message, "rejected by logic err="))
+        {
+            let (error, details) = match error.find(". Details: ") {
+                Some(i) => (&error[..i], &error[i + ". Details: ".len()..]),
+                None => (error, ""),
+            };
+            return AlgodErrorCause::LogicEval {
+                tx_id,
+                txn_index: None,
+                app_id: number_after(details, "app="),
+                pc: number_after(details, "pc=").or_else(|| number_after(error, "pc=")),
+                message: error.to_owned(),
+            };
+        }
+        if let Some(overspend) = after(message, "overspend") {
+            return AlgodErrorCause::Overspend {
+                tx_id,
+                account: word_after(overspend, "(account "),
+                tried_to_spend: number_after(overspend, "tried to spend {"),
+            };
+        }
+        if message.contains(" below min ") && message.contains(" balance ") {
+            return AlgodErrorCause::BelowMinBalance {
+                tx_id,
+                account: word_after(message, "account "),
+                balance: number_after(message, " balance "),
+                min_balance: number_after(message, " below min "),
+            };
+        }
+        if let Some(lease) = after(message, "using an overlapping lease") {
+            return AlgodErrorCause::LeaseInUse {
+                tx_id,
+                sender: word_after(lease, ":("),
+            };
+        }
+        if let Some(fee) = after(message, "fee ").filter(|fee| fee.contains(" below threshold ")) {
+            return AlgodErrorCause::FeeTooSmall {
+                tx_id,
+                fee: number(fee),
+                min_fee: number_after(fee, " below threshold "),
+            };
+        }
+        if let Some(min_fee) = after(message, "which is less than the minimum ") {
+            // "had fee <fee>, ..." or, for groups, "had <fee> in fees, ... minimum <n> * <min_fee>"
+            let fee = number_after(message, "had fee ").or_else(|| number_after(message, "had "));
+            let min_fee = match (number(min_fee), number_after(min_fee, "* ")) {
+                (Some(count), Some(min_fee)) => count.checked_mul(min_fee),
+                (min_fee, None) => min_fee,
+                _ => None,
+            };
+            return AlgodErrorCause::FeeTooSmall {
+                tx_id,
+                fee,
+                min_fee,
+            };
+        }
+
+        AlgodErrorCause::Other
+    }
+
+    /// The id of the transaction that caused the error, if known.
+    pub fn tx_id(&self) -> Option<&str> {
+        match self {
+            AlgodErrorCause::LogicEval { tx_id, .. }
+            | AlgodErrorCause::Overspend { tx_id, .. }
+            | AlgodErrorCause::BelowMinBalance { tx_id, .. }
+            | AlgodErrorCause::AlreadyInLedger { tx_id }
+            | AlgodErrorCause::FeeTooSmall { tx_id, .. }
+            | AlgodErrorCause::LeaseInUse { tx_id, .. } => tx_id.as_deref(),
+            AlgodErrorCause::TxnDead { .. }
+            | AlgodErrorCause::TxnNotYetValid { .. }
+            | AlgodErrorCause::Other => None,
+        }
+    }
+}
+
+/// The text following the first occurrence of `prefix`.
+fn after<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.find(prefix).map(|i| &text[i + prefix.len()..])
+}
+
+/// The number at the start of `text`.
+fn number(text: &str) -> Option<u64> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+fn number_after(text: &str, prefix: &str) -> Option<u64> {
+    after(text, prefix).and_then(number)
+}
+
+/// The alphanumeric word (e.g. a transaction id or address) following `prefix`.
+fn word_after(text: &str, prefix: &str) -> Option<String> {
+    let word = after(text, prefix)?;
+    let end = word
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(word.len());
+    // skips prose like "transaction had fee"
+    if end > 0
+        && word[..end]
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        Some(word[..end].to_owned())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX_ID: &str = "NX4GUKFOS5VPZKQ5ZL7YH6RPSJLYWBWUBJM3XXA4R6BT5V4JTXPQ";
+    const ADDRESS: &str = "7ZUECA7HFLZTXENRV24SHLU4AVPUTMTTDUFUBNBD64C73F3UHRTHAIOF6Q";
+
+    #[test]
+    fn test_parses_logic_eval_errors() {
+        let cause = AlgodErrorCause::parse(&format!(
+            "TransactionPool.Remember: transaction {TX_ID}: logic eval error: assert failed pc=12. Details: app=1005, pc=12, opcodes=intc_1 // 0\n==\nassert"
+        ));
+
+        assert_eq!(
+            AlgodErrorCause::LogicEval {
+                tx_id: Some(TX_ID.to_owned()),
+                txn_index: None,
+                app_id: Some(1005),
+                pc: Some(12),
+                message: "assert failed pc=12".to_owned(),
+            },
+            cause
+        );
+
+        let cause = AlgodErrorCause::parse(&format!(
+            "TransactionPool.Remember: transaction {TX_ID}: rejected by logic err=err opcode executed. Details: pc=3"
+        ));
+
+        assert_eq!(
+            AlgodErrorCause::LogicEval {
+                tx_id: Some(TX_ID.to_owned()),
+                txn_index: None,
+                app_id: None,
+                pc: Some(3),
+                message: "err opcode executed".to_owned(),
+            },
+            cause
+        );
+    }
+
+    #[test]
+    fn test_parses_balance_errors() {
+        let cause = AlgodErrorCause::parse(&format!(
+            "TransactionPool.Remember: transaction {TX_ID}: overspend (account {ADDRESS}, data {{_struct:{{}} Status:Offline MicroAlgos:{{Raw:1000}}}}, tried to spend {{1001000}})"
+        ));
+        assert_eq!(
+            AlgodErrorCause::Overspend {
+                tx_id: Some(TX_ID.to_owned()),
+                account: Some(ADDRESS.to_owned()),
+                tried_to_spend: Some(1001000),
+            },
+            cause
+        );
+
+        let cause = AlgodErrorCause::parse(&format!(
+            "TransactionPool.Remember: transaction {TX_ID}: account {ADDRESS} balance 100000 below min 200000 (1 assets)"
+        ));
+        assert_eq!(
+            AlgodErrorCause::BelowMinBalance {
+                tx_id: Some(TX_ID.to_owned()),
+                account: Some(ADDRESS.to_owned()),
+                balance: Some(100000),
+                min_balance: Some(200000),
+            },
+            cause
+        );
+    }
+
+    #[test]
+    fn test_parses_validity_errors() {
+        assert_eq!(
+            AlgodErrorCause::TxnDead {
+                round: 1000,
+                first_valid: 500,
+                last_valid: 900
+            },
+            AlgodErrorCause::parse(
+                "TransactionPool.Remember: txn dead: round 1000 outside of 500--900"
+            )
+        );
+        assert_eq!(
+            AlgodErrorCause::TxnNotYetValid {
+                round: 400,
+                first_valid: 500,
+                last_valid: 900
+            },
+            AlgodErrorCause::parse(
+                "TransactionPool.Remember: txn dead: round 400 outside of 500--900"
+            )
+        );
+        assert_eq!(
+            AlgodErrorCause::AlreadyInLedger {
+                tx_id: Some(TX_ID.to_owned())
+            },
+            AlgodErrorCause::parse(&format!(
+                "TransactionPool.Remember: transaction already in ledger: {TX_ID}"
+            ))
+        );
+        assert_eq!(
+            AlgodErrorCause::LeaseInUse {
+                tx_id: Some(TX_ID.to_owned()),
+                sender: Some(ADDRESS.to_owned()),
+            },
+            AlgodErrorCause::parse(&format!(
+                "TransactionPool.Remember: transaction {TX_ID} using an overlapping lease (sender, lease):({ADDRESS}, [1 2 3])"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parses_fee_errors() {
+        assert_eq!(
+            AlgodErrorCause::FeeTooSmall {
+                tx_id: Some(TX_ID.to_owned()),
+                fee: Some(10),
+                min_fee: Some(1000),
+            },
+            AlgodErrorCause::parse(&format!(
+                "TransactionPool.checkSufficientFee: transaction {TX_ID}: fee 10 below threshold 1000 (242 bytes * fee/byte 0)"
+            ))
+        );
+        assert_eq!(
+            AlgodErrorCause::FeeTooSmall {
+                tx_id: None,
+                fee: Some(1000),
+                min_fee: Some(2000),
+            },
+            AlgodErrorCause::parse(
+                "TransactionPool.Remember: txgroup had 1000 in fees, which is less than the minimum 2 * 1000"
+            )
+        );
+        assert_eq!(
+            AlgodErrorCause::FeeTooSmall {
+                tx_id: None,
+                fee: Some(10),
+                min_fee: Some(1000),
+            },
+            AlgodErrorCause::parse("transaction had fee 10, which is less than the minimum 1000")
+        );
+    }
+
+    #[test]
+    fn test_other_errors() {
+        assert_eq!(
+            AlgodErrorCause::Other,
+            AlgodErrorCause::parse("failed to retrieve information from the ledger")
+        );
+    }
+}
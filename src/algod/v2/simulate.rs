@@ -0,0 +1,209 @@
+use super::Algod;
+use crate::Error;
+use algonaut_algod::models::{
+    SimulateRequest, SimulateRequestTransactionGroup, SimulateTraceConfig,
+    SimulateTransaction200Response,
+};
+use algonaut_model::transaction::ApiSignedTransaction;
+use algonaut_transaction::{SignedTransaction, Transaction};
+use std::convert::{TryFrom, TryInto};
+
+/// Builds a simulate request and sends it to algod.
+///
+/// Created with [Algod::simulate].
+#[derive(Debug, Clone)]
+pub struct SimulateBuilder<'a> {
+    algod: &'a Algod,
+    request: SimulateRequest,
+}
+
+impl<'a> SimulateBuilder<'a> {
+    pub(super) fn new(algod: &'a Algod) -> Self {
+        SimulateBuilder {
+            algod,
+            request: SimulateRequest::default(),
+        }
+    }
+
+    /// Adds a signed transaction group to simulate.
+    pub fn group(mut self, txns: &[SignedTransaction]) -> Result<Self, Error> {
+        let txns = txns
+            .iter()
+            .map(|t| ApiSignedTransaction::try_from(t.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.request
+            .txn_groups
+            .push(SimulateRequestTransactionGroup::new(txns));
+        Ok(self)
+    }
+
+    /// Adds a transaction group to simulate without signatures.
+    ///
+    /// This allows empty signatures for the request.
+    pub fn unsigned_group(mut self, txns: &[Transaction]) -> Result<Self, Error> {
+        let txns = txns
+            .iter()
+            .map(|t| empty_signed_tx(t.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.request
+            .txn_groups
+            .push(SimulateRequestTransactionGroup::new(txns));
+        Ok(self.allow_empty_signatures(true))
+    }
+
+    /// Simulates transactions without signatures as if they were correctly signed.
+    pub fn allow_empty_signatures(mut self, allow: bool) -> Self {
+        self.request.allow_empty_signatures = Some(allow);
+        self
+    }
+
+    /// Lifts the limits on log opcode usage.
+    pub fn allow_more_logging(mut self, allow: bool) -> Self {
+        self.request.allow_more_logging = Some(allow);
+        self
+    }
+
+    /// Allows access to resources missing in the transactions' reference arrays.
+    /// The accessed resources are reported in the response.
+    pub fn allow_unnamed_resources(mut self, allow: bool) -> Self {
+        self.request.allow_unnamed_resources = Some(allow);
+        self
+    }
+
+    /// Adds opcode budget to each simulated group.
+    pub fn extra_opcode_budget(mut self, budget: u64) -> Self {
+        self.request.extra_opcode_budget = Some(budget);
+        self
+    }
+
+    /// Simulates with the state after the given round, instead of the latest round.
+    pub fn round(mut self, round: u64) -> Self {
+        self.request.round = Some(round);
+        self
+    }
+
+    /// Configures which execution trace information is returned.
+    pub fn exec_trace_config(mut self, config: SimulateTraceConfig) -> Self {
+        self.request.exec_trace_config = Some(config);
+        self
+    }
+
+    /// The request that will be sent.
+    pub fn request(&self) -> &SimulateRequest {
+        &self.request
+    }
+
+    /// Sends the request to algod.
+    pub async fn send(self) -> Result<SimulateTransaction200Response, Error> {
+        self.algod.simulate_txns(self.request).await
+    }
+}
+
+/// Wraps a transaction without signature, to be simulated with empty signatures allowed.
+fn empty_signed_tx(tx: Transaction) -> Result<ApiSignedTransaction, Error> {
+    Ok(ApiSignedTransaction {
+        lsig: None,
+        msig: None,
+        sig: None,
+        transaction_id: tx.id()?,
+        transaction: tx.try_into()?,
+        auth_address: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_fixtures::payment;
+    use algonaut_algod::models::SimulateTransactionResult;
+    use algonaut_transaction::account::Account;
+
+    #[test]
+    fn test_builds_request_with_options() {
+        let algod = Algod::new("http://example.com", "").unwrap();
+        let account = Account::generate();
+        let tx = payment(&account.address(), 1);
+        let signed_tx = account.sign_transaction(tx.clone()).unwrap();
+        let tx_id = tx.id().unwrap();
+
+        let builder = algod
+            .simulate()
+            .group(&[signed_tx])
+            .unwrap()
+            .unsigned_group(&[tx])
+            .unwrap()
+            .allow_more_logging(true)
+            .allow_unnamed_resources(true)
+            .extra_opcode_budget(700)
+            .round(10)
+            .exec_trace_config(SimulateTraceConfig {
+                enable: Some(true),
+                ..SimulateTraceConfig::default()
+            });
+        let request = builder.request();
+
+        assert_eq!(2, request.txn_groups.len());
+        assert!(request.txn_groups[0].txns[0].sig.is_some());
+        assert!(request.txn_groups[1].txns[0].sig.is_none());
+        assert_eq!(tx_id, request.txn_groups[1].txns[0].transaction_id);
+        assert_eq!(Some(true), request.allow_empty_signatures);
+        assert_eq!(Some(true), request.allow_more_logging);
+        assert_eq!(Some(true), request.allow_unnamed_resources);
+        assert_eq!(Some(700), request.extra_opcode_budget);
+        assert_eq!(Some(10), request.round);
+        assert_eq!(
+            Some(true),
+            request.exec_trace_config.as_ref().and_then(|c| c.enable)
+        );
+    }
+
+    #[test]
+    fn test_decodes_exec_trace_and_unnamed_resources() {
+        let json = serde_json::json!({
+            "exec-trace": {
+                "approval-program-hash": "AQID",
+                "approval-program-trace": [
+                    { "pc": 1 },
+                    {
+                        "pc": 4,
+                        "stack-additions": [{ "type": 2, "uint": 5 }],
+                        "scratch-changes": [{ "slot": 1, "new-value": { "type": 1, "bytes": "AQ==" } }],
+                        "state-changes": [{
+                            "app-state-type": "b",
+                            "key": "Ym94",
+                            "operation": "w",
+                            "new-value": { "type": 1, "bytes": "AQ==" }
+                        }]
+                    }
+                ]
+            },
+            "unnamed-resources-accessed": {
+                "accounts": ["AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAY5HFKQ"],
+                "assets": [10],
+                "boxes": [{ "app": 1, "name": "Ym94" }],
+                "asset-holdings": [{
+                    "account": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAY5HFKQ",
+                    "asset": 10
+                }]
+            },
+            "txn-result": { "pool-error": "", "txn": {} }
+        })
+        .to_string();
+
+        let res: SimulateTransactionResult = serde_json::from_str(&json).unwrap();
+
+        let trace = res.exec_trace.unwrap();
+        assert_eq!(vec![1, 2, 3], trace.approval_program_hash.unwrap().0);
+        let units = trace.approval_program_trace.unwrap();
+        assert_eq!(2, units.len());
+        assert_eq!(Some(5), units[1].stack_additions.as_ref().unwrap()[0].uint);
+        assert_eq!(
+            b"box".to_vec(),
+            units[1].state_changes.as_ref().unwrap()[0].key.0
+        );
+        let resources = res.unnamed_resources_accessed.unwrap();
+        assert_eq!(Some(vec![10]), resources.assets);
+        assert_eq!(b"box".to_vec(), resources.boxes.unwrap()[0].name.0);
+        assert_eq!(10, resources.asset_holdings.unwrap()[0].asset);
+    }
+}
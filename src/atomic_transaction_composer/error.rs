@@ -0,0 +1,108 @@
+use algonaut_core::TransactionTypeEnum;
+use thiserror::Error;
+
+use super::{AtomicTransactionComposerStatus, MAX_ATOMIC_GROUP_SIZE};
+
+/// An error of the [AtomicTransactionComposer](super::AtomicTransactionComposer) or its signers.
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ComposerError {
+    /// The composer's status doesn't allow the operation, e.g. adding transactions to a built group.
+    #[error("can't {} with status: {:?}", operation, status)]
+    InvalidStatus {
+        operation: &'static str,
+        status: AtomicTransactionComposerStatus,
+    },
+    /// The group would have more transactions than allowed.
+    #[error(
+        "group size: {} exceeds max group size: {}",
+        size,
+        MAX_ATOMIC_GROUP_SIZE
+    )]
+    GroupSizeExceeded { size: usize },
+    /// The composer has no transactions to build a group with.
+    #[error("should not build transaction group with 0 transactions in composer")]
+    EmptyGroup,
+    /// A method call got a different number of arguments than the method declares.
+    #[error(
+        "incorrect number of arguments were provided: {} != {}",
+        provided,
+        expected
+    )]
+    ArgCount { provided: usize, expected: usize },
+    /// A method argument value doesn't match its declared type.
+    #[error("invalid arg value: {}, expected: {}", value, expected)]
+    InvalidArgValue { expected: String, value: String },
+    /// A transaction added to the composer already has a group id.
+    #[error("expected empty group id")]
+    UnexpectedGroupId,
+    /// A transaction argument has a different type than the method declares.
+    #[error(
+        "expected transaction with type {:?}, but got type {:?}",
+        expected,
+        actual
+    )]
+    TransactionType {
+        expected: TransactionTypeEnum,
+        actual: TransactionTypeEnum,
+    },
+    /// A signer was asked to sign a transaction outside of the group.
+    #[error(
+        "index to sign: {} out of bounds for group of size: {}",
+        index,
+        group_size
+    )]
+    SignIndexOutOfBounds { index: usize, group_size: usize },
+    /// A signer returned a different number of signed transactions than it was asked to sign.
+    #[error(
+        "signer returned {} signed transactions, expected: {}",
+        returned,
+        expected
+    )]
+    SignerCount { returned: usize, expected: usize },
+    /// A signer returned a signed transaction that isn't the transaction at `index`.
+    #[error(
+        "signed transaction at index: {} doesn't match the transaction to sign",
+        index
+    )]
+    SignedTransactionMismatch { index: usize },
+    /// A signer returned a signed transaction with a wrong id.
+    #[error(
+        "signed transaction id: {} at index: {} doesn't match expected id: {}",
+        id,
+        index,
+        expected
+    )]
+    SignedTransactionId {
+        index: usize,
+        id: String,
+        expected: String,
+    },
+    /// The kmd signer's wallet doesn't exist.
+    #[error("kmd wallet not found: {0}")]
+    KmdWalletNotFound(String),
+    /// The kmd signer's wallet has no keys of the sender's multisig.
+    #[error("kmd wallet: {} has no keys for multisig: {}", wallet, address)]
+    KmdNoMultisigKeys { wallet: String, address: String },
+    /// Kmd returned a signature that couldn't be decoded.
+    #[error("couldn't decode kmd signature: {0}")]
+    KmdSignature(String),
+    /// An ABI type or value couldn't be encoded or decoded.
+    #[error("abi error: {0}")]
+    Abi(String),
+    /// An app call didn't log an ABI return value as its last log.
+    #[error("app call transaction did not log a return value")]
+    MissingReturnValue,
+    /// The simulate response doesn't have a result for every transaction of the group.
+    #[error(
+        "simulate response has {} transaction results, expected: {}",
+        returned,
+        expected
+    )]
+    SimulateResultCount { returned: usize, expected: usize },
+    /// The simulate response has no result for the simulated group.
+    #[error("simulate response has no group result")]
+    MissingSimulateGroupResult,
+    /// The simulated group failed, e.g. because a transaction was rejected.
+    #[error("simulation failed: {0}")]
+    SimulationFailed(String),
+}
@@ -1,3 +1,4 @@
+pub mod error;
 pub mod transaction_signer;
 
 use algonaut_abi::{
@@ -8,7 +9,9 @@ use algonaut_abi::{
     abi_type::{AbiType, AbiValue},
     make_tuple_type,
 };
-use algonaut_algod::models::{PendingTransactionResponse, TransactionParams200Response};
+use algonaut_algod::models::{
+    PendingTransactionResponse, SimulateTransaction200Response, TransactionParams200Response,
+};
 use algonaut_core::{Address, CompiledTeal, MicroAlgos};
 use algonaut_crypto::HashDigest;
 use algonaut_transaction::{
@@ -20,14 +23,22 @@ use algonaut_transaction::{
     tx_group::TxGroup,
     SignedTransaction, Transaction, TransactionType, TxnBuilder,
 };
-use data_encoding::BASE64;
 use num_bigint::BigUint;
 use num_traits::ToPrimitive;
-use std::collections::HashMap;
-
-use crate::{algod::v2::Algod, util::wait_for_pending_tx::wait_for_pending_transaction, Error};
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    algod::v2::{rejection::AlgodErrorCause, Algod},
+    util::{
+        app_call_resources::populate_app_call_resources,
+        fee_planner::{FeePlan, GroupFeePlanner},
+        single_group_result,
+        wait_for_pending_tx::wait_for_pending_transaction,
+    },
+    Error,
+};
 
-use self::transaction_signer::TransactionSigner;
+use self::{error::ComposerError, transaction_signer::TransactionSigner};
 
 /// 4-byte prefix for logged return values, from https://github.com/algorandfoundation/ARCs/blob/main/ARCs/arc-0004.md#standard-format
 const ABI_RETURN_HASH: [u8; 4] = [0x15, 0x1f, 0x7c, 0x75];
@@ -46,7 +57,7 @@ pub struct TransactionWithSigner {
     /// An unsigned transaction
     pub tx: Transaction,
     /// A transaction signer that can authorize the transaction
-    pub signer: TransactionSigner,
+    pub signer: Arc<dyn TransactionSigner>,
 }
 
 /// Represents the output from a successful ABI method call.
@@ -105,7 +116,7 @@ pub struct AddMethodCallParams {
     /// If provided, the address that the sender will be rekeyed to at the conclusion of this application call
     pub rekey_to: Option<Address>,
     /// A transaction Signer that can authorize this application call from sender
-    pub signer: TransactionSigner,
+    pub signer: Arc<dyn TransactionSigner>,
     /// A list of boxes that the app call has access to
     pub boxes: Option<Vec<BoxReference>>,
 }
@@ -123,6 +134,50 @@ pub struct ExecuteResult {
     pub method_results: Vec<AbiMethodResult>,
 }
 
+#[derive(Debug, Clone)]
+/// SimulateResult contains the results of calling the Simulate method on an
+/// AtomicTransactionComposer object.
+pub struct SimulateResult {
+    /// Whether the simulated group would have succeeded if it was submitted.
+    /// This is false if the group was simulated with empty signatures.
+    pub would_succeed: bool,
+    /// The round immediately preceding the simulation
+    pub simulate_round: u64,
+    /// A list of the TxIDs for each transaction in the simulated group
+    pub tx_ids: Vec<String>,
+    /// Results for each transaction in the simulated group
+    pub tx_results: Vec<SimulateTxResult>,
+    /// Return values for all the ABI method calls in the simulated group.
+    /// Calls that didn't log a return value (e.g. because the group failed) have an error return value.
+    pub method_results: Vec<AbiMethodResult>,
+    /// Total budget added during execution of app calls in the group
+    pub app_budget_added: Option<u64>,
+    /// Total budget consumed during execution of app calls in the group
+    pub app_budget_consumed: Option<u64>,
+    /// The complete algod simulate response
+    pub simulate_response: SimulateTransaction200Response,
+}
+
+#[derive(Debug, Clone)]
+/// The simulation result of a single transaction of the group.
+pub struct SimulateTxResult {
+    /// The TxID of the transaction
+    pub tx_id: String,
+    /// Why the group failed, if it failed because of this transaction
+    pub failure_message: Option<String>,
+    /// If the group failed because of this transaction, the path to the failing transaction,
+    /// starting with this transaction's index and followed by the indexes of inner transactions.
+    pub failed_at: Option<Vec<u64>>,
+    /// Budget used by the app call, including inner app calls
+    pub app_budget_consumed: Option<u64>,
+    /// Budget used by the logic signature
+    pub logic_sig_budget_consumed: Option<u64>,
+    /// Whether the transaction was simulated without a signature
+    pub missing_signature: bool,
+    /// The evaluation result of the transaction
+    pub tx_info: PendingTransactionResponse,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AtomicTransactionComposerStatus {
     /// The atomic group is still under construction.
@@ -230,15 +285,18 @@ impl AtomicTransactionComposer {
     /// or if adding this transaction causes the current group to exceed MaxAtomicGroupSize.
     pub fn add_transaction(&mut self, txn_with_signer: TransactionWithSigner) -> Result<(), Error> {
         if self.status != AtomicTransactionComposerStatus::Building {
-            return Err(Error::Msg(
-                "status must be BUILDING in order to add transactions".to_owned(),
-            ));
+            return Err(ComposerError::InvalidStatus {
+                operation: "add transactions",
+                status: self.status,
+            }
+            .into());
         }
 
         if self.len() == MAX_ATOMIC_GROUP_SIZE {
-            return Err(Error::Msg(format!(
-                "reached max group size: {MAX_ATOMIC_GROUP_SIZE}"
-            )));
+            return Err(ComposerError::GroupSizeExceeded {
+                size: self.len() + 1,
+            }
+            .into());
         }
 
         validate_tx(&txn_with_signer.tx, TransactionArgType::Any)?;
@@ -250,21 +308,24 @@ impl AtomicTransactionComposer {
 
     pub fn add_method_call(&mut self, params: &mut AddMethodCallParams) -> Result<(), Error> {
         if self.status != AtomicTransactionComposerStatus::Building {
-            return Err(Error::Msg(
-                "status must be BUILDING in order to add transactions".to_owned(),
-            ));
+            return Err(ComposerError::InvalidStatus {
+                operation: "add transactions",
+                status: self.status,
+            }
+            .into());
         }
         if params.method_args.len() != params.method.args.len() {
-            return Err(Error::Msg(format!(
-                "incorrect number of arguments were provided: {} != {}",
-                params.method_args.len(),
-                params.method.args.len()
-            )));
+            return Err(ComposerError::ArgCount {
+                provided: params.method_args.len(),
+                expected: params.method.args.len(),
+            }
+            .into());
         }
         if self.len() + params.method.get_tx_count() > MAX_ATOMIC_GROUP_SIZE {
-            return Err(Error::Msg(format!(
-                "reached max group size: {MAX_ATOMIC_GROUP_SIZE}"
-            )));
+            return Err(ComposerError::GroupSizeExceeded {
+                size: self.len() + params.method.get_tx_count(),
+            }
+            .into());
         }
 
         let mut method_types = vec![];
@@ -355,6 +416,54 @@ impl AtomicTransactionComposer {
         Ok(())
     }
 
+    /// Fills the reference arrays of the app calls in this composer with the resources they access,
+    /// by simulating the group with unnamed resources allowed.
+    ///
+    /// References are appended, so the indexes of the existing references (e.g. ABI arguments) stay valid.
+    /// An error will be thrown if the composer's status is not Building.
+    pub async fn populate_app_call_resources(&mut self, algod: &Algod) -> Result<(), Error> {
+        if self.status != AtomicTransactionComposerStatus::Building {
+            return Err(ComposerError::InvalidStatus {
+                operation: "populate app call resources",
+                status: self.status,
+            }
+            .into());
+        }
+
+        let txs: Vec<Transaction> = self.txs.iter().map(|t| t.tx.clone()).collect();
+        let populated_txs = populate_app_call_resources(algod, &txs).await?;
+        for (tx_with_signer, tx) in self.txs.iter_mut().zip(populated_txs) {
+            tx_with_signer.tx = tx;
+        }
+        Ok(())
+    }
+
+    /// Raises the fee of the planner's fee payer to cover the inner transactions issued by the
+    /// app calls in this composer, by simulating the group.
+    ///
+    /// An error will be thrown if the composer's status is not Building.
+    pub async fn cover_inner_tx_fees(
+        &mut self,
+        algod: &Algod,
+        planner: &GroupFeePlanner,
+    ) -> Result<FeePlan, Error> {
+        if self.status != AtomicTransactionComposerStatus::Building {
+            return Err(ComposerError::InvalidStatus {
+                operation: "cover inner transaction fees",
+                status: self.status,
+            }
+            .into());
+        }
+
+        let mut txs: Vec<Transaction> = self.txs.iter().map(|t| t.tx.clone()).collect();
+        let plan = planner.plan(algod, &txs).await?;
+        plan.apply(&mut txs)?;
+        for (tx_with_signer, tx) in self.txs.iter_mut().zip(txs) {
+            tx_with_signer.tx = tx;
+        }
+        Ok(plan)
+    }
+
     /// Finalize the transaction group and returned the finalized transactions.
     /// The composer's status will be at least BUILT after executing this method.
     pub fn build_group(&mut self) -> Result<Vec<TransactionWithSigner>, Error> {
@@ -363,9 +472,7 @@ impl AtomicTransactionComposer {
         }
 
         if self.txs.is_empty() {
-            return Err(Error::Msg(
-                "should not build transaction group with 0 transactions in composer".to_owned(),
-            ));
+            return Err(ComposerError::EmptyGroup.into());
         } else if self.txs.len() > 1 {
             let mut group_txs = vec![];
             for tx in self.txs.iter_mut() {
@@ -378,17 +485,23 @@ impl AtomicTransactionComposer {
         Ok(self.txs.clone())
     }
 
-    pub fn gather_signatures(&mut self) -> Result<Vec<SignedTransaction>, Error> {
+    /// Obtain signatures for each transaction in this group, calling each signer once with all
+    /// the transactions it's responsible for. Signers are identified by their `Arc` pointer,
+    /// so transactions that should be signed together have to share the same signer instance.
+    ///
+    /// The signed transactions are returned in group order.
+    /// The composer's status will be at least SIGNED after executing this method.
+    pub async fn gather_signatures(&mut self) -> Result<Vec<SignedTransaction>, Error> {
         if self.status >= AtomicTransactionComposerStatus::Signed {
             return Ok(self.signed_txs.clone());
         }
 
         let tx_and_signers = self.build_group()?;
 
-        let txs: Vec<Transaction> = self.txs.clone().into_iter().map(|t| t.tx).collect();
+        let txs: Vec<Transaction> = tx_and_signers.iter().map(|t| t.tx.clone()).collect();
 
         let mut visited = vec![false; txs.len()];
-        let mut signed_txs = vec![];
+        let mut signed_txs: Vec<Option<SignedTransaction>> = vec![None; txs.len()];
 
         for (i, tx_with_signer) in tx_and_signers.iter().enumerate() {
             if visited[i] {
@@ -398,25 +511,34 @@ impl AtomicTransactionComposer {
             let mut indices_to_sign = vec![];
 
             for (j, other) in tx_and_signers.iter().enumerate() {
-                if !visited[j] && tx_with_signer.signer == other.signer {
+                if !visited[j] && Arc::ptr_eq(&tx_with_signer.signer, &other.signer) {
                     indices_to_sign.push(j);
                     visited[j] = true;
                 }
             }
 
-            if indices_to_sign.is_empty() {
-                return Err(Error::Msg(
-                    "invalid tx signer provided, isn't equal to self".to_owned(),
-                ));
+            let signer_signed_txs = tx_with_signer
+                .signer
+                .sign_transactions(&txs, &indices_to_sign)
+                .await?;
+
+            if signer_signed_txs.len() != indices_to_sign.len() {
+                return Err(ComposerError::SignerCount {
+                    returned: signer_signed_txs.len(),
+                    expected: indices_to_sign.len(),
+                }
+                .into());
             }
 
-            let filtered_tx_group = indices_to_sign
-                .into_iter()
-                .map(|i| txs[i].clone())
-                .collect();
-            signed_txs = tx_with_signer.signer.sign_transactions(filtered_tx_group)?;
+            for (index, signed_tx) in indices_to_sign.into_iter().zip(signer_signed_txs) {
+                validate_signed_tx(&txs[index], &signed_tx, index)?;
+                signed_txs[index] = Some(signed_tx);
+            }
         }
 
+        // every index was visited, so every transaction has been signed
+        let signed_txs: Vec<SignedTransaction> = signed_txs.into_iter().flatten().collect();
+
         self.signed_txs = signed_txs.clone();
 
         self.status = AtomicTransactionComposerStatus::Signed;
@@ -433,23 +555,53 @@ impl AtomicTransactionComposer {
 
     pub async fn submit(&mut self, algod: &Algod) -> Result<Vec<String>, Error> {
         if self.status >= AtomicTransactionComposerStatus::Submitted {
-            return Err(Error::Msg(
-                "Atomic Transaction Composer cannot submit committed transaction".to_owned(),
-            ));
+            return Err(ComposerError::InvalidStatus {
+                operation: "submit",
+                status: self.status,
+            }
+            .into());
         }
 
-        self.gather_signatures()?;
+        self.gather_signatures().await?;
 
-        algod.send_txns(&self.signed_txs).await?;
+        algod
+            .send_txns(&self.signed_txs)
+            .await
+            .map_err(|e| self.with_failed_txn_index(e))?;
 
         self.status = AtomicTransactionComposerStatus::Submitted;
 
         Ok(self.get_txs_ids())
     }
 
+    /// Sets the group index of the transaction that failed evaluation, which algod identifies by id.
+    fn with_failed_txn_index(&self, error: Error) -> Error {
+        match error {
+            Error::Algod(mut rejection) => {
+                if let AlgodErrorCause::LogicEval {
+                    tx_id: Some(tx_id),
+                    txn_index,
+                    ..
+                } = &mut rejection.cause
+                {
+                    *txn_index = self
+                        .signed_txs
+                        .iter()
+                        .position(|t| &t.transaction_id == tx_id);
+                }
+                Error::Algod(rejection)
+            }
+            error => error,
+        }
+    }
+
     pub async fn execute(&mut self, algod: &Algod) -> Result<ExecuteResult, Error> {
         if self.status >= AtomicTransactionComposerStatus::Committed {
-            return Err(Error::Msg("status is already committed".to_owned()));
+            return Err(ComposerError::InvalidStatus {
+                operation: "execute",
+                status: self.status,
+            }
+            .into());
         }
 
         self.submit(algod).await?;
@@ -510,6 +662,100 @@ impl AtomicTransactionComposer {
             method_results: return_list,
         })
     }
+    /// Simulates the transaction group with algod, without submitting it to the network.
+    ///
+    /// If the group hasn't been signed yet, it's simulated with empty signatures, without
+    /// signing it. Otherwise the gathered signatures are used.
+    /// The composer's status will be at least BUILT after executing this method.
+    pub async fn simulate(&mut self, algod: &Algod) -> Result<SimulateResult, Error> {
+        if self.status >= AtomicTransactionComposerStatus::Submitted {
+            return Err(ComposerError::InvalidStatus {
+                operation: "simulate",
+                status: self.status,
+            }
+            .into());
+        }
+
+        let txs: Vec<Transaction> = self.build_group()?.into_iter().map(|t| t.tx).collect();
+        let tx_ids = txs.iter().map(|t| t.id()).collect::<Result<Vec<_>, _>>()?;
+
+        let simulate = if self.status >= AtomicTransactionComposerStatus::Signed {
+            algod.simulate().group(&self.signed_txs)?
+        } else {
+            algod.simulate().unsigned_group(&txs)?
+        };
+        let simulate_response = simulate.send().await?;
+
+        self.to_simulate_result(tx_ids, simulate_response)
+    }
+
+    /// Maps the algod response of simulating this composer's group.
+    fn to_simulate_result(
+        &self,
+        tx_ids: Vec<String>,
+        simulate_response: SimulateTransaction200Response,
+    ) -> Result<SimulateResult, Error> {
+        let group_result = single_group_result(&simulate_response, tx_ids.len())?;
+
+        let failed_tx_index = group_result
+            .failed_at
+            .as_ref()
+            .and_then(|path| path.first())
+            .map(|i| *i as usize);
+
+        let tx_results: Vec<SimulateTxResult> = group_result
+            .txn_results
+            .iter()
+            .zip(tx_ids.iter())
+            .enumerate()
+            .map(|(i, (res, tx_id))| {
+                let failed = failed_tx_index == Some(i);
+                SimulateTxResult {
+                    tx_id: tx_id.clone(),
+                    failure_message: group_result.failure_message.clone().filter(|_| failed),
+                    failed_at: group_result.failed_at.clone().filter(|_| failed),
+                    app_budget_consumed: res.app_budget_consumed,
+                    logic_sig_budget_consumed: res.logic_sig_budget_consumed,
+                    missing_signature: res.missing_signature.unwrap_or(false),
+                    tx_info: (*res.txn_result).clone(),
+                }
+            })
+            .collect();
+
+        let mut method_results = vec![];
+        for (i, tx_result) in tx_results.iter().enumerate() {
+            let method = match self.method_map.get(&i) {
+                Some(method) => method,
+                None => continue,
+            };
+            let return_type = method.returns.clone().type_()?;
+            method_results.push(
+                match get_return_value_with_return_type(
+                    &tx_result.tx_info,
+                    &tx_result.tx_id,
+                    return_type,
+                ) {
+                    Ok(res) => res,
+                    Err(e) => AbiMethodResult {
+                        tx_id: tx_result.tx_id.clone(),
+                        tx_info: tx_result.tx_info.clone(),
+                        return_value: Err(AbiReturnDecodeError(format!("{e:?}"))),
+                    },
+                },
+            );
+        }
+
+        Ok(SimulateResult {
+            would_succeed: simulate_response.would_succeed,
+            simulate_round: simulate_response.last_round,
+            tx_ids,
+            tx_results,
+            method_results,
+            app_budget_added: group_result.app_budget_added,
+            app_budget_consumed: group_result.app_budget_consumed,
+            simulate_response,
+        })
+    }
 }
 
 fn get_return_value_with_return_type(
@@ -540,23 +786,44 @@ impl From<TransactionError> for Error {
 impl From<AbiError> for Error {
     fn from(e: AbiError) -> Self {
         match e {
-            AbiError::Msg(msg) => Self::Msg(msg),
+            AbiError::Msg(msg) => ComposerError::Abi(msg).into(),
         }
     }
 }
 
 fn validate_tx(tx: &Transaction, expected_type: TransactionArgType) -> Result<(), Error> {
     if tx.group.is_some() {
-        return Err(Error::Msg("Expected empty group id".to_owned()));
+        return Err(ComposerError::UnexpectedGroupId.into());
+    }
+
+    if let TransactionArgType::One(expected) = expected_type {
+        let actual = to_tx_type_enum(&tx.txn_type);
+        if expected != actual {
+            return Err(ComposerError::TransactionType { expected, actual }.into());
+        }
     }
 
-    if expected_type != TransactionArgType::Any
-        && expected_type != TransactionArgType::One(to_tx_type_enum(&tx.txn_type))
-    {
-        return Err(Error::Msg(format!(
-            "expected transaction with type {expected_type:?}, but got type {:?}",
-            tx.txn_type
-        )));
+    Ok(())
+}
+
+/// Ensures that a signer returned the signature for the transaction it was asked to sign.
+fn validate_signed_tx(
+    tx: &Transaction,
+    signed_tx: &SignedTransaction,
+    index: usize,
+) -> Result<(), Error> {
+    if &signed_tx.transaction != tx {
+        return Err(ComposerError::SignedTransactionMismatch { index }.into());
+    }
+
+    let tx_id = tx.id()?;
+    if signed_tx.transaction_id != tx_id {
+        return Err(ComposerError::SignedTransactionId {
+            index,
+            id: signed_tx.transaction_id.clone(),
+            expected: tx_id,
+        }
+        .into());
     }
 
     Ok(())
@@ -570,9 +837,11 @@ fn add_tx_arg_type_to_method_call(
     let txn_and_signer = match arg_value {
         AbiArgValue::TxWithSigner(tx_with_signer) => tx_with_signer,
         _ => {
-            return Err(Error::Msg(
-                "invalid arg value, expected transaction".to_owned(),
-            ));
+            return Err(ComposerError::InvalidArgValue {
+                expected: "transaction".to_owned(),
+                value: format!("{arg_value:?}"),
+            }
+            .into());
         }
     };
 
@@ -594,9 +863,11 @@ fn add_abi_obj_arg_to_method_call(
             method_args.push(value.clone());
         }
         AbiArgValue::TxWithSigner(_) => {
-            return Err(Error::Msg(
-                "Invalid state: shouldn't be here with a tx with signer value type".to_owned(),
-            ));
+            return Err(ComposerError::InvalidArgValue {
+                expected: format!("{abi_type:?}"),
+                value: format!("{arg_value:?}"),
+            }
+            .into());
         }
     }
 
@@ -652,9 +923,11 @@ fn add_to_foreign_array(
                 foreign_accounts,
                 Some(sender),
             )),
-            _ => Err(Error::Msg(format!(
-                "Invalid value type: {arg_value:?} for arg type: {arg_type:?}"
-            ))),
+            _ => Err(ComposerError::InvalidArgValue {
+                expected: format!("{arg_type:?}"),
+                value: format!("{arg_value:?}"),
+            }
+            .into()),
         },
         ReferenceArgType::Asset => match arg_value.int() {
             Some(int) => {
@@ -664,9 +937,11 @@ fn add_to_foreign_array(
 
                 Ok(populate_foreign_array(intu64, foreign_assets, None))
             }
-            _ => Err(Error::Msg(format!(
-                "Invalid value type: {arg_value:?} for arg type: {arg_type:?}"
-            ))),
+            _ => Err(ComposerError::InvalidArgValue {
+                expected: format!("{arg_type:?}"),
+                value: format!("{arg_value:?}"),
+            }
+            .into()),
         },
         ReferenceArgType::Application => match arg_value.int() {
             Some(int) => {
@@ -676,9 +951,11 @@ fn add_to_foreign_array(
 
                 Ok(populate_foreign_array(intu64, foreign_apps, Some(app_id)))
             }
-            _ => Err(Error::Msg(format!(
-                "Invalid value type: {arg_value:?} for arg type: {arg_type:?}"
-            ))),
+            _ => Err(ComposerError::InvalidArgValue {
+                expected: format!("{arg_type:?}"),
+                value: format!("{arg_value:?}"),
+            }
+            .into()),
         },
     }
 }
@@ -739,30 +1016,23 @@ fn get_return_value_with_abi_type(
     abi_type: &AbiType,
 ) -> Result<Result<AbiMethodReturnValue, AbiReturnDecodeError>, Error> {
     if pending_tx.logs.is_none() {
-        return Err(Error::Msg(
-            "App call transaction did not log a return value".to_owned(),
-        ));
+        return Err(ComposerError::MissingReturnValue.into());
     }
 
     // safe to unwrap given the previous check
     let logs = &pending_tx.logs.clone().unwrap();
 
     if logs.is_empty() {
-        return Err(Error::Msg(
-            "App call transaction did not log a return value".to_owned(),
-        ));
+        return Err(ComposerError::MissingReturnValue.into());
     }
 
     let ret_line = &logs[logs.len() - 1];
 
-    let decoded_ret_line: Vec<u8> = BASE64
-        .decode(&ret_line.0[..])
-        .map_err(|e| Error::Msg(format!("BASE64 Decoding error: {e:?}")))?;
+    // logs are base64 decoded when deserializing the response
+    let decoded_ret_line: &[u8] = &ret_line.0;
 
-    if !check_log_ret(&decoded_ret_line) {
-        return Err(Error::Msg(
-            "App call transaction did not log a return value(2)".to_owned(),
-        ));
+    if !check_log_ret(decoded_ret_line) {
+        return Err(ComposerError::MissingReturnValue.into());
     }
 
     let abi_encoded = &decoded_ret_line[ABI_RETURN_HASH.len()..decoded_ret_line.len()];
@@ -784,3 +1054,342 @@ fn check_log_ret(log_line: &[u8]) -> bool {
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atomic_transaction_composer::transaction_signer::BasicAccountSigner;
+    use crate::util::{
+        test_fixtures::{payment, txn_params},
+        test_server::{TestResponse, TestServer},
+    };
+    use algonaut_transaction::{account::Account, transaction::TransactionSignature};
+    use async_trait::async_trait;
+
+    /// Signs the requested transactions in reverse order.
+    #[derive(Debug)]
+    struct ReversingSigner(Account);
+
+    #[async_trait]
+    impl TransactionSigner for ReversingSigner {
+        async fn sign_transactions(
+            &self,
+            tx_group: &[Transaction],
+            indexes_to_sign: &[usize],
+        ) -> Result<Vec<SignedTransaction>, Error> {
+            let mut signed = BasicAccountSigner(self.0.clone())
+                .sign_transactions(tx_group, indexes_to_sign)
+                .await?;
+            signed.reverse();
+            Ok(signed)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gather_signatures_with_several_signers() {
+        let alice = Account::generate();
+        let bob = Account::generate();
+        let alice_signer: Arc<dyn TransactionSigner> = Arc::new(BasicAccountSigner(alice.clone()));
+        let bob_signer: Arc<dyn TransactionSigner> = Arc::new(BasicAccountSigner(bob.clone()));
+
+        let mut composer = AtomicTransactionComposer::default();
+        for (account, signer, amount) in [
+            (&alice, &alice_signer, 1),
+            (&bob, &bob_signer, 2),
+            (&alice, &alice_signer, 3),
+            (&bob, &bob_signer, 4),
+        ] {
+            composer
+                .add_transaction(TransactionWithSigner {
+                    tx: payment(&account.address(), amount),
+                    signer: signer.clone(),
+                })
+                .unwrap();
+        }
+
+        let signed_txs = composer.gather_signatures().await.unwrap();
+        let group = composer.build_group().unwrap();
+
+        assert_eq!(4, signed_txs.len());
+        assert_eq!(AtomicTransactionComposerStatus::Signed, composer.status());
+        for (signed_tx, tx_with_signer) in signed_txs.iter().zip(group) {
+            assert_eq!(tx_with_signer.tx, signed_tx.transaction);
+            assert!(tx_with_signer.tx.group.is_some());
+            let sig = match signed_tx.sig {
+                TransactionSignature::Single(sig) => sig,
+                _ => panic!("expected single signature"),
+            };
+            assert!(signed_tx
+                .transaction
+                .sender()
+                .as_public_key()
+                .verify(&signed_tx.transaction.bytes_to_sign().unwrap(), &sig));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gather_signatures_rejects_mismatching_signed_tx() {
+        let alice = Account::generate();
+        let signer: Arc<dyn TransactionSigner> = Arc::new(ReversingSigner(alice.clone()));
+
+        let mut composer = AtomicTransactionComposer::default();
+        for amount in [1, 2] {
+            composer
+                .add_transaction(TransactionWithSigner {
+                    tx: payment(&alice.address(), amount),
+                    signer: signer.clone(),
+                })
+                .unwrap();
+        }
+
+        assert_eq!(
+            Err(Error::Composer(ComposerError::SignedTransactionMismatch {
+                index: 0
+            })),
+            composer.gather_signatures().await
+        );
+        assert_eq!(AtomicTransactionComposerStatus::Built, composer.status());
+    }
+
+    #[test]
+    fn test_composer_errors_are_typed() {
+        let alice = Account::generate();
+        let signer: Arc<dyn TransactionSigner> = Arc::new(BasicAccountSigner(alice.clone()));
+
+        let mut composer = AtomicTransactionComposer::default();
+        assert_eq!(
+            Some(Error::Composer(ComposerError::EmptyGroup)),
+            composer.build_group().err()
+        );
+
+        let mut composer = composer_with_method_call();
+        assert_eq!(
+            Some(Error::Composer(ComposerError::InvalidStatus {
+                operation: "add transactions",
+                status: AtomicTransactionComposerStatus::Built,
+            })),
+            composer
+                .add_transaction(TransactionWithSigner {
+                    tx: payment(&alice.address(), 1),
+                    signer,
+                })
+                .err()
+        );
+    }
+
+    #[test]
+    fn test_missing_return_value_is_typed() {
+        let response: PendingTransactionResponse = serde_json::from_str(
+            &serde_json::json!({ "pool-error": "", "txn": {}, "logs": ["AQID"] }).to_string(),
+        )
+        .unwrap();
+
+        let res = get_return_value_with_abi_type(&response, &AbiType::uint(64).unwrap());
+
+        assert_eq!(
+            Some(Error::Composer(ComposerError::MissingReturnValue)),
+            res.err()
+        );
+    }
+
+    /// Builds a group with a payment and an `add(uint64,uint64)uint64` method call.
+    fn composer_with_method_call() -> AtomicTransactionComposer {
+        let alice = Account::generate();
+        let signer: Arc<dyn TransactionSigner> = Arc::new(BasicAccountSigner(alice.clone()));
+
+        let mut composer = AtomicTransactionComposer::default();
+        composer
+            .add_transaction(TransactionWithSigner {
+                tx: payment(&alice.address(), 1),
+                signer: signer.clone(),
+            })
+            .unwrap();
+        composer
+            .add_method_call(&mut AddMethodCallParams {
+                app_id: 1,
+                method: AbiMethod::from_signature("add(uint64,uint64)uint64").unwrap(),
+                method_args: vec![
+                    AbiArgValue::AbiValue(AbiValue::Int(1u64.into())),
+                    AbiArgValue::AbiValue(AbiValue::Int(2u64.into())),
+                ],
+                fee: MicroAlgos(1000),
+                sender: alice.address(),
+                suggested_params: txn_params(),
+                on_complete: ApplicationCallOnComplete::NoOp,
+                approval_program: None,
+                clear_program: None,
+                global_schema: None,
+                local_schema: None,
+                extra_pages: 0,
+                note: None,
+                lease: None,
+                rekey_to: None,
+                signer,
+                boxes: None,
+            })
+            .unwrap();
+        composer.build_group().unwrap();
+        composer
+    }
+
+    #[tokio::test]
+    async fn test_submit_reports_failed_txn_index() {
+        let mut composer = composer_with_method_call();
+        let failed_tx_id = tx_ids(&composer)[1].clone();
+        let message = format!(
+            "TransactionPool.Remember: transaction {failed_tx_id}: logic eval error: assert failed pc=8. Details: app=1, pc=8, opcodes=assert"
+        );
+        let node = TestServer::start(move |_| TestResponse::error(400, &message));
+
+        let err = composer
+            .submit(&Algod::new(&node.url, "").unwrap())
+            .await
+            .unwrap_err();
+
+        match err.algod_cause() {
+            Some(AlgodErrorCause::LogicEval {
+                tx_id,
+                txn_index,
+                app_id,
+                pc,
+                message,
+            }) => {
+                assert_eq!(Some(&failed_tx_id), tx_id.as_ref());
+                assert_eq!(Some(1), *txn_index);
+                assert_eq!(Some(1), *app_id);
+                assert_eq!(Some(8), *pc);
+                assert_eq!("assert failed pc=8", message);
+            }
+            cause => panic!("expected logic eval error, got: {:?}", cause),
+        }
+        assert_eq!(AtomicTransactionComposerStatus::Signed, composer.status());
+    }
+
+    #[tokio::test]
+    async fn test_execute_decodes_method_return_value() {
+        let mut composer = composer_with_method_call();
+        let tx_ids = tx_ids(&composer);
+        let submitted_tx_id = tx_ids[0].clone();
+        let node = TestServer::start(move |request| {
+            if request.path == "/v2/transactions" {
+                TestResponse::json(&format!(r#"{{"txId":"{submitted_tx_id}"}}"#))
+            } else if request.path.starts_with("/v2/transactions/pending/") {
+                // return prefix followed by uint64 3, base64 encoded like algod's logs
+                TestResponse::json(
+                    r#"{"pool-error":"","txn":{},"confirmed-round":5,"logs":["FR98dQAAAAAAAAAD"]}"#,
+                )
+            } else {
+                TestResponse::error(404, "not found")
+            }
+        });
+
+        let res = composer
+            .execute(&Algod::new(&node.url, "").unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(Some(5), res.confirmed_round);
+        assert_eq!(tx_ids, res.tx_ids);
+        assert_eq!(1, res.method_results.len());
+        match &res.method_results[0].return_value {
+            Ok(AbiMethodReturnValue::Some(AbiValue::Int(value))) => {
+                assert_eq!(&BigUint::from(3u64), value)
+            }
+            other => panic!("unexpected return value: {:?}", other),
+        }
+        assert_eq!(
+            AtomicTransactionComposerStatus::Committed,
+            composer.status()
+        );
+    }
+
+    fn tx_ids(composer: &AtomicTransactionComposer) -> Vec<String> {
+        composer.txs.iter().map(|t| t.tx.id().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_simulate_result_decodes_method_return_value() {
+        let composer = composer_with_method_call();
+        let tx_ids = tx_ids(&composer);
+
+        let response: SimulateTransaction200Response = serde_json::from_str(
+            &serde_json::json!({
+                "last-round": 100,
+                "version": 2,
+                "would-succeed": true,
+                "txn-groups": [{
+                    "app-budget-added": 700,
+                    "app-budget-consumed": 12,
+                    "txn-results": [
+                        { "txn-result": { "pool-error": "", "txn": {} } },
+                        {
+                            "app-budget-consumed": 12,
+                            "txn-result": {
+                                "pool-error": "",
+                                "txn": {},
+                                // return prefix followed by uint64 3
+                                "logs": ["FR98dQAAAAAAAAAD"]
+                            }
+                        }
+                    ]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let res = composer
+            .to_simulate_result(tx_ids.clone(), response)
+            .unwrap();
+
+        assert!(res.would_succeed);
+        assert_eq!(100, res.simulate_round);
+        assert_eq!(tx_ids, res.tx_ids);
+        assert_eq!(Some(12), res.app_budget_consumed);
+        assert_eq!(Some(12), res.tx_results[1].app_budget_consumed);
+        assert!(res.tx_results.iter().all(|r| r.failure_message.is_none()));
+        assert_eq!(1, res.method_results.len());
+        assert_eq!(tx_ids[1], res.method_results[0].tx_id);
+        match &res.method_results[0].return_value {
+            Ok(AbiMethodReturnValue::Some(AbiValue::Int(value))) => {
+                assert_eq!(&BigUint::from(3u64), value)
+            }
+            other => panic!("unexpected return value: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_simulate_result_reports_failing_transaction() {
+        let composer = composer_with_method_call();
+        let tx_ids = tx_ids(&composer);
+
+        let response: SimulateTransaction200Response = serde_json::from_str(
+            &serde_json::json!({
+                "last-round": 100,
+                "version": 2,
+                "would-succeed": false,
+                "txn-groups": [{
+                    "failed-at": [1],
+                    "failure-message": "logic eval error: err opcode executed",
+                    "txn-results": [
+                        { "txn-result": { "pool-error": "", "txn": {} } },
+                        { "txn-result": { "pool-error": "", "txn": {} } }
+                    ]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let res = composer.to_simulate_result(tx_ids, response).unwrap();
+
+        assert!(!res.would_succeed);
+        assert!(res.tx_results[0].failure_message.is_none());
+        assert_eq!(
+            Some("logic eval error: err opcode executed".to_owned()),
+            res.tx_results[1].failure_message
+        );
+        assert_eq!(Some(vec![1]), res.tx_results[1].failed_at);
+        assert!(res.method_results[0].return_value.is_err());
+    }
+}
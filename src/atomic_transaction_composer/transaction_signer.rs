@@ -1,52 +1,277 @@
-use algonaut_core::MultisigAddress;
+use super::error::ComposerError;
+use crate::{kmd::v1::Kmd, Error};
+use algonaut_core::{Address, MultisigAddress, MultisigSignature};
+use algonaut_crypto::Ed25519PublicKey;
 use algonaut_transaction::{
     account::Account, contract_account::ContractAccount, error::TransactionError,
     transaction::TransactionSignature, SignedTransaction, Transaction,
 };
+use async_trait::async_trait;
+use std::{collections::HashMap, fmt::Debug};
 
+/// Authorizes transactions of an atomic group.
+///
+/// Implementations receive the complete (grouped) transaction group, so they can e.g. show it to a
+/// user before signing, and the indexes of the transactions they're expected to sign.
+// reqwest futures aren't thread safe with the WASM target, so signers backed by a http client
+// (e.g. kmd) couldn't implement this trait if it required `Send`. WASM is single threaded.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait TransactionSigner: Debug + Send + Sync {
+    /// Signs the transactions at `indexes_to_sign` in `tx_group`.
+    ///
+    /// Returns the signed transactions in the same order as `indexes_to_sign`.
+    async fn sign_transactions(
+        &self,
+        tx_group: &[Transaction],
+        indexes_to_sign: &[usize],
+    ) -> Result<Vec<SignedTransaction>, Error>;
+}
+
+/// Signs transactions with an in-memory account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicAccountSigner(pub Account);
+
+/// Signs transactions with a logic signature (contract account).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractAccountSigner(pub ContractAccount);
+
+/// Signs transactions with a multisig address, using all the given accounts.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum TransactionSigner {
-    BasicAccount(Account),
-    ContractAccount(ContractAccount),
-    MultisigAccount {
-        address: MultisigAddress,
-        accounts: Vec<Account>,
-    },
+pub struct MultisigAccountSigner {
+    pub address: MultisigAddress,
+    pub accounts: Vec<Account>,
 }
 
-impl TransactionSigner {
-    pub fn sign_transactions(
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TransactionSigner for BasicAccountSigner {
+    async fn sign_transactions(
         &self,
-        tx_group: Vec<Transaction>,
-    ) -> Result<Vec<SignedTransaction>, TransactionError> {
-        match self {
-            TransactionSigner::BasicAccount(account) => {
-                let mut signed_txs = vec![];
-                for tx in tx_group {
-                    signed_txs.push(account.sign_transaction(tx)?);
-                }
-                Ok(signed_txs)
-            }
+        tx_group: &[Transaction],
+        indexes_to_sign: &[usize],
+    ) -> Result<Vec<SignedTransaction>, Error> {
+        let mut signed_txs = vec![];
+        for tx in txs_to_sign(tx_group, indexes_to_sign)? {
+            signed_txs.push(self.0.sign_transaction(tx)?);
+        }
+        Ok(signed_txs)
+    }
+}
 
-            TransactionSigner::ContractAccount(account) => {
-                let mut signed_txs = vec![];
-                for tx in tx_group {
-                    signed_txs.push(account.sign(tx, vec![])?);
-                }
-                Ok(signed_txs)
-            }
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TransactionSigner for ContractAccountSigner {
+    async fn sign_transactions(
+        &self,
+        tx_group: &[Transaction],
+        indexes_to_sign: &[usize],
+    ) -> Result<Vec<SignedTransaction>, Error> {
+        let mut signed_txs = vec![];
+        for tx in txs_to_sign(tx_group, indexes_to_sign)? {
+            signed_txs.push(self.0.sign(tx, vec![])?);
+        }
+        Ok(signed_txs)
+    }
+}
 
-            TransactionSigner::MultisigAccount { address, accounts } => {
-                let mut signed_txs = vec![];
-                for tx in tx_group {
-                    signed_txs.push(sign_msig_tx(address, accounts, tx)?);
-                }
-                Ok(signed_txs)
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TransactionSigner for MultisigAccountSigner {
+    async fn sign_transactions(
+        &self,
+        tx_group: &[Transaction],
+        indexes_to_sign: &[usize],
+    ) -> Result<Vec<SignedTransaction>, Error> {
+        let mut signed_txs = vec![];
+        for tx in txs_to_sign(tx_group, indexes_to_sign)? {
+            signed_txs.push(sign_msig_tx(&self.address, &self.accounts, tx)?);
+        }
+        Ok(signed_txs)
+    }
+}
+
+/// Signs transactions with keys stored in a kmd wallet.
+///
+/// Transactions sent from a multisig address imported in the wallet are signed with all the
+/// multisig keys the wallet holds. Transactions of senders rekeyed with
+/// [KmdTransactionSigner::auth_address] are signed with the key of their auth address.
+/// Any other transaction is signed with the sender's key.
+///
+/// A wallet handle is initialized for each signing request and released afterwards.
+#[derive(Debug, Clone)]
+pub struct KmdTransactionSigner {
+    kmd: Kmd,
+    wallet_name: String,
+    wallet_password: String,
+    auth_addresses: HashMap<Address, Address>,
+}
+
+impl KmdTransactionSigner {
+    pub fn new(kmd: Kmd, wallet_name: &str, wallet_password: &str) -> KmdTransactionSigner {
+        KmdTransactionSigner {
+            kmd,
+            wallet_name: wallet_name.to_owned(),
+            wallet_password: wallet_password.to_owned(),
+            auth_addresses: HashMap::new(),
+        }
+    }
+
+    /// Signs the transactions of `sender`, which was rekeyed to `auth_address`,
+    /// with the wallet's key of `auth_address`.
+    pub fn auth_address(mut self, sender: Address, auth_address: Address) -> Self {
+        self.auth_addresses.insert(sender, auth_address);
+        self
+    }
+
+    async fn wallet_id(&self) -> Result<String, Error> {
+        self.kmd
+            .list_wallets()
+            .await?
+            .wallets
+            .into_iter()
+            .find(|wallet| wallet.name == self.wallet_name)
+            .map(|wallet| wallet.id)
+            .ok_or_else(|| ComposerError::KmdWalletNotFound(self.wallet_name.clone()).into())
+    }
+
+    async fn sign_with_handle(
+        &self,
+        wallet_handle: &str,
+        txs: Vec<Transaction>,
+    ) -> Result<Vec<SignedTransaction>, Error> {
+        let multisig_addresses = self.kmd.list_multisig(wallet_handle).await?.addresses;
+        let wallet_addresses = self.kmd.list_keys(wallet_handle).await?.addresses;
+
+        let mut signed_txs = vec![];
+        for tx in txs {
+            let sender = tx.sender();
+            let (sig, auth_address) = if let Some(auth_address) = self.auth_addresses.get(&sender) {
+                let res = self
+                    .kmd
+                    .sign_transaction_with_public_key(
+                        wallet_handle,
+                        &self.wallet_password,
+                        &tx,
+                        Ed25519PublicKey(auth_address.0),
+                    )
+                    .await?;
+                kmd_signature(&res.signed_transaction)?
+            } else if multisig_addresses.contains(&sender.to_string()) {
+                let msig = self
+                    .sign_multisig(wallet_handle, &wallet_addresses, &tx)
+                    .await?;
+                (TransactionSignature::Multi(msig), None)
+            } else {
+                let res = self
+                    .kmd
+                    .sign_transaction(wallet_handle, &self.wallet_password, &tx)
+                    .await?;
+                kmd_signature(&res.signed_transaction)?
+            };
+
+            signed_txs.push(SignedTransaction {
+                transaction_id: tx.id()?,
+                transaction: tx,
+                sig,
+                auth_address,
+            });
+        }
+        Ok(signed_txs)
+    }
+
+    /// Signs `tx` with every key of the sender's multisig that's stored in the wallet.
+    async fn sign_multisig(
+        &self,
+        wallet_handle: &str,
+        wallet_addresses: &[String],
+        tx: &Transaction,
+    ) -> Result<MultisigSignature, Error> {
+        let preimage = self
+            .kmd
+            .export_multisig(wallet_handle, &tx.sender().to_string())
+            .await?;
+
+        let mut msig = None;
+        for pk in preimage.pks {
+            if !wallet_addresses.contains(&Address(pk.0).to_string()) {
+                continue;
             }
+            let res = self
+                .kmd
+                .sign_multisig_transaction(wallet_handle, &self.wallet_password, tx, pk, msig)
+                .await?;
+            msig = Some(
+                rmp_serde::from_slice(&res.multisig)
+                    .map_err(|e| ComposerError::KmdSignature(e.to_string()))?,
+            );
         }
+
+        msig.ok_or_else(|| {
+            ComposerError::KmdNoMultisigKeys {
+                wallet: self.wallet_name.clone(),
+                address: tx.sender().to_string(),
+            }
+            .into()
+        })
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl TransactionSigner for KmdTransactionSigner {
+    async fn sign_transactions(
+        &self,
+        tx_group: &[Transaction],
+        indexes_to_sign: &[usize],
+    ) -> Result<Vec<SignedTransaction>, Error> {
+        let txs = txs_to_sign(tx_group, indexes_to_sign)?;
+
+        let wallet_id = self.wallet_id().await?;
+        let wallet_handle = self
+            .kmd
+            .init_wallet_handle(&wallet_id, &self.wallet_password)
+            .await?
+            .wallet_handle_token;
+
+        let signed_txs = self.sign_with_handle(&wallet_handle, txs).await;
+        // release the handle also if signing failed, reporting the signing error first
+        let release_res = self.kmd.release_wallet_handle(&wallet_handle).await;
+
+        let signed_txs = signed_txs?;
+        release_res?;
+        Ok(signed_txs)
     }
 }
 
+/// Decodes the signature and auth address of a transaction signed by kmd.
+fn kmd_signature(
+    signed_transaction: &[u8],
+) -> Result<(TransactionSignature, Option<Address>), ComposerError> {
+    let signed: SignedTransaction = rmp_serde::from_slice(signed_transaction)
+        .map_err(|e| ComposerError::KmdSignature(e.to_string()))?;
+    Ok((signed.sig, signed.auth_address))
+}
+
+/// Returns copies of the transactions at `indexes_to_sign`, in the order of the indexes.
+pub(crate) fn txs_to_sign(
+    tx_group: &[Transaction],
+    indexes_to_sign: &[usize],
+) -> Result<Vec<Transaction>, Error> {
+    indexes_to_sign
+        .iter()
+        .map(|i| {
+            tx_group.get(*i).cloned().ok_or_else(|| {
+                ComposerError::SignIndexOutOfBounds {
+                    index: *i,
+                    group_size: tx_group.len(),
+                }
+                .into()
+            })
+        })
+        .collect()
+}
+
 fn sign_msig_tx(
     address: &MultisigAddress,
     accounts: &[Account],
@@ -70,3 +295,258 @@ fn sign_msig_tx(
         Err(TransactionError::NoAccountsToSign)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{
+        test_fixtures::payment,
+        test_server::{TestResponse, TestServer},
+    };
+    use algonaut_core::ToMsgPack;
+    use data_encoding::BASE64;
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_basic_account_signs_only_requested_indexes() {
+        let account = Account::generate();
+        let tx_group = vec![
+            payment(&account.address(), 1),
+            payment(&account.address(), 2),
+            payment(&account.address(), 3),
+        ];
+
+        let signed = BasicAccountSigner(account)
+            .sign_transactions(&tx_group, &[2, 0])
+            .await
+            .unwrap();
+
+        assert_eq!(2, signed.len());
+        assert_eq!(tx_group[2], signed[0].transaction);
+        assert_eq!(tx_group[0], signed[1].transaction);
+    }
+
+    #[tokio::test]
+    async fn test_index_out_of_bounds_fails() {
+        let account = Account::generate();
+        let tx_group = vec![payment(&account.address(), 1)];
+
+        let res = BasicAccountSigner(account)
+            .sign_transactions(&tx_group, &[1])
+            .await;
+
+        assert!(res.is_err());
+    }
+
+    /// A kmd node whose wallet "main" holds `accounts` and the multisig `msig`.
+    ///
+    /// Signs like kmd with the stored keys. Fails transaction signing if `fail_signing` is set.
+    fn kmd_node(accounts: Vec<Account>, msig: MultisigAddress, fail_signing: bool) -> TestServer {
+        let partial_msig: Arc<Mutex<Option<MultisigSignature>>> = Arc::new(Mutex::new(None));
+        TestServer::start(move |request| {
+            let body: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+            let tx = || -> Transaction {
+                let bytes = BASE64
+                    .decode(body["transaction"].as_str().unwrap().as_bytes())
+                    .unwrap();
+                rmp_serde::from_slice(&bytes).unwrap()
+            };
+            let response = match request.path.as_str() {
+                "/v1/wallets" => json!({ "wallets": [wallet("1", "other"), wallet("2", "main")] }),
+                "/v1/wallet/init" => json!({ "wallet_handle_token": "handle" }),
+                "/v1/wallet/release" => json!({}),
+                "/v1/key/list" => json!({
+                    "addresses": accounts.iter().map(|a| a.address().to_string()).collect::<Vec<_>>()
+                }),
+                "/v1/multisig/list" => json!({ "addresses": [msig.address().to_string()] }),
+                "/v1/multisig/export" => json!({
+                    "multisig_version": msig.version,
+                    "threshold": msig.threshold,
+                    "pks": msig.public_keys.iter().map(|pk| BASE64.encode(&pk.0)).collect::<Vec<_>>(),
+                }),
+                "/v1/transaction/sign" if fail_signing => {
+                    return TestResponse::error(500, "wallet locked")
+                }
+                "/v1/transaction/sign" => {
+                    let tx = tx();
+                    // the key to sign with defaults to the sender's
+                    let key = match &body["public_key"] {
+                        Value::Null => tx.sender().0.to_vec(),
+                        pk => serde_json::from_value(pk.clone()).unwrap(),
+                    };
+                    let account = accounts
+                        .iter()
+                        .find(|a| a.address().0[..] == key[..])
+                        .unwrap();
+                    let signed = account.sign_transaction(tx).unwrap();
+                    json!({ "signed_transaction": BASE64.encode(&signed.to_msg_pack().unwrap()) })
+                }
+                "/v1/multisig/sign" => {
+                    let pk: Vec<u8> = serde_json::from_value(body["public_key"].clone()).unwrap();
+                    let account = accounts
+                        .iter()
+                        .find(|a| a.address().0[..] == pk[..])
+                        .unwrap();
+                    let mut partial_msig = partial_msig.lock().unwrap();
+                    let signed = match body["partial_multisig"] {
+                        Value::Null => account.init_transaction_msig(&tx(), &msig),
+                        _ => {
+                            account.append_to_transaction_msig(&tx(), partial_msig.take().unwrap())
+                        }
+                    }
+                    .unwrap();
+                    *partial_msig = Some(signed.clone());
+                    json!({ "multisig": BASE64.encode(&rmp_serde::to_vec_named(&signed).unwrap()) })
+                }
+                _ => return TestResponse::error(404, "not found"),
+            };
+            TestResponse::json(&response.to_string())
+        })
+    }
+
+    fn wallet(id: &str, name: &str) -> Value {
+        json!({
+            "driver_name": "sqlite",
+            "driver_version": 1,
+            "id": id,
+            "mnemonic_ux": false,
+            "name": name,
+            "supported_txs": ["pay"],
+        })
+    }
+
+    fn kmd(node: &TestServer) -> Kmd {
+        Kmd::new(
+            &node.url,
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        )
+        .unwrap()
+    }
+
+    fn count(node: &TestServer, path: &str) -> usize {
+        node.paths().iter().filter(|p| *p == path).count()
+    }
+
+    #[tokio::test]
+    async fn test_kmd_signs_requested_indexes_with_wallet_keys() {
+        let single = Account::generate();
+        let (msig_a, msig_b, msig_c) = (
+            Account::generate(),
+            Account::generate(),
+            Account::generate(),
+        );
+        let msig = MultisigAddress::new(
+            1,
+            2,
+            &[msig_a.address(), msig_b.address(), msig_c.address()],
+        )
+        .unwrap();
+        // the wallet holds only 2 of the multisig keys
+        let node = kmd_node(
+            vec![single.clone(), msig_a.clone(), msig_b.clone()],
+            msig.clone(),
+            false,
+        );
+        let tx_group = vec![
+            payment(&single.address(), 1),
+            payment(&msig.address(), 2),
+            payment(&single.address(), 3),
+        ];
+
+        let signed = KmdTransactionSigner::new(kmd(&node), "main", "pw")
+            .sign_transactions(&tx_group, &[2, 1])
+            .await
+            .unwrap();
+
+        assert_eq!(2, signed.len());
+        assert_eq!(tx_group[2], signed[0].transaction);
+        assert_eq!(
+            single.sign_transaction(tx_group[2].clone()).unwrap().sig,
+            signed[0].sig
+        );
+        assert_eq!(tx_group[1], signed[1].transaction);
+        let expected_msig = MultisigAccountSigner {
+            address: msig,
+            accounts: vec![msig_a, msig_b],
+        }
+        .sign_transactions(&tx_group, &[1])
+        .await
+        .unwrap();
+        assert_eq!(expected_msig[0].sig, signed[1].sig);
+
+        let requests = node.requests();
+        let init = requests
+            .iter()
+            .find(|r| r.path == "/v1/wallet/init")
+            .unwrap();
+        let init: Value = serde_json::from_slice(&init.body).unwrap();
+        assert_eq!("2", init["wallet_id"]);
+        assert_eq!("pw", init["wallet_password"]);
+        assert_eq!(1, count(&node, "/v1/transaction/sign"));
+        assert_eq!(2, count(&node, "/v1/multisig/sign"));
+        assert_eq!(
+            Some("/v1/wallet/release"),
+            node.paths().last().map(|p| p.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_kmd_releases_handle_if_signing_fails() {
+        let account = Account::generate();
+        let msig = MultisigAddress::new(1, 1, &[Account::generate().address()]).unwrap();
+        let node = kmd_node(vec![account.clone()], msig, true);
+        let tx_group = vec![payment(&account.address(), 1)];
+
+        let res = KmdTransactionSigner::new(kmd(&node), "main", "pw")
+            .sign_transactions(&tx_group, &[0])
+            .await;
+
+        assert!(res.is_err());
+        assert_eq!(1, count(&node, "/v1/wallet/init"));
+        assert_eq!(1, count(&node, "/v1/wallet/release"));
+    }
+
+    #[tokio::test]
+    async fn test_kmd_unknown_wallet_fails_without_handle() {
+        let account = Account::generate();
+        let msig = MultisigAddress::new(1, 1, &[account.address()]).unwrap();
+        let node = kmd_node(vec![account.clone()], msig, false);
+        let tx_group = vec![payment(&account.address(), 1)];
+
+        let res = KmdTransactionSigner::new(kmd(&node), "missing", "pw")
+            .sign_transactions(&tx_group, &[0])
+            .await;
+
+        assert!(res.is_err());
+        assert_eq!(0, count(&node, "/v1/wallet/init"));
+    }
+
+    #[tokio::test]
+    async fn test_kmd_signs_rekeyed_sender_with_auth_key() {
+        let (sender, auth) = (Account::generate(), Account::generate());
+        let msig = MultisigAddress::new(1, 1, &[sender.address()]).unwrap();
+        let node = kmd_node(vec![auth.clone()], msig, false);
+        let tx_group = vec![payment(&sender.address(), 1)];
+
+        let signed = KmdTransactionSigner::new(kmd(&node), "main", "pw")
+            .auth_address(sender.address(), auth.address())
+            .sign_transactions(&tx_group, &[0])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            auth.sign_transaction(tx_group[0].clone()).unwrap(),
+            signed[0]
+        );
+        assert_eq!(Some(auth.address()), signed[0].auth_address);
+        let sign = node
+            .requests()
+            .into_iter()
+            .find(|r| r.path == "/v1/transaction/sign")
+            .unwrap();
+        let sign: Value = serde_json::from_slice(&sign.body).unwrap();
+        let public_key: Vec<u8> = serde_json::from_value(sign["public_key"].clone()).unwrap();
+        assert_eq!(auth.address().0.to_vec(), public_key);
+    }
+}
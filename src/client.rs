@@ -0,0 +1,424 @@
+//! Configuration of the HTTP clients of [Algod], [Indexer] and [Kmd].
+
+use crate::{algod::v2::Algod, indexer::v2::Indexer, kmd::v1::Kmd, util::sleep, Error};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::{future::Future, str::FromStr, time::Duration};
+
+/// User agent of the clients, unless set with [ClientBuilder::user_agent].
+const DEFAULT_USER_AGENT: &str = "algonaut";
+
+/// Builds [Algod], [Indexer] and [Kmd] clients with timeouts, custom headers and retries.
+///
+/// ```no_run
+/// # use algonaut::client::ClientBuilder;
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), algonaut::Error> {
+/// let algod = ClientBuilder::new()
+///     .connect_timeout(Duration::from_secs(5))
+///     .timeout(Duration::from_secs(30))
+///     .header("X-API-Key", "my-api-key")
+///     .max_retries(3)
+///     .algod("https://mainnet-api.example.com", "")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    headers: Vec<(String, String)>,
+    user_agent: Option<String>,
+    retry_policy: RetryPolicy,
+    http_client: Option<reqwest::Client>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Sets the timeout for connecting to the server.
+    ///
+    /// Not supported on wasm, where the browser's timeouts apply.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for a whole request, from connecting until the response was read.
+    ///
+    /// Requests that wait on the server, like [Algod::status_after_block], need a timeout above
+    /// the server's. To only fail on stalled responses, use [ClientBuilder::read_timeout].
+    ///
+    /// Not supported on wasm, where the browser's timeouts apply.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for each read of a response body of algod and indexer requests,
+    /// e.g. to fail if a node stops sending a block midway.
+    ///
+    /// Not supported on wasm, where the browser's timeouts apply.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a header sent with every request, e.g. the API key of a hosted node provider.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the user agent sent with every request.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_owned());
+        self
+    }
+
+    /// Sets how often a failed request is retried, see [RetryPolicy]. Defaults to no retries.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the max. delay between retries, in milliseconds.
+    pub fn max_retry_delay_ms(mut self, max_retry_delay_ms: u32) -> Self {
+        self.retry_policy.max_retry_delay_ms = max_retry_delay_ms;
+        self
+    }
+
+    /// Sends the requests with a pre-configured client.
+    ///
+    /// The client is used as is: timeouts and headers set on this builder are ignored,
+    /// except for the user agent and read timeout of algod and indexer requests.
+    pub fn http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Builds a v2 client for Algorand protocol daemon.
+    pub fn algod(&self, url: &str, token: &str) -> Result<Algod, Error> {
+        Ok(Algod::with_client(
+            url,
+            token,
+            self.build_http_client()?,
+            self.user_agent_or_default(),
+            self.retry_policy.clone(),
+            self.read_timeout,
+        ))
+    }
+
+    /// Builds a v2 client for Algorand's indexer.
+    pub fn indexer(&self, url: &str, token: &str) -> Result<Indexer, Error> {
+        Ok(Indexer::with_client(
+            url,
+            token,
+            self.build_http_client()?,
+            self.user_agent_or_default(),
+            self.retry_policy.clone(),
+            self.read_timeout,
+        ))
+    }
+
+    /// Builds a v1 client for the Algorand key management daemon.
+    ///
+    /// Returns an error if the url or token have an invalid format.
+    pub fn kmd(&self, url: &str, token: &str) -> Result<Kmd, Error> {
+        Kmd::with_client(
+            url,
+            token,
+            self.build_http_client()?,
+            self.retry_policy.clone(),
+        )
+    }
+
+    fn user_agent_or_default(&self) -> String {
+        self.user_agent
+            .clone()
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_owned())
+    }
+
+    fn build_http_client(&self) -> Result<reqwest::Client, Error> {
+        if let Some(http_client) = &self.http_client {
+            return Ok(http_client.clone());
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(
+                HeaderName::from_str(name).map_err(|e| Error::BadHeader(e.to_string()))?,
+                HeaderValue::from_str(value).map_err(|e| Error::BadHeader(e.to_string()))?,
+            );
+        }
+        let builder = reqwest::Client::builder()
+            .default_headers(headers)
+            .user_agent(self.user_agent_or_default());
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = {
+            let mut builder = builder;
+            if let Some(timeout) = self.connect_timeout {
+                builder = builder.connect_timeout(timeout);
+            }
+            if let Some(timeout) = self.timeout {
+                builder = builder.timeout(timeout);
+            }
+            builder
+        };
+
+        builder
+            .build()
+            .map_err(|e| Error::Msg(format!("Couldn't build the HTTP client: {e}")))
+    }
+}
+
+/// Retries of failed requests, with an exponential backoff.
+///
+/// Only idempotent (GET) requests are retried, and only when they failed with a transient error,
+/// see [Error::is_transient].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Max. delay between retries, in milliseconds.
+    pub max_retry_delay_ms: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            max_retry_delay_ms: 10_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Sends the request, retrying it after transient errors.
+    pub(crate) async fn retry<T, F, Fut>(&self, request: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut failures = 0;
+        loop {
+            match request().await {
+                Err(e) if e.is_transient() && failures < self.max_retries => {
+                    failures += 1;
+                    log::debug!("Request failed, retrying: {e}");
+                    sleep(retry_delay_ms(failures, self.max_retry_delay_ms)).await;
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+/// Returns the exponential backoff delay before retrying, after `failures` failed requests.
+fn retry_delay_ms(failures: u32, max_delay_ms: u32) -> u32 {
+    250u32
+        .saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+        .min(max_delay_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_server::{TestResponse, TestServer};
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    const VERSION: &str = r#"{
+        "build": {
+            "branch": "", "build_number": 0, "channel": "", "commit_hash": "",
+            "major": 3, "minor": 0, "patch": 0
+        },
+        "genesis_hash_b64": "", "genesis_id": "", "versions": []
+    }"#;
+
+    /// Responds with `failures` errors with the status before succeeding.
+    fn flaky_node(failures: u32, status: u16) -> (TestServer, Arc<AtomicU32>) {
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+        let node = TestServer::start(move |_| {
+            if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                TestResponse::error(status, "unavailable")
+            } else {
+                TestResponse::json(VERSION)
+            }
+        });
+        (node, requests)
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(250, retry_delay_ms(1, 1000));
+        assert_eq!(500, retry_delay_ms(2, 1000));
+        assert_eq!(1000, retry_delay_ms(3, 1000));
+        assert_eq!(1000, retry_delay_ms(40, 1000));
+    }
+
+    #[tokio::test]
+    async fn test_sends_headers_and_user_agent() {
+        let node = TestServer::start(|_| TestResponse::json(VERSION));
+
+        let algod = ClientBuilder::new()
+            .header("X-API-Key", "secret")
+            .user_agent("batch-job/1.0")
+            .algod(&node.url, "token")
+            .unwrap();
+        algod.version().await.unwrap();
+
+        let request = &node.requests()[0];
+        assert_eq!(Some("secret"), request.header("x-api-key"));
+        assert_eq!(Some("batch-job/1.0"), request.header("user-agent"));
+        assert_eq!(Some("token"), request.header("x-algo-api-token"));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let (node, requests) = flaky_node(2, 503);
+
+        let algod = ClientBuilder::new()
+            .max_retries(2)
+            .max_retry_delay_ms(1)
+            .algod(&node.url, "")
+            .unwrap();
+
+        algod.version().await.unwrap();
+        assert_eq!(3, requests.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (node, requests) = flaky_node(5, 429);
+
+        let indexer = ClientBuilder::new()
+            .max_retries(1)
+            .max_retry_delay_ms(1)
+            .indexer(&node.url, "")
+            .unwrap();
+
+        let err = indexer.health().await.unwrap_err();
+        assert!(err.is_transient());
+        assert_eq!(2, requests.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors_or_posts() {
+        let (node, requests) = flaky_node(5, 400);
+        let algod = ClientBuilder::new()
+            .max_retries(3)
+            .max_retry_delay_ms(1)
+            .algod(&node.url, "")
+            .unwrap();
+
+        assert!(algod.version().await.is_err());
+        assert_eq!(1, requests.load(Ordering::SeqCst));
+
+        let (node, requests) = flaky_node(5, 503);
+        let algod = ClientBuilder::new()
+            .max_retries(3)
+            .max_retry_delay_ms(1)
+            .algod(&node.url, "")
+            .unwrap();
+
+        assert!(algod.send_raw_txn(&[1, 2, 3]).await.is_err());
+        assert_eq!(1, requests.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_times_out() {
+        let node = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(500));
+            TestResponse::json(VERSION)
+        });
+
+        let algod = ClientBuilder::new()
+            .timeout(Duration::from_millis(50))
+            .algod(&node.url, "")
+            .unwrap();
+
+        let err = algod.version().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Request(crate::error::RequestError {
+                details: crate::error::RequestErrorDetails::Timeout,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_times_out_reading_stalled_response() {
+        let node = TestServer::start(|_| {
+            TestResponse::json(VERSION).delay_body(Duration::from_millis(500))
+        });
+
+        let algod = ClientBuilder::new()
+            .read_timeout(Duration::from_millis(50))
+            .algod(&node.url, "")
+            .unwrap();
+
+        let err = algod.version().await.unwrap_err();
+        assert!(err.is_transient());
+        assert!(matches!(
+            err,
+            Error::Request(crate::error::RequestError {
+                details: crate::error::RequestErrorDetails::Timeout,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_does_not_limit_slow_responses() {
+        let node = TestServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(200));
+            TestResponse::json(VERSION)
+        });
+
+        let algod = ClientBuilder::new()
+            .read_timeout(Duration::from_millis(50))
+            .algod(&node.url, "")
+            .unwrap();
+
+        assert!(algod.version().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_refused_connection_is_transient() {
+        // a port that nothing listens on anymore
+        let url = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        let algod = ClientBuilder::new().algod(&url, "").unwrap();
+
+        let err = algod.version().await.unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                Error::Request(crate::error::RequestError {
+                    details: crate::error::RequestErrorDetails::Connection { .. },
+                    ..
+                })
+            ),
+            "not a connection error: {:?}",
+            err
+        );
+        assert!(err.is_transient());
+    }
+
+    #[test]
+    fn test_rejects_invalid_headers() {
+        let res = ClientBuilder::new()
+            .header("X-API-Key", "line\nbreak")
+            .algod("http://localhost", "");
+        assert!(matches!(res, Err(Error::BadHeader(_))));
+    }
+}
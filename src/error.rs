@@ -1,3 +1,5 @@
+use crate::algod::v2::rejection::{AlgodErrorCause, AlgodRejection};
+use crate::atomic_transaction_composer::error::ComposerError;
 use std::fmt::Debug;
 use thiserror::Error;
 
@@ -21,6 +23,12 @@ pub enum Error {
     /// HTTP calls errors
     #[error("http error: {0}")]
     Request(RequestError),
+    /// Algod returned an error response, e.g. rejected a transaction.
+    #[error("algod error: {0}")]
+    Algod(Box<AlgodRejection>),
+    /// The atomic transaction composer couldn't build, sign or decode a group.
+    #[error("composer error: {0}")]
+    Composer(ComposerError),
 
     /// General text-only errors. Dedicated error variants can be created, if needed.
     #[error("Msg: {0}")]
@@ -34,10 +42,33 @@ pub enum Error {
 impl Error {
     /// Returns if the error is a `RequestError` that failed with a status code of 404.
     pub fn is_404(&self) -> bool {
-        if let Some(e) = self.as_request_error() {
-            e.is_404()
-        } else {
-            false
+        match self {
+            Self::Algod(rejection) => rejection.status == 404,
+            _ => self.as_request_error().is_some_and(RequestError::is_404),
+        }
+    }
+
+    /// Returns if the request may succeed when retried: it couldn't connect or send the request,
+    /// timed out, or the server was overloaded or unavailable (status 429 or 5xx).
+    ///
+    /// Other client errors, e.g. a response that couldn't be decoded, aren't transient.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Algod(rejection) => is_transient_status(rejection.status),
+            Self::Request(e) => match &e.details {
+                RequestErrorDetails::Http { status, .. } => is_transient_status(*status),
+                RequestErrorDetails::Timeout | RequestErrorDetails::Connection { .. } => true,
+                RequestErrorDetails::Client { .. } => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Gets the cause of an algod error response, or none otherwise.
+    pub fn algod_cause(&self) -> Option<&AlgodErrorCause> {
+        match self {
+            Self::Algod(rejection) => Some(&rejection.cause),
+            _ => None,
         }
     }
 
@@ -50,6 +81,10 @@ impl Error {
     }
 }
 
+fn is_transient_status(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
 #[derive(Error, Clone, Debug, PartialEq, Eq)]
 #[error("{:?}, {}", url, details)]
 pub struct RequestError {
@@ -69,13 +104,17 @@ impl RequestError {
 }
 
 #[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum RequestErrorDetails {
     /// Http call error with optional message (returned by remote API)
     #[error("Http error: {}, {}", status, message)]
     Http { status: u16, message: String },
-    /// Timeout
-    #[error("Timeout connecting to the server.")]
+    /// The connection, the whole request or a read of the response timed out
+    #[error("Timeout waiting for the server.")]
     Timeout,
+    /// Connecting to the server or sending the request failed, e.g. the connection was refused or reset
+    #[error("Connection error: {}", description)]
+    Connection { description: String },
     /// Client generated errors (while e.g. building request or decoding response)
     #[error("Client error: {}", description)]
     Client { description: String },
@@ -93,18 +132,46 @@ impl RequestErrorDetails {
     }
 }
 
+impl From<&reqwest::Error> for RequestErrorDetails {
+    /// Details of a request that didn't get a response, classified like kmd's.
+    fn from(error: &reqwest::Error) -> Self {
+        algonaut_kmd::error::RequestErrorDetails::from(error).into()
+    }
+}
+
+/// Returns the message of an algod or indexer error response.
+///
+/// They return errors as JSON with a `message`, falling back to the raw body.
+pub(crate) fn response_error_message(content: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .and_then(|json| json.get("message")?.as_str().map(ToOwned::to_owned))
+        .unwrap_or_else(|| content.to_owned())
+}
+
 impl From<crate::algod::v2::error::AlgodError> for Error {
     fn from(error: crate::algod::v2::error::AlgodError) -> Self {
         match error {
             crate::algod::v2::error::AlgodError::Msg(msg) => Error::Msg(msg),
+            crate::algod::v2::error::AlgodError::Request(e) => Error::Request(e),
+            crate::algod::v2::error::AlgodError::Rejected(rejection) => {
+                Error::Algod(Box::new(rejection))
+            }
         }
     }
 }
 
+impl From<ComposerError> for Error {
+    fn from(error: ComposerError) -> Self {
+        Error::Composer(error)
+    }
+}
+
 impl From<crate::indexer::v2::error::IndexerError> for Error {
     fn from(error: crate::indexer::v2::error::IndexerError) -> Self {
         match error {
             crate::indexer::v2::error::IndexerError::Msg(msg) => Error::Msg(msg),
+            crate::indexer::v2::error::IndexerError::Request(e) => Error::Request(e),
         }
     }
 }
@@ -134,9 +201,15 @@ impl From<algonaut_kmd::error::RequestErrorDetails> for RequestErrorDetails {
                 RequestErrorDetails::Http { status, message }
             }
             algonaut_kmd::error::RequestErrorDetails::Timeout => RequestErrorDetails::Timeout {},
+            algonaut_kmd::error::RequestErrorDetails::Connection { description } => {
+                RequestErrorDetails::Connection { description }
+            }
             algonaut_kmd::error::RequestErrorDetails::Client { description } => {
                 RequestErrorDetails::Client { description }
             }
+            details => RequestErrorDetails::Client {
+                description: details.to_string(),
+            },
         }
     }
 }
@@ -186,3 +259,31 @@ fn check_404() {
         "an unrelated request error is saying that it is a 404 error"
     );
 }
+
+#[test]
+fn check_transient() {
+    let request_error = |details| Error::Request(RequestError::new(None, details));
+
+    assert!(request_error(RequestErrorDetails::Timeout).is_transient());
+    assert!(request_error(RequestErrorDetails::Connection {
+        description: "connection refused".to_owned(),
+    })
+    .is_transient());
+    assert!(request_error(RequestErrorDetails::Http {
+        status: 503,
+        message: "unavailable".to_owned(),
+    })
+    .is_transient());
+    assert!(
+        !request_error(RequestErrorDetails::Client {
+            description: "error decoding response body".to_owned(),
+        })
+        .is_transient(),
+        "a client error that would fail again is transient"
+    );
+    assert!(!request_error(RequestErrorDetails::Http {
+        status: 400,
+        message: "bad request".to_owned(),
+    })
+    .is_transient());
+}
@@ -1,5 +1,6 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, io};
 
+use crate::error::{response_error_message, RequestError, RequestErrorDetails};
 use algonaut_indexer::apis;
 use thiserror::Error;
 
@@ -8,10 +9,29 @@ pub enum IndexerError {
     /// General text-only errors. Dedicated error variants can be created, if needed.
     #[error("Msg: {0}")]
     Msg(String),
+    /// The request failed or the indexer returned an error response.
+    #[error("{0}")]
+    Request(RequestError),
 }
 
 impl<T: Debug> From<apis::Error<T>> for IndexerError {
     fn from(error: apis::Error<T>) -> Self {
-        IndexerError::Msg(format!("{:?}", error))
+        match error {
+            apis::Error::ResponseError(response) => IndexerError::Request(RequestError::new(
+                None,
+                RequestErrorDetails::Http {
+                    status: response.status.as_u16(),
+                    message: response_error_message(&response.content),
+                },
+            )),
+            apis::Error::Reqwest(e) => IndexerError::Request(RequestError::new(
+                e.url().map(|u| u.to_string()),
+                RequestErrorDetails::from(&e),
+            )),
+            apis::Error::Io(e) if e.kind() == io::ErrorKind::TimedOut => {
+                IndexerError::Request(RequestError::new(None, RequestErrorDetails::Timeout))
+            }
+            error => IndexerError::Msg(format!("{:?}", error)),
+        }
     }
 }
@@ -1,7 +1,13 @@
 use self::error::IndexerError;
-use crate::Error;
+use crate::{
+    client::{ClientBuilder, RetryPolicy},
+    Error,
+};
 use algonaut_indexer::{
-    apis::configuration::{ApiKey, Configuration},
+    apis::{
+        self,
+        configuration::{ApiKey, Configuration},
+    },
     models::{
         Block, HealthCheck, LookupAccountAppLocalStates200Response, LookupAccountAssets200Response,
         LookupAccountById200Response, LookupAccountCreatedApplications200Response,
@@ -11,6 +17,7 @@ use algonaut_indexer::{
         SearchForAccounts200Response, SearchForApplicationBoxes200Response,
     },
 };
+use std::{fmt::Debug, future::Future, time::Duration};
 
 /// Error class wrapping errors from algonaut_indexer
 pub(crate) mod error;
@@ -18,15 +25,29 @@ pub(crate) mod error;
 #[derive(Debug, Clone)]
 pub struct Indexer {
     pub(crate) configuration: Configuration,
+    retry_policy: RetryPolicy,
 }
 
 impl Indexer {
     /// Build a v2 client for Algorand's indexer.
+    ///
+    /// For timeouts, custom headers or retries, use [ClientBuilder](crate::client::ClientBuilder).
     pub fn new(url: &str, token: &str) -> Result<Self, Error> {
+        ClientBuilder::new().indexer(url, token)
+    }
+
+    pub(crate) fn with_client(
+        url: &str,
+        token: &str,
+        client: reqwest::Client,
+        user_agent: String,
+        retry_policy: RetryPolicy,
+        read_timeout: Option<Duration>,
+    ) -> Indexer {
         let conf = Configuration {
             base_path: url.to_owned(),
-            user_agent: Some("algonaut".to_owned()),
-            client: reqwest::Client::new(),
+            user_agent: Some(user_agent),
+            client,
             basic_auth: None,
             oauth_access_token: None,
             bearer_access_token: None,
@@ -34,20 +55,31 @@ impl Indexer {
                 prefix: None,
                 key: token.to_owned(),
             }),
+            read_timeout,
         };
 
-        Ok(Self {
+        Indexer {
             configuration: conf,
-        })
+            retry_policy,
+        }
+    }
+
+    /// Sends a request, retrying it according to the retry policy. All indexer requests are idempotent.
+    async fn get<T, E, F, Fut>(&self, request: F) -> Result<T, Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, apis::Error<E>>>,
+        E: Debug,
+    {
+        self.retry_policy
+            .retry(|| async { Ok(request().await.map_err(Into::<IndexerError>::into)?) })
+            .await
     }
 
     /// Health check.
     pub async fn health(&self) -> Result<HealthCheck, Error> {
-        Ok(
-            algonaut_indexer::apis::common_api::make_health_check(&self.configuration)
-                .await
-                .map_err(Into::<IndexerError>::into)?,
-        )
+        self.get(|| algonaut_indexer::apis::common_api::make_health_check(&self.configuration))
+            .await
     }
 
     /// Lookup an account's asset holdings, optionally for a specific ID.
@@ -59,7 +91,7 @@ impl Indexer {
         limit: Option<u64>,
         next: Option<&str>,
     ) -> Result<LookupAccountAppLocalStates200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_account_app_local_states(
                 &self.configuration,
                 account_id,
@@ -68,9 +100,8 @@ impl Indexer {
                 limit,
                 next,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup an account's asset holdings, optionally for a specific ID.
@@ -82,16 +113,17 @@ impl Indexer {
         limit: Option<u64>,
         next: Option<&str>,
     ) -> Result<LookupAccountAssets200Response, Error> {
-        Ok(algonaut_indexer::apis::lookup_api::lookup_account_assets(
-            &self.configuration,
-            account_id,
-            asset_id,
-            include_all,
-            limit,
-            next,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::lookup_api::lookup_account_assets(
+                &self.configuration,
+                account_id,
+                asset_id,
+                include_all,
+                limit,
+                next,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Lookup account information.
@@ -102,15 +134,16 @@ impl Indexer {
         include_all: Option<bool>,
         exclude: Option<Vec<String>>,
     ) -> Result<LookupAccountById200Response, Error> {
-        Ok(algonaut_indexer::apis::lookup_api::lookup_account_by_id(
-            &self.configuration,
-            account_id,
-            round,
-            include_all,
-            exclude,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::lookup_api::lookup_account_by_id(
+                &self.configuration,
+                account_id,
+                round,
+                include_all,
+                exclude.clone(),
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Lookup an account's created application parameters, optionally for a specific ID.
@@ -122,7 +155,7 @@ impl Indexer {
         limit: Option<u64>,
         next: Option<&str>,
     ) -> Result<LookupAccountCreatedApplications200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_account_created_applications(
                 &self.configuration,
                 account_id,
@@ -131,9 +164,8 @@ impl Indexer {
                 limit,
                 next,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup an account's created asset parameters, optionally for a specific ID.
@@ -145,7 +177,7 @@ impl Indexer {
         limit: Option<u64>,
         next: Option<&str>,
     ) -> Result<LookupAccountCreatedAssets200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_account_created_assets(
                 &self.configuration,
                 account_id,
@@ -154,9 +186,8 @@ impl Indexer {
                 limit,
                 next,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup account transactions. Transactions are returned newest to oldest.
@@ -180,7 +211,7 @@ impl Indexer {
         currency_less_than: Option<u64>,
         rekey_to: Option<bool>,
     ) -> Result<LookupAccountTransactions200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_account_transactions(
                 &self.configuration,
                 account_id,
@@ -194,15 +225,14 @@ impl Indexer {
                 min_round,
                 max_round,
                 asset_id,
-                before_time,
-                after_time,
+                before_time.clone(),
+                after_time.clone(),
                 currency_greater_than,
                 currency_less_than,
                 rekey_to,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Given an application ID and box name, returns base64 encoded box name and value. Box names must be in the goal app call arg form 'encoding:value'. For ints, use the form 'int:1234'. For raw bytes, encode base 64 and use 'b64' prefix as in 'b64:A=='. For printable strings, use the form 'str:hello'. For addresses, use the form 'addr:XYZ...'.
@@ -211,15 +241,14 @@ impl Indexer {
         application_id: u64,
         name: &str,
     ) -> Result<algonaut_indexer::models::Box, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_application_box_by_id_and_name(
                 &self.configuration,
                 application_id,
                 name,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup application.
@@ -228,15 +257,14 @@ impl Indexer {
         application_id: u64,
         include_all: Option<bool>,
     ) -> Result<LookupApplicationById200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_application_by_id(
                 &self.configuration,
                 application_id,
                 include_all,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup application logs.
@@ -251,7 +279,7 @@ impl Indexer {
         max_round: Option<u64>,
         sender_address: Option<&str>,
     ) -> Result<LookupApplicationLogsById200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_application_logs_by_id(
                 &self.configuration,
                 application_id,
@@ -262,9 +290,8 @@ impl Indexer {
                 max_round,
                 sender_address,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup the list of accounts who hold this asset
@@ -277,17 +304,18 @@ impl Indexer {
         currency_greater_than: Option<u64>,
         currency_less_than: Option<u64>,
     ) -> Result<LookupAssetBalances200Response, Error> {
-        Ok(algonaut_indexer::apis::lookup_api::lookup_asset_balances(
-            &self.configuration,
-            asset_id,
-            include_all,
-            limit,
-            next,
-            currency_greater_than,
-            currency_less_than,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::lookup_api::lookup_asset_balances(
+                &self.configuration,
+                asset_id,
+                include_all,
+                limit,
+                next,
+                currency_greater_than,
+                currency_less_than,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Lookup asset information.
@@ -296,13 +324,14 @@ impl Indexer {
         asset_id: u64,
         include_all: Option<bool>,
     ) -> Result<LookupAssetById200Response, Error> {
-        Ok(algonaut_indexer::apis::lookup_api::lookup_asset_by_id(
-            &self.configuration,
-            asset_id,
-            include_all,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::lookup_api::lookup_asset_by_id(
+                &self.configuration,
+                asset_id,
+                include_all,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Lookup transactions for an asset. Transactions are returned oldest to newest.
@@ -328,7 +357,7 @@ impl Indexer {
         exclude_close_to: Option<bool>,
         rekey_to: Option<bool>,
     ) -> Result<LookupAccountTransactions200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_asset_transactions(
                 &self.configuration,
                 asset_id,
@@ -341,8 +370,8 @@ impl Indexer {
                 round,
                 min_round,
                 max_round,
-                before_time,
-                after_time,
+                before_time.clone(),
+                after_time.clone(),
                 currency_greater_than,
                 currency_less_than,
                 address,
@@ -350,9 +379,8 @@ impl Indexer {
                 exclude_close_to,
                 rekey_to,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Lookup block.
@@ -361,13 +389,14 @@ impl Indexer {
         round_number: u64,
         header_only: Option<bool>,
     ) -> Result<Block, Error> {
-        Ok(algonaut_indexer::apis::lookup_api::lookup_block(
-            &self.configuration,
-            round_number,
-            header_only,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::lookup_api::lookup_block(
+                &self.configuration,
+                round_number,
+                header_only,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Lookup a single transaction.
@@ -375,11 +404,10 @@ impl Indexer {
         &self,
         txid: &str,
     ) -> Result<LookupTransaction200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::lookup_api::lookup_transaction(&self.configuration, txid)
-                .await
-                .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Search for accounts.
@@ -397,21 +425,22 @@ impl Indexer {
         round: Option<u64>,
         application_id: Option<u64>,
     ) -> Result<SearchForAccounts200Response, Error> {
-        Ok(algonaut_indexer::apis::search_api::search_for_accounts(
-            &self.configuration,
-            asset_id,
-            limit,
-            next,
-            currency_greater_than,
-            include_all,
-            exclude,
-            currency_less_than,
-            auth_addr,
-            round,
-            application_id,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::search_api::search_for_accounts(
+                &self.configuration,
+                asset_id,
+                limit,
+                next,
+                currency_greater_than,
+                include_all,
+                exclude.clone(),
+                currency_less_than,
+                auth_addr,
+                round,
+                application_id,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Given an application ID, returns the box names of that application sorted lexicographically.
@@ -421,16 +450,15 @@ impl Indexer {
         limit: Option<u64>,
         next: Option<&str>,
     ) -> Result<SearchForApplicationBoxes200Response, Error> {
-        Ok(
+        self.get(|| {
             algonaut_indexer::apis::search_api::search_for_application_boxes(
                 &self.configuration,
                 application_id,
                 limit,
                 next,
             )
-            .await
-            .map_err(Into::<IndexerError>::into)?,
-        )
+        })
+        .await
     }
 
     /// Search for applications
@@ -442,16 +470,17 @@ impl Indexer {
         limit: Option<u64>,
         next: Option<&str>,
     ) -> Result<LookupAccountCreatedApplications200Response, Error> {
-        Ok(algonaut_indexer::apis::search_api::search_for_applications(
-            &self.configuration,
-            application_id,
-            creator,
-            include_all,
-            limit,
-            next,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::search_api::search_for_applications(
+                &self.configuration,
+                application_id,
+                creator,
+                include_all,
+                limit,
+                next,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Search for assets.
@@ -466,18 +495,19 @@ impl Indexer {
         unit: Option<&str>,
         asset_id: Option<u64>,
     ) -> Result<LookupAccountCreatedAssets200Response, Error> {
-        Ok(algonaut_indexer::apis::search_api::search_for_assets(
-            &self.configuration,
-            include_all,
-            limit,
-            next,
-            creator,
-            name,
-            unit,
-            asset_id,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::search_api::search_for_assets(
+                &self.configuration,
+                include_all,
+                limit,
+                next,
+                creator,
+                name,
+                unit,
+                asset_id,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 
     /// Search for transactions. Transactions are returned oldest to newest unless the address parameter is used, in which case results are returned newest to oldest.
@@ -504,30 +534,31 @@ impl Indexer {
         rekey_to: Option<bool>,
         application_id: Option<u64>,
     ) -> Result<LookupAccountTransactions200Response, Error> {
-        Ok(algonaut_indexer::apis::search_api::search_for_transactions(
-            &self.configuration,
-            limit,
-            next,
-            note_prefix,
-            tx_type,
-            sig_type,
-            txid,
-            round,
-            min_round,
-            max_round,
-            asset_id,
-            before_time,
-            after_time,
-            currency_greater_than,
-            currency_less_than,
-            address,
-            address_role,
-            exclude_close_to,
-            rekey_to,
-            application_id,
-        )
+        self.get(|| {
+            algonaut_indexer::apis::search_api::search_for_transactions(
+                &self.configuration,
+                limit,
+                next,
+                note_prefix,
+                tx_type,
+                sig_type,
+                txid,
+                round,
+                min_round,
+                max_round,
+                asset_id,
+                before_time.clone(),
+                after_time.clone(),
+                currency_greater_than,
+                currency_less_than,
+                address,
+                address_role,
+                exclude_close_to,
+                rekey_to,
+                application_id,
+            )
+        })
         .await
-        .map_err(Into::<IndexerError>::into)?)
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::Error;
+use crate::{client::RetryPolicy, Error};
 use algonaut_core::{Address, MultisigSignature, ToMsgPack};
 use algonaut_crypto::{Ed25519PublicKey, MasterDerivationKey};
 use algonaut_kmd::{kmd::v1::Client, token::ApiToken, Headers};
@@ -15,12 +15,14 @@ use algonaut_transaction::Transaction;
 #[derive(Debug, Clone)]
 pub struct Kmd {
     pub(crate) client: Client,
+    retry_policy: RetryPolicy,
 }
 
 impl Kmd {
     /// Build a v1 client for the Algorand key management daemon.
     ///
     /// For third party providers / custom headers, use [with_headers](Self::with_headers).
+    /// For timeouts or retries, use [ClientBuilder](crate::client::ClientBuilder).
     ///
     /// Returns an error if the url or token have an invalid format.
     pub fn new(url: &str, token: &str) -> Result<Kmd, Error> {
@@ -38,17 +40,35 @@ impl Kmd {
     pub fn with_headers(url: &str, headers: Headers) -> Result<Kmd, Error> {
         Ok(Kmd {
             client: Client::new(url, headers)?,
+            retry_policy: RetryPolicy::default(),
+        })
+    }
+
+    pub(crate) fn with_client(
+        url: &str,
+        token: &str,
+        client: reqwest::Client,
+        retry_policy: RetryPolicy,
+    ) -> Result<Kmd, Error> {
+        let token = ApiToken::parse(token)?.to_string();
+        Ok(Kmd {
+            client: Client::with_http_client(url, vec![("X-KMD-API-Token", &token)], client)?,
+            retry_policy,
         })
     }
 
     /// Retrieves the current version
     pub async fn versions(&self) -> Result<VersionsResponse, Error> {
-        Ok(self.client.versions().await?)
+        self.retry_policy
+            .retry(|| async { Ok(self.client.versions().await?) })
+            .await
     }
 
     /// List all of the wallets that kmd is aware of
     pub async fn list_wallets(&self) -> Result<ListWalletsResponse, Error> {
-        Ok(self.client.list_wallets().await?)
+        self.retry_policy
+            .retry(|| async { Ok(self.client.list_wallets().await?) })
+            .await
     }
 
     /// Creates a wallet
@@ -196,6 +216,25 @@ impl Kmd {
             .await?)
     }
 
+    /// Sign a transaction with the key of `public_key`, e.g. the auth address of a rekeyed sender
+    pub async fn sign_transaction_with_public_key(
+        &self,
+        wallet_handle: &str,
+        wallet_password: &str,
+        transaction: &Transaction,
+        public_key: Ed25519PublicKey,
+    ) -> Result<SignTransactionResponse, Error> {
+        Ok(self
+            .client
+            .sign_transaction_with_public_key(
+                wallet_handle,
+                wallet_password,
+                transaction.to_msg_pack()?,
+                public_key,
+            )
+            .await?)
+    }
+
     /// Lists all of the multisig accounts whose preimages this wallet stores
     pub async fn list_multisig(&self, wallet_handle: &str) -> Result<ListMultisigResponse, Error> {
         Ok(self.client.list_multisig(wallet_handle).await?)
@@ -22,6 +22,8 @@ pub mod kmd;
 
 pub mod atomic_transaction_composer;
 
+pub mod client;
+
 pub mod error;
 pub use error::Error;
 
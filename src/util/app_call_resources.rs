@@ -0,0 +1,559 @@
+use super::{assign_group_id, check_txn_result_count, simulate_unsigned_group};
+use crate::{algod::v2::Algod, Error};
+use algonaut_algod::models::{SimulateTransactionGroupResult, SimulateUnnamedResourcesAccessed};
+use algonaut_core::{consensus::ConsensusParams, Address};
+use algonaut_transaction::{
+    transaction::{ApplicationCallTransaction, BoxReference},
+    Transaction, TransactionType,
+};
+
+/// Fills the reference arrays (accounts, foreign apps, foreign assets and boxes) of the app calls in `txs`.
+///
+/// The group is simulated with unnamed resources allowed, and the resources the simulation
+/// reports are added to the app calls, see [apply_unnamed_resources].
+///
+/// The reference limits are those of the node's current consensus protocol.
+///
+/// The transactions don't have to be signed. If they have a group id, it's recomputed.
+pub async fn populate_app_call_resources(
+    algod: &Algod,
+    txs: &[Transaction],
+) -> Result<Vec<Transaction>, Error> {
+    let grouped = txs.iter().any(|tx| tx.group.is_some());
+
+    let group_result =
+        simulate_unsigned_group(algod.simulate().allow_unnamed_resources(true), txs).await?;
+    let mut txs = txs.to_vec();
+
+    // a protocol newer than this crate likely didn't lower the limits, so the latest known are used
+    let params = ConsensusParams::for_version(&algod.txn_params().await?.consensus_version)
+        .unwrap_or_default();
+    apply_unnamed_resources(&mut txs, &group_result, &params)?;
+    assign_group_id(&mut txs, grouped)?;
+    Ok(txs)
+}
+
+/// Adds the unnamed resources reported by a simulation of `txs` to the reference arrays of the app calls.
+///
+/// Resources accessed by a single transaction are added to that transaction.
+/// Resources shared by the group are packed into the app calls in group order, within the per
+/// transaction reference limits of `params`. Local states and asset holdings are placed next to the app or
+/// account they belong to if possible.
+///
+/// Returns an error if a resource doesn't fit anywhere.
+pub fn apply_unnamed_resources(
+    txs: &mut [Transaction],
+    group_result: &SimulateTransactionGroupResult,
+    params: &ConsensusParams,
+) -> Result<(), Error> {
+    check_txn_result_count(group_result, txs.len())?;
+
+    for (i, (tx, res)) in txs
+        .iter_mut()
+        .zip(group_result.txn_results.iter())
+        .enumerate()
+    {
+        if let Some(resources) = &res.unnamed_resources_accessed {
+            let resources = Resources::new(resources)?;
+            if !resources.is_empty() {
+                let sender = tx.sender();
+                let call = app_call_mut(tx).ok_or_else(|| {
+                    Error::Msg(format!(
+                        "Transaction {i} accessed unnamed resources, but it isn't an app call"
+                    ))
+                })?;
+                add_tx_resources(call, &sender, resources, params)
+                    .map_err(|e| Error::Msg(format!("Transaction {i}: {e}")))?;
+            }
+        }
+    }
+
+    if let Some(resources) = &group_result.unnamed_resources_accessed {
+        add_group_resources(txs, Resources::new(resources)?, params)?;
+    }
+    Ok(())
+}
+
+/// Unnamed resources, with parsed addresses.
+#[derive(Debug, Default)]
+struct Resources {
+    accounts: Vec<Address>,
+    app_locals: Vec<(Address, u64)>,
+    apps: Vec<u64>,
+    asset_holdings: Vec<(Address, u64)>,
+    assets: Vec<u64>,
+    boxes: Vec<BoxReference>,
+    extra_box_refs: u64,
+}
+
+impl Resources {
+    fn new(resources: &SimulateUnnamedResourcesAccessed) -> Result<Resources, Error> {
+        let opt_vec = |v: &Option<Vec<u64>>| v.clone().unwrap_or_default();
+        Ok(Resources {
+            accounts: resources
+                .accounts
+                .iter()
+                .flatten()
+                .map(|a| a.parse::<Address>())
+                .collect::<Result<_, _>>()?,
+            app_locals: resources
+                .app_locals
+                .iter()
+                .flatten()
+                .map(|r| Ok((r.account.parse::<Address>()?, r.app)))
+                .collect::<Result<_, Error>>()?,
+            apps: opt_vec(&resources.apps),
+            asset_holdings: resources
+                .asset_holdings
+                .iter()
+                .flatten()
+                .map(|r| Ok((r.account.parse::<Address>()?, r.asset)))
+                .collect::<Result<_, Error>>()?,
+            assets: opt_vec(&resources.assets),
+            boxes: resources
+                .boxes
+                .iter()
+                .flatten()
+                .map(|b| BoxReference {
+                    app_id: Some(b.app),
+                    name: b.name.0.clone(),
+                })
+                .collect(),
+            extra_box_refs: resources.extra_box_refs.unwrap_or(0),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+            && self.app_locals.is_empty()
+            && self.apps.is_empty()
+            && self.asset_holdings.is_empty()
+            && self.assets.is_empty()
+            && self.boxes.is_empty()
+            && self.extra_box_refs == 0
+    }
+}
+
+/// References to be added to an app call.
+#[derive(Debug, Default)]
+struct Refs {
+    accounts: Vec<Address>,
+    apps: Vec<u64>,
+    assets: Vec<u64>,
+    boxes: Vec<BoxReference>,
+}
+
+/// Adds resources that can only be accessed by the app call that accessed them.
+fn add_tx_resources(
+    call: &mut ApplicationCallTransaction,
+    sender: &Address,
+    resources: Resources,
+    params: &ConsensusParams,
+) -> Result<(), Error> {
+    let mut refs = Refs {
+        accounts: resources.accounts,
+        apps: resources.apps,
+        assets: resources.assets,
+        boxes: resources.boxes,
+    };
+    for (account, app) in resources.app_locals {
+        refs.accounts.push(account);
+        refs.apps.push(app);
+    }
+    for (account, asset) in resources.asset_holdings {
+        refs.accounts.push(account);
+        refs.assets.push(asset);
+    }
+    for _ in 0..resources.extra_box_refs {
+        refs.boxes.push(empty_box_ref());
+    }
+    if !try_add_refs(call, sender, &refs, params) {
+        return Err(Error::Msg(
+            "accessed resources exceed the app call reference limits".to_owned(),
+        ));
+    }
+    Ok(())
+}
+
+/// Adds resources that can be accessed by any transaction of the group.
+fn add_group_resources(
+    txs: &mut [Transaction],
+    resources: Resources,
+    params: &ConsensusParams,
+) -> Result<(), Error> {
+    for (account, app) in &resources.app_locals {
+        add_to_group(
+            txs,
+            params,
+            |call, sender| has_account(call, sender, account) || has_app(call, *app),
+            &Refs {
+                accounts: vec![*account],
+                apps: vec![*app],
+                ..Refs::default()
+            },
+            &format!("local state of app {app} for account {account}"),
+        )?;
+    }
+    for (account, asset) in &resources.asset_holdings {
+        add_to_group(
+            txs,
+            params,
+            |call, sender| has_account(call, sender, account) || has_asset(call, *asset),
+            &Refs {
+                accounts: vec![*account],
+                assets: vec![*asset],
+                ..Refs::default()
+            },
+            &format!("holding of asset {asset} for account {account}"),
+        )?;
+    }
+    for account in &resources.accounts {
+        add_to_group(
+            txs,
+            params,
+            |_, _| false,
+            &Refs {
+                accounts: vec![*account],
+                ..Refs::default()
+            },
+            &format!("account {account}"),
+        )?;
+    }
+    for box_ in &resources.boxes {
+        // the box's app has to be available in the same app call
+        let app = box_.app_id.unwrap_or(0);
+        add_to_group(
+            txs,
+            params,
+            |call, _| has_app(call, app),
+            &Refs {
+                apps: vec![app],
+                boxes: vec![box_.clone()],
+                ..Refs::default()
+            },
+            &format!("box {:?} of app {app}", box_.name),
+        )?;
+    }
+    for asset in &resources.assets {
+        add_to_group(
+            txs,
+            params,
+            |_, _| false,
+            &Refs {
+                assets: vec![*asset],
+                ..Refs::default()
+            },
+            &format!("asset {asset}"),
+        )?;
+    }
+    for app in &resources.apps {
+        add_to_group(
+            txs,
+            params,
+            |_, _| false,
+            &Refs {
+                apps: vec![*app],
+                ..Refs::default()
+            },
+            &format!("app {app}"),
+        )?;
+    }
+    for _ in 0..resources.extra_box_refs {
+        add_to_group(
+            txs,
+            params,
+            |_, _| false,
+            &Refs {
+                boxes: vec![empty_box_ref()],
+                ..Refs::default()
+            },
+            "extra box reference",
+        )?;
+    }
+    Ok(())
+}
+
+/// Adds `refs` to the first app call of the group where they fit,
+/// trying first the app calls for which `preferred` returns true.
+fn add_to_group<F>(
+    txs: &mut [Transaction],
+    params: &ConsensusParams,
+    preferred: F,
+    refs: &Refs,
+    description: &str,
+) -> Result<(), Error>
+where
+    F: Fn(&ApplicationCallTransaction, &Address) -> bool,
+{
+    for only_preferred in [true, false] {
+        for tx in txs.iter_mut() {
+            let sender = tx.sender();
+            if let Some(call) = app_call_mut(tx) {
+                if (!only_preferred || preferred(call, &sender))
+                    && try_add_refs(call, &sender, refs, params)
+                {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Err(Error::Msg(format!(
+        "No app call in the group has room for a reference to the {description}"
+    )))
+}
+
+/// Adds the references that aren't available yet to the app call, if they fit within the limits
+/// of `params`. Returns whether they were added.
+fn try_add_refs(
+    call: &mut ApplicationCallTransaction,
+    sender: &Address,
+    refs: &Refs,
+    params: &ConsensusParams,
+) -> bool {
+    let mut new = Refs::default();
+    for account in &refs.accounts {
+        if !has_account(call, sender, account) && !new.accounts.contains(account) {
+            new.accounts.push(*account);
+        }
+    }
+    for app in &refs.apps {
+        if !has_app(call, *app) && !new.apps.contains(app) {
+            new.apps.push(*app);
+        }
+    }
+    for asset in &refs.assets {
+        if !has_asset(call, *asset) && !new.assets.contains(asset) {
+            new.assets.push(*asset);
+        }
+    }
+    for box_ in &refs.boxes {
+        // empty box refs only add IO budget, so they're never considered duplicates
+        if box_.name.is_empty() || !has_box(call, box_) {
+            new.boxes.push(box_.clone());
+        }
+    }
+
+    let accounts = (len(&call.accounts) + new.accounts.len()) as u64;
+    let apps = (len(&call.foreign_apps) + new.apps.len()) as u64;
+    let assets = (len(&call.foreign_assets) + new.assets.len()) as u64;
+    let boxes = (len(&call.boxes) + new.boxes.len()) as u64;
+    if accounts > params.max_app_txn_accounts
+        || apps > params.max_app_txn_foreign_apps
+        || assets > params.max_app_txn_foreign_assets
+        || boxes > params.max_app_box_references
+        || accounts + apps + assets + boxes > params.max_app_total_txn_references
+    {
+        return false;
+    }
+
+    // appending keeps the indexes of existing references (e.g. ABI arguments) valid
+    extend(&mut call.accounts, new.accounts);
+    extend(&mut call.foreign_apps, new.apps);
+    extend(&mut call.foreign_assets, new.assets);
+    extend(&mut call.boxes, new.boxes);
+    true
+}
+
+fn app_call_mut(tx: &mut Transaction) -> Option<&mut ApplicationCallTransaction> {
+    match &mut tx.txn_type {
+        TransactionType::ApplicationCallTransaction(call) => Some(call),
+        _ => None,
+    }
+}
+
+fn has_account(call: &ApplicationCallTransaction, sender: &Address, account: &Address) -> bool {
+    account == sender || call.accounts.iter().flatten().any(|a| a == account)
+}
+
+fn has_app(call: &ApplicationCallTransaction, app: u64) -> bool {
+    // app 0 refers to the called app
+    app == 0 || call.app_id == Some(app) || call.foreign_apps.iter().flatten().any(|a| *a == app)
+}
+
+fn has_asset(call: &ApplicationCallTransaction, asset: u64) -> bool {
+    call.foreign_assets.iter().flatten().any(|a| *a == asset)
+}
+
+fn has_box(call: &ApplicationCallTransaction, box_: &BoxReference) -> bool {
+    let app_id = |b: &BoxReference| match b.app_id {
+        Some(0) | None => call.app_id.unwrap_or(0),
+        Some(app_id) => app_id,
+    };
+    call.boxes
+        .iter()
+        .flatten()
+        .any(|b| b.name == box_.name && app_id(b) == app_id(box_))
+}
+
+fn empty_box_ref() -> BoxReference {
+    BoxReference {
+        app_id: None,
+        name: vec![],
+    }
+}
+
+fn len<T>(v: &Option<Vec<T>>) -> usize {
+    v.as_ref().map_or(0, |v| v.len())
+}
+
+fn extend<T>(v: &mut Option<Vec<T>>, items: Vec<T>) {
+    if !items.is_empty() {
+        v.get_or_insert_with(Vec::new).extend(items);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_fixtures::{app_call, payment};
+    use algonaut_core::consensus::{CONSENSUS_V35, CONSENSUS_V36};
+    use algonaut_transaction::account::Account;
+
+    fn call(tx: &Transaction) -> &ApplicationCallTransaction {
+        match &tx.txn_type {
+            TransactionType::ApplicationCallTransaction(call) => call,
+            _ => panic!("not an app call"),
+        }
+    }
+
+    fn group_result(json: serde_json::Value) -> SimulateTransactionGroupResult {
+        serde_json::from_str(&json.to_string()).unwrap()
+    }
+
+    fn txn_result(resources: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "txn-result": { "pool-error": "", "txn": {} },
+            "unnamed-resources-accessed": resources
+        })
+    }
+
+    #[test]
+    fn test_adds_tx_resources_to_accessing_tx() {
+        let sender = Account::generate().address();
+        let other = Account::generate().address();
+        let mut txs = vec![app_call(&sender, 1), app_call(&sender, 2)];
+
+        let result = group_result(serde_json::json!({
+            "txn-results": [
+                txn_result(serde_json::json!({})),
+                txn_result(serde_json::json!({
+                    "accounts": [other.to_string(), sender.to_string()],
+                    "apps": [3],
+                    "assets": [4],
+                    "boxes": [{ "app": 2, "name": "Ym94" }]
+                }))
+            ]
+        }));
+        apply_unnamed_resources(&mut txs, &result, &ConsensusParams::default()).unwrap();
+
+        assert_eq!(None, call(&txs[0]).accounts);
+        let call = call(&txs[1]);
+        assert_eq!(Some(vec![other]), call.accounts);
+        assert_eq!(Some(vec![3]), call.foreign_apps);
+        assert_eq!(Some(vec![4]), call.foreign_assets);
+        assert_eq!(
+            Some(vec![BoxReference {
+                app_id: Some(2),
+                name: b"box".to_vec()
+            }]),
+            call.boxes
+        );
+    }
+
+    #[test]
+    fn test_tx_resources_of_non_app_call_fail() {
+        let sender = Account::generate().address();
+        let mut txs = vec![payment(&sender, 1)];
+
+        let result = group_result(serde_json::json!({
+            "txn-results": [txn_result(serde_json::json!({ "assets": [4] }))]
+        }));
+
+        assert!(apply_unnamed_resources(&mut txs, &result, &ConsensusParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_packs_group_resources() {
+        let sender = Account::generate().address();
+        let holder = Account::generate().address();
+        let mut txs = vec![
+            payment(&sender, 1),
+            app_call(&sender, 1),
+            app_call(&sender, 2),
+        ];
+
+        let result = group_result(serde_json::json!({
+            "txn-results": [
+                txn_result(serde_json::json!({})),
+                txn_result(serde_json::json!({})),
+                txn_result(serde_json::json!({}))
+            ],
+            "unnamed-resources-accessed": {
+                "app-locals": [{ "account": holder.to_string(), "app": 2 }],
+                "asset-holdings": [{ "account": holder.to_string(), "asset": 10 }],
+                "boxes": [{ "app": 2, "name": "Ym94" }],
+                "assets": [11, 12, 13, 14, 15, 16, 17, 18],
+                "extra-box-refs": 1
+            }
+        }));
+        apply_unnamed_resources(&mut txs, &result, &ConsensusParams::default()).unwrap();
+
+        // the local state and the box go to the call of app 2, the holding next to the account
+        let second_call = call(&txs[2]);
+        assert_eq!(Some(vec![holder]), second_call.accounts);
+        assert_eq!(Some(vec![10]), second_call.foreign_assets);
+        assert_eq!(None, second_call.foreign_apps);
+        // the assets fill the first call up to the limit, so the extra box ref goes to the second
+        let first_call = call(&txs[1]);
+        assert_eq!(
+            Some(vec![11, 12, 13, 14, 15, 16, 17, 18]),
+            first_call.foreign_assets
+        );
+        assert_eq!(
+            Some(vec![
+                BoxReference {
+                    app_id: Some(2),
+                    name: b"box".to_vec()
+                },
+                empty_box_ref()
+            ]),
+            second_call.boxes
+        );
+        assert_eq!(None, first_call.boxes);
+    }
+
+    #[test]
+    fn test_group_resources_exceeding_limits_fail() {
+        let sender = Account::generate().address();
+        let mut txs = vec![app_call(&sender, 1)];
+
+        let result = group_result(serde_json::json!({
+            "txn-results": [txn_result(serde_json::json!({}))],
+            "unnamed-resources-accessed": {
+                "assets": [1, 2, 3, 4, 5, 6, 7, 8, 9]
+            }
+        }));
+
+        assert!(apply_unnamed_resources(&mut txs, &result, &ConsensusParams::default()).is_err());
+    }
+
+    #[test]
+    fn test_uses_reference_limits_of_params() {
+        let sender = Account::generate().address();
+        let result = group_result(serde_json::json!({
+            "txn-results": [txn_result(serde_json::json!({
+                "boxes": [{ "app": 1, "name": "Ym94" }]
+            }))]
+        }));
+
+        // boxes can be referenced only since v36
+        let before_boxes = ConsensusParams::for_version(CONSENSUS_V35).unwrap();
+        let mut txs = vec![app_call(&sender, 1)];
+        assert!(apply_unnamed_resources(&mut txs, &result, &before_boxes).is_err());
+
+        let with_boxes = ConsensusParams::for_version(CONSENSUS_V36).unwrap();
+        let mut txs = vec![app_call(&sender, 1)];
+        apply_unnamed_resources(&mut txs, &result, &with_boxes).unwrap();
+        assert_eq!(1, len(&call(&txs[0]).boxes));
+    }
+}
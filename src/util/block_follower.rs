@@ -0,0 +1,327 @@
+use crate::{algod::v2::Algod, client::RetryPolicy, Error};
+use algonaut_algod::ext::block::BlockResponse;
+use async_trait::async_trait;
+use futures::{stream, Stream};
+use std::{
+    fmt::Debug,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+/// Persists the cursor of a [BlockFollower]: the round of the next block to fetch.
+// not `Send` on WASM, for stores backed by a http client, see `TransactionSigner`
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait CursorStore: Debug + Send + Sync {
+    /// Loads the saved cursor, or none if nothing was saved yet.
+    async fn load(&self) -> Result<Option<u64>, Error>;
+
+    /// Saves the cursor.
+    async fn save(&self, next_round: u64) -> Result<(), Error>;
+}
+
+/// Keeps the cursor in memory, so following can't be resumed after a restart.
+#[derive(Debug, Default)]
+pub struct InMemoryCursorStore {
+    next_round: Mutex<Option<u64>>,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl CursorStore for InMemoryCursorStore {
+    async fn load(&self) -> Result<Option<u64>, Error> {
+        Ok(*self
+            .next_round
+            .lock()
+            .map_err(|e| Error::Msg(e.to_string()))?)
+    }
+
+    async fn save(&self, next_round: u64) -> Result<(), Error> {
+        *self
+            .next_round
+            .lock()
+            .map_err(|e| Error::Msg(e.to_string()))? = Some(next_round);
+        Ok(())
+    }
+}
+
+/// Follows the chain, block by block, in order.
+///
+/// Blocks behind the node's last round are fetched right away, to catch up,
+/// afterwards the follower waits for each new block with [Algod::status_after_block].
+/// Requests failing with a transient error (e.g. because the node is unreachable) are retried
+/// with an exponential backoff, other errors are returned right away.
+///
+/// The cursor is saved when the next block is requested from the stream,
+/// i.e. after the previous block was processed, so no block is skipped when resuming.
+#[derive(Debug, Clone)]
+pub struct BlockFollower {
+    algod: Algod,
+    store: Arc<dyn CursorStore>,
+    start_round: u64,
+    retry_policy: RetryPolicy,
+}
+
+struct FollowerState<F> {
+    follower: BlockFollower,
+    fetch: F,
+    next_round: Option<u64>,
+    last_round: u64,
+    processed_round: Option<u64>,
+}
+
+impl BlockFollower {
+    /// Creates a follower that starts at the saved cursor or, if none was saved, at `start_round`.
+    pub fn new(algod: Algod, store: Arc<dyn CursorStore>, start_round: u64) -> BlockFollower {
+        BlockFollower {
+            algod,
+            store,
+            start_round,
+            retry_policy: RetryPolicy {
+                max_retries: 10,
+                max_retry_delay_ms: 30_000,
+            },
+        }
+    }
+
+    /// Sets how often a request failing with a transient error is retried before the stream
+    /// returns the error and ends.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the max. delay between retries, in milliseconds.
+    pub fn max_retry_delay_ms(mut self, max_retry_delay_ms: u32) -> Self {
+        self.retry_policy.max_retry_delay_ms = max_retry_delay_ms;
+        self
+    }
+
+    /// Returns a stream of the followed blocks.
+    ///
+    /// The stream only ends after returning an error.
+    pub fn stream(self) -> impl Stream<Item = Result<BlockResponse, Error>> {
+        let algod = self.algod.clone();
+        self.stream_with(move |round| {
+            let algod = algod.clone();
+            async move { algod.block(round).await }
+        })
+    }
+
+    /// Like [BlockFollower::stream], fetching each round with `fetch`,
+    /// e.g. to request the block as msgpack or to fall back to another source.
+    ///
+    /// Errors of `fetch` are retried like errors fetching blocks.
+    pub fn stream_with<T, F, Fut>(self, fetch: F) -> impl Stream<Item = Result<T, Error>>
+    where
+        F: Fn(u64) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let state = FollowerState {
+            follower: self,
+            fetch,
+            next_round: None,
+            last_round: 0,
+            processed_round: None,
+        };
+        stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_block().await {
+                Ok(block) => Some((Ok(block), Some(state))),
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+}
+
+impl<F> FollowerState<F> {
+    async fn next_block<T, Fut>(&mut self) -> Result<T, Error>
+    where
+        F: Fn(u64) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let follower = &self.follower;
+        let next_round = match (self.processed_round.take(), self.next_round) {
+            (Some(processed_round), _) => {
+                follower.store.save(processed_round + 1).await?;
+                processed_round + 1
+            }
+            (None, Some(next_round)) => next_round,
+            (None, None) => follower.store.load().await?.unwrap_or(follower.start_round),
+        };
+        self.next_round = Some(next_round);
+
+        let retry_policy = &follower.retry_policy;
+        loop {
+            if next_round > self.last_round {
+                // returns when the block is available or after a server side timeout
+                self.last_round = retry_policy
+                    .retry(|| {
+                        follower
+                            .algod
+                            .status_after_block(next_round.saturating_sub(1))
+                    })
+                    .await?
+                    .last_round;
+            } else {
+                let block = retry_policy.retry(|| (self.fetch)(next_round)).await?;
+                self.processed_round = Some(next_round);
+                return Ok(block);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{
+        test_fixtures::status,
+        test_server::{TestResponse, TestServer},
+    };
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+    #[tokio::test]
+    async fn test_in_memory_cursor_store() {
+        let store = InMemoryCursorStore::default();
+        assert_eq!(None, store.load().await.unwrap());
+
+        store.save(10).await.unwrap();
+
+        assert_eq!(Some(10), store.load().await.unwrap());
+    }
+
+    /// A node at `last_round`, producing a block whenever a client waits at the tip.
+    /// The first `failing_block_requests` block requests fail with `failure_status`.
+    fn node(last_round: u64, failing_block_requests: u32, failure_status: u16) -> TestServer {
+        let last_round = Arc::new(AtomicU64::new(last_round));
+        let failures = Arc::new(AtomicU32::new(failing_block_requests));
+        TestServer::start(move |request| {
+            let path = request.path.as_str();
+            if let Some(round) = path.strip_prefix("/v2/status/wait-for-block-after/") {
+                let round: u64 = round.parse().unwrap();
+                let current = last_round.load(Ordering::SeqCst);
+                if current > round {
+                    TestResponse::json(&status(current))
+                } else {
+                    TestResponse::json(&status(last_round.fetch_add(1, Ordering::SeqCst) + 1))
+                }
+            } else if let Some(round) = path.strip_prefix("/v2/blocks/") {
+                if failures.load(Ordering::SeqCst) > 0 {
+                    failures.fetch_sub(1, Ordering::SeqCst);
+                    return TestResponse::error(failure_status, "failed");
+                }
+                let round = round.split('?').next().unwrap();
+                TestResponse::json(&format!(r#"{{"block":{{"rnd":{round}}}}}"#))
+            } else {
+                TestResponse::error(404, "not found")
+            }
+        })
+    }
+
+    fn follower(node: &TestServer, store: Arc<dyn CursorStore>, start_round: u64) -> BlockFollower {
+        BlockFollower::new(Algod::new(&node.url, "").unwrap(), store, start_round)
+            .max_retry_delay_ms(1)
+    }
+
+    /// The rounds of the next `count` blocks of the stream.
+    async fn rounds(
+        stream: &mut (impl Stream<Item = Result<BlockResponse, Error>> + Unpin),
+        count: usize,
+    ) -> Vec<u64> {
+        let mut rounds = vec![];
+        for _ in 0..count {
+            let block = stream.next().await.unwrap().unwrap();
+            rounds.push(block.block.round.unwrap());
+        }
+        rounds
+    }
+
+    fn paths(node: &TestServer) -> Vec<String> {
+        node.paths()
+            .into_iter()
+            .map(|p| p.split('?').next().unwrap().to_owned())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_catches_up_then_waits_for_new_blocks() {
+        let node = node(5, 0, 0);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(follower(&node, store, 3).stream());
+
+        assert_eq!(vec![3, 4, 5, 6, 7], rounds(&mut stream, 5).await);
+
+        assert_eq!(
+            vec![
+                "/v2/status/wait-for-block-after/2",
+                "/v2/blocks/3",
+                "/v2/blocks/4",
+                "/v2/blocks/5",
+                "/v2/status/wait-for-block-after/5",
+                "/v2/blocks/6",
+                "/v2/status/wait-for-block-after/6",
+                "/v2/blocks/7",
+            ],
+            paths(&node)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resumes_from_saved_cursor() {
+        let node = node(20, 0, 0);
+        let store = Arc::new(InMemoryCursorStore::default());
+        store.save(10).await.unwrap();
+        let mut stream = Box::pin(follower(&node, store.clone(), 1).stream());
+
+        assert_eq!(vec![10, 11, 12], rounds(&mut stream, 3).await);
+
+        // block 12 hasn't been processed yet
+        assert_eq!(Some(12), store.load().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let node = node(5, 2, 503);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(follower(&node, store, 5).max_retries(2).stream());
+
+        assert_eq!(vec![5], rounds(&mut stream, 1).await);
+
+        let block_requests = paths(&node)
+            .iter()
+            .filter(|p| p.starts_with("/v2/blocks/"))
+            .count();
+        assert_eq!(3, block_requests);
+    }
+
+    #[tokio::test]
+    async fn test_returns_transient_error_after_max_retries() {
+        let node = node(5, 3, 503);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(follower(&node, store, 5).max_retries(2).stream());
+
+        let err = stream.next().await.unwrap().unwrap_err();
+
+        assert!(err.is_transient(), "not the original error: {:?}", err);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_returns_non_transient_error_without_retrying() {
+        let node = node(5, 1, 400);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(follower(&node, store, 5).stream());
+
+        let err = stream.next().await.unwrap().unwrap_err();
+
+        assert!(!err.is_transient());
+        assert!(stream.next().await.is_none());
+        let block_requests = paths(&node)
+            .iter()
+            .filter(|p| p.starts_with("/v2/blocks/"))
+            .count();
+        assert_eq!(1, block_requests);
+    }
+}
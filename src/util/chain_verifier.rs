@@ -0,0 +1,152 @@
+use crate::Error;
+use algonaut_algod::ext::{block::Block, block_header::BlockHeader};
+use algonaut_crypto::HashDigest;
+use data_encoding::BASE32_NOPAD;
+
+/// Verifies that `blocks` form a chain: consecutive rounds, each block referencing the block before
+/// with its `prev` hash. Returns the hash of the last block.
+///
+/// Detects forks and blocks tampered with (by an untrusted node) within the batch.
+/// On its own, this doesn't show that the batch belongs to the canonical chain: check that the returned hash
+/// matches a trusted one, or verify the batch with [verify_chain_from], starting at a trusted hash.
+pub fn verify_chain(blocks: &[Block]) -> Result<HashDigest, Error> {
+    let (first, rest) = blocks
+        .split_first()
+        .ok_or_else(|| Error::Msg("No blocks to verify".to_owned()))?;
+    let first = header(first)?;
+    let hash = block_hash(&first)?;
+    verify_links(first.round, hash, rest)
+}
+
+/// Verifies that `blocks` form a chain following the block with the hash `prev_hash`,
+/// e.g. the hash returned when verifying the previous batch. Returns the hash of the last block.
+pub fn verify_chain_from(prev_hash: &HashDigest, blocks: &[Block]) -> Result<HashDigest, Error> {
+    match blocks.first() {
+        Some(first) => {
+            let round = header(first)?.round;
+            if round == 0 {
+                return Err(Error::Msg(
+                    "The genesis block has no previous block".to_owned(),
+                ));
+            }
+            verify_links(round - 1, *prev_hash, blocks)
+        }
+        None => Ok(*prev_hash),
+    }
+}
+
+fn verify_links(
+    mut prev_round: u64,
+    mut prev_hash: HashDigest,
+    blocks: &[Block],
+) -> Result<HashDigest, Error> {
+    for block in blocks {
+        let header = header(block)?;
+        if header.round != prev_round + 1 {
+            return Err(Error::Msg(format!(
+                "Expected block: {}, got: {}",
+                prev_round + 1,
+                header.round
+            )));
+        }
+        if header.branch != prev_hash {
+            return Err(Error::Msg(format!(
+                "Block: {} doesn't follow block: {prev_round}, its prev is: {}, but block: {prev_round} hashes to: {}",
+                header.round,
+                display_block_hash(&header.branch),
+                display_block_hash(&prev_hash)
+            )));
+        }
+        prev_round = header.round;
+        prev_hash = block_hash(&header)?;
+    }
+    Ok(prev_hash)
+}
+
+fn header(block: &Block) -> Result<BlockHeader, Error> {
+    block
+        .header()
+        .map_err(|e| Error::Msg(format!("Couldn't read block header: {e}")))
+}
+
+fn block_hash(header: &BlockHeader) -> Result<HashDigest, Error> {
+    Ok(header.hash()?)
+}
+
+/// Block hashes are displayed like algod does, e.g. in `prev`.
+fn display_block_hash(hash: &HashDigest) -> String {
+    format!("blk-{}", BASE32_NOPAD.encode(&hash.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A chain of blocks following the block with the hash `prev`.
+    fn chain(prev: &HashDigest, first_round: u64, len: u64) -> Vec<Block> {
+        let mut prev = *prev;
+        (first_round..first_round + len)
+            .map(|round| {
+                let block: Block = serde_json::from_value(serde_json::json!({
+                    "gen": "mainnet-v1.0",
+                    "gh": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                    "prev": display_block_hash(&prev),
+                    "proto": "https://github.com/algorandfoundation/specs/tree/236dcc18c9c507d794813ab768e467ea42d1b4d9",
+                    "rnd": round,
+                    "ts": 1736510450 + round,
+                    "spt": { "0": { "n": 46513152, "t": 1000, "v": "4Wc2EpwiEdNcflxBRoUEaqJxGAjIOkAW2mBmbZX6Ptk=" } },
+                }))
+                .unwrap();
+                prev = block.header().unwrap().hash().unwrap();
+                block
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_verifies_chain() {
+        let prev = HashDigest([7; 32]);
+        let blocks = chain(&prev, 100, 4);
+
+        let hash = verify_chain(&blocks).unwrap();
+
+        assert_eq!(hash, blocks[3].header().unwrap().hash().unwrap());
+        assert_eq!(hash, verify_chain_from(&prev, &blocks).unwrap());
+        // batches can be verified one after the other
+        let next = chain(&hash, 104, 2);
+        verify_chain_from(&hash, &next).unwrap();
+    }
+
+    #[test]
+    fn test_detects_tampered_block() {
+        let mut blocks = chain(&HashDigest([7; 32]), 100, 4);
+        blocks[1].timestamp = Some(0);
+
+        let err = verify_chain(&blocks).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("Block: 102 doesn't follow block: 101"));
+    }
+
+    #[test]
+    fn test_detects_fork() {
+        let prev = HashDigest([7; 32]);
+        let mut blocks = chain(&prev, 100, 2);
+        let fork = chain(&HashDigest([8; 32]), 102, 1);
+        blocks.extend(fork);
+
+        assert!(verify_chain(&blocks).is_err());
+        assert!(verify_chain_from(&HashDigest([8; 32]), &chain(&prev, 100, 1)).is_err());
+    }
+
+    #[test]
+    fn test_detects_missing_round() {
+        let mut blocks = chain(&HashDigest([7; 32]), 100, 3);
+        blocks.remove(1);
+
+        let err = verify_chain(&blocks).unwrap_err();
+
+        assert!(err.to_string().contains("Expected block: 101, got: 102"));
+    }
+}
@@ -0,0 +1,242 @@
+use super::{check_txn_result_count, simulate_unsigned_group};
+use crate::{algod::v2::Algod, Error};
+use algonaut_algod::models::{PendingTransactionResponse, SimulateTransactionGroupResult};
+use algonaut_core::{MicroAlgos, SuggestedTransactionParams};
+use algonaut_transaction::Transaction;
+
+/// Plans the fees of a transaction group, so that the inner transactions issued by its app calls
+/// are covered by the fee of one of the group's transactions (the fee payer).
+///
+/// The fees are pooled: the fee payer is only raised by the amount the group's current fees don't cover.
+#[derive(Debug, Clone)]
+pub struct GroupFeePlanner {
+    params: SuggestedTransactionParams,
+    fee_payer: usize,
+    max_fee: MicroAlgos,
+}
+
+/// The fees planned by [GroupFeePlanner].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeePlan {
+    /// The number of inner transactions issued by each transaction of the group, including nested ones.
+    pub inner_tx_counts: Vec<u64>,
+    /// The total fee the group has to pay.
+    pub required_fee: MicroAlgos,
+    /// The fee of each transaction of the group.
+    pub fees: Vec<MicroAlgos>,
+}
+
+impl GroupFeePlanner {
+    /// Creates a planner that raises the fee of the first transaction of the group, up to `max_fee`.
+    pub fn new(params: &SuggestedTransactionParams, max_fee: MicroAlgos) -> GroupFeePlanner {
+        GroupFeePlanner {
+            params: params.clone(),
+            fee_payer: 0,
+            max_fee,
+        }
+    }
+
+    /// Sets the index of the transaction that pays the missing fees.
+    pub fn fee_payer(mut self, index: usize) -> Self {
+        self.fee_payer = index;
+        self
+    }
+
+    /// Simulates `txs` and plans their fees.
+    ///
+    /// The fee payer is simulated with the max fee, so the inner transactions can be issued.
+    /// The transactions don't have to be signed.
+    pub async fn plan(&self, algod: &Algod, txs: &[Transaction]) -> Result<FeePlan, Error> {
+        let mut simulate_txs = txs.to_vec();
+        self.fee_payer_tx(&mut simulate_txs)?.fee = self.max_fee;
+        let group_result = simulate_unsigned_group(algod.simulate(), &simulate_txs).await?;
+
+        self.plan_with_simulation(txs, &group_result)
+    }
+
+    /// Plans the fees of `txs`, with the result of simulating them.
+    ///
+    /// Each transaction has to pay its estimated fee and each inner transaction the min fee,
+    /// minus what the inner transactions already pay themselves.
+    /// Returns an error if the fee payer's fee would exceed the max fee.
+    pub fn plan_with_simulation(
+        &self,
+        txs: &[Transaction],
+        group_result: &SimulateTransactionGroupResult,
+    ) -> Result<FeePlan, Error> {
+        check_txn_result_count(group_result, txs.len())?;
+        if self.fee_payer >= txs.len() {
+            return Err(fee_payer_out_of_bounds(self.fee_payer, txs.len()));
+        }
+
+        let mut required_fee = MicroAlgos(0);
+        let mut paid_fee = MicroAlgos(0);
+        let mut inner_tx_counts = vec![];
+        for (tx, res) in txs.iter().zip(group_result.txn_results.iter()) {
+            required_fee = required_fee + tx.estimate_basic_sig_fee_with_params(&self.params)?;
+            paid_fee = paid_fee + tx.fee;
+
+            let (count, inner_paid_fee) = inner_txs_fees(&res.txn_result);
+            required_fee = required_fee + self.params.min_fee * count;
+            paid_fee = paid_fee + inner_paid_fee;
+            inner_tx_counts.push(count);
+        }
+
+        let mut fees: Vec<MicroAlgos> = txs.iter().map(|tx| tx.fee).collect();
+        if required_fee > paid_fee {
+            let fee_payer_fee = fees[self.fee_payer] + (required_fee - paid_fee);
+            if fee_payer_fee > self.max_fee {
+                return Err(Error::Msg(format!(
+                    "Fee payer's fee: {} exceeds the max fee: {}",
+                    fee_payer_fee, self.max_fee
+                )));
+            }
+            fees[self.fee_payer] = fee_payer_fee;
+        }
+
+        Ok(FeePlan {
+            inner_tx_counts,
+            required_fee,
+            fees,
+        })
+    }
+
+    fn fee_payer_tx<'a>(&self, txs: &'a mut [Transaction]) -> Result<&'a mut Transaction, Error> {
+        let len = txs.len();
+        txs.get_mut(self.fee_payer)
+            .ok_or_else(|| fee_payer_out_of_bounds(self.fee_payer, len))
+    }
+}
+
+impl FeePlan {
+    /// Sets the planned fees in `txs`.
+    ///
+    /// Changing the fees invalidates the group id, so it has to be assigned afterwards.
+    pub fn apply(&self, txs: &mut [Transaction]) -> Result<(), Error> {
+        if txs.len() != self.fees.len() {
+            return Err(Error::Msg(format!(
+                "Fee plan is for {} transactions, got: {}",
+                self.fees.len(),
+                txs.len()
+            )));
+        }
+        for (tx, fee) in txs.iter_mut().zip(self.fees.iter()) {
+            tx.fee = *fee;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the number of inner transactions of `tx_result`, including nested ones, and the fees they pay.
+fn inner_txs_fees(tx_result: &PendingTransactionResponse) -> (u64, MicroAlgos) {
+    let mut count = 0;
+    let mut paid_fee = MicroAlgos(0);
+    for inner_tx in tx_result.inner_txns.iter().flatten() {
+        let (nested_count, nested_paid_fee) = inner_txs_fees(inner_tx);
+        count += 1 + nested_count;
+        paid_fee =
+            paid_fee + nested_paid_fee + inner_tx.txn["txn"]["fee"].as_u64().unwrap_or_default();
+    }
+    (count, paid_fee)
+}
+
+fn fee_payer_out_of_bounds(fee_payer: usize, len: usize) -> Error {
+    Error::Msg(format!(
+        "Fee payer index: {fee_payer} out of bounds for group of size: {len}"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_fixtures::{app_call, txn_params};
+    use algonaut_transaction::{account::Account, Pay, TxnBuilder};
+    use serde_json::json;
+
+    fn txs(fee: u64) -> Vec<Transaction> {
+        let address = Account::generate().address();
+        vec![
+            TxnBuilder::with_fee(
+                &txn_params(),
+                MicroAlgos(fee),
+                Pay::new(address, address, MicroAlgos(1)).build(),
+            )
+            .build()
+            .unwrap(),
+            app_call(&address, 1),
+        ]
+    }
+
+    /// A simulation where the app call issues 2 inner txs, the second of them 1 nested inner tx,
+    /// paying 1000 of their fees.
+    fn group_result() -> SimulateTransactionGroupResult {
+        let json = json!({
+            "txn-results": [
+                { "txn-result": { "pool-error": "", "txn": {} } },
+                {
+                    "txn-result": {
+                        "pool-error": "",
+                        "txn": {},
+                        "inner-txns": [
+                            { "pool-error": "", "txn": { "txn": { "fee": 1000 } } },
+                            {
+                                "pool-error": "",
+                                "txn": { "txn": {} },
+                                "inner-txns": [{ "pool-error": "", "txn": { "txn": {} } }]
+                            }
+                        ]
+                    }
+                }
+            ]
+        });
+        serde_json::from_str(&json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_raises_fee_payer_fee() {
+        let mut txs = txs(1000);
+
+        let plan = GroupFeePlanner::new(&txn_params().into(), MicroAlgos(10_000))
+            .plan_with_simulation(&txs, &group_result())
+            .unwrap();
+        plan.apply(&mut txs).unwrap();
+
+        assert_eq!(vec![0, 3], plan.inner_tx_counts);
+        assert_eq!(MicroAlgos(5000), plan.required_fee);
+        assert_eq!(vec![MicroAlgos(3000), MicroAlgos(1000)], plan.fees);
+        assert_eq!(MicroAlgos(3000), txs[0].fee);
+    }
+
+    #[test]
+    fn test_keeps_fees_if_covered() {
+        let txs = txs(4000);
+
+        let plan = GroupFeePlanner::new(&txn_params().into(), MicroAlgos(10_000))
+            .fee_payer(1)
+            .plan_with_simulation(&txs, &group_result())
+            .unwrap();
+
+        assert_eq!(vec![MicroAlgos(4000), MicroAlgos(1000)], plan.fees);
+    }
+
+    #[test]
+    fn test_fee_above_max_fee_fails() {
+        let txs = txs(1000);
+
+        let res = GroupFeePlanner::new(&txn_params().into(), MicroAlgos(2000))
+            .plan_with_simulation(&txs, &group_result());
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_fee_payer_out_of_bounds_fails() {
+        let txs = txs(1000);
+
+        let res = GroupFeePlanner::new(&txn_params().into(), MicroAlgos(10_000))
+            .fee_payer(2)
+            .plan_with_simulation(&txs, &group_result());
+
+        assert!(res.is_err());
+    }
+}
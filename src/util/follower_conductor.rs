@@ -0,0 +1,250 @@
+use super::block_follower::CursorStore;
+use crate::{algod::v2::Algod, client::RetryPolicy, Error};
+use algonaut_algod::ext::{block::BlockResponse, ledger_state_delta::LedgerStateDelta};
+use std::{future::Future, sync::Arc};
+
+/// A round fetched by a [FollowerConductor].
+#[derive(Debug, Clone)]
+pub struct FollowedRound {
+    pub round: u64,
+    pub block: BlockResponse,
+    pub delta: LedgerStateDelta,
+}
+
+/// Drives a follower node (a node with `EnableFollowMode` set) through the chain, round by round.
+///
+/// For each round, the conductor sets the node's sync round, waits until the node has the round,
+/// fetches its block and state delta and passes them to a handler.
+/// The cursor (the next round) is saved only after the handler succeeded,
+/// and the node is allowed to advance past the round only afterwards,
+/// so the node keeps the round's delta until it was processed.
+///
+/// Rounds are processed at least once: after a restart, the conductor resumes at the saved cursor,
+/// reprocessing the last round if the handler succeeded but the cursor wasn't saved.
+/// Follower nodes keep only a few rounds behind the sync round, so resuming fails if the node
+/// advanced further in the meantime (e.g. because the sync round was unset).
+///
+/// Requests failing with a transient error (see [Error::is_transient]) are retried
+/// with an exponential backoff, other errors are returned right away. Handler errors aren't retried.
+#[derive(Debug, Clone)]
+pub struct FollowerConductor {
+    algod: Algod,
+    store: Arc<dyn CursorStore>,
+    start_round: u64,
+    retry_policy: RetryPolicy,
+}
+
+impl FollowerConductor {
+    /// Creates a conductor that starts at the saved cursor or, if none was saved, at `start_round`.
+    pub fn new(algod: Algod, store: Arc<dyn CursorStore>, start_round: u64) -> FollowerConductor {
+        FollowerConductor {
+            algod,
+            store,
+            start_round,
+            retry_policy: RetryPolicy {
+                max_retries: 10,
+                max_retry_delay_ms: 30_000,
+            },
+        }
+    }
+
+    /// Sets how often a request failing with a transient error is retried before giving up.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the max. delay between retries, in milliseconds.
+    pub fn max_retry_delay_ms(mut self, max_retry_delay_ms: u32) -> Self {
+        self.retry_policy.max_retry_delay_ms = max_retry_delay_ms;
+        self
+    }
+
+    /// Processes rounds with `handler`, until the handler or a request fails.
+    ///
+    /// Only returns with the error.
+    pub async fn run<F, Fut>(&self, mut handler: F) -> Result<(), Error>
+    where
+        F: FnMut(FollowedRound) -> Fut,
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        loop {
+            self.process_next_round(&mut handler).await?;
+        }
+    }
+
+    /// Processes the round at the cursor with `handler`, returning the round.
+    pub async fn process_next_round<F, Fut>(&self, handler: F) -> Result<u64, Error>
+    where
+        F: FnOnce(FollowedRound) -> Fut,
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        let round = self.store.load().await?.unwrap_or(self.start_round);
+
+        self.retry_policy.retry(|| self.algod.sync(round)).await?;
+
+        let mut last_round = self
+            .retry_policy
+            .retry(|| self.algod.status())
+            .await?
+            .last_round;
+        while last_round < round {
+            // returns when the next block is available or after a server side timeout
+            last_round = self
+                .retry_policy
+                .retry(|| self.algod.status_after_block(last_round))
+                .await?
+                .last_round;
+        }
+
+        let block = self.retry_policy.retry(|| self.algod.block(round)).await?;
+        let delta = self
+            .retry_policy
+            .retry(|| self.algod.state_delta(round))
+            .await?;
+
+        handler(FollowedRound {
+            round,
+            block,
+            delta,
+        })
+        .await?;
+
+        self.store.save(round + 1).await?;
+        Ok(round)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{
+        block_follower::InMemoryCursorStore,
+        test_fixtures::status,
+        test_server::{TestResponse, TestServer},
+    };
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn delta_requests(node: &TestServer) -> usize {
+        node.paths()
+            .iter()
+            .filter(|p| p.starts_with("/v2/deltas/"))
+            .count()
+    }
+
+    /// A follower node at round 5. The first `failing_delta_requests` state delta requests fail
+    /// with `failure_status`.
+    fn follower_node(failing_delta_requests: u32, failure_status: u16) -> TestServer {
+        let failures = AtomicU32::new(failing_delta_requests);
+        TestServer::start(move |request| {
+            let path = request.path.as_str();
+            if path.starts_with("/v2/ledger/sync/") || path.starts_with("/v2/status") {
+                TestResponse::json(&status(5))
+            } else if let Some(round) = path.strip_prefix("/v2/blocks/") {
+                let round = round.split('?').next().unwrap();
+                TestResponse::json(&format!(r#"{{"block":{{"rnd":{round}}}}}"#))
+            } else if path.starts_with("/v2/deltas/") {
+                if failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok()
+                {
+                    return TestResponse::error(failure_status, "failed");
+                }
+                TestResponse::bytes(
+                    rmp_serde::to_vec_named(&serde_json::json!({"Totals": {"rwdlvl": 7}})).unwrap(),
+                )
+            } else {
+                TestResponse::error(404, "not found")
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_processes_rounds_in_order() {
+        let node = follower_node(0, 0);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let conductor =
+            FollowerConductor::new(Algod::new(&node.url, "").unwrap(), store.clone(), 3);
+
+        let mut processed = vec![];
+        for _ in 0..2 {
+            conductor
+                .process_next_round(|followed| {
+                    processed.push((followed.round, followed.block.block.round));
+                    assert_eq!(7, followed.delta.totals.rewards_level);
+                    async { Ok(()) }
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(vec![(3, Some(3)), (4, Some(4))], processed);
+        assert_eq!(Some(5), store.load().await.unwrap());
+        let syncs = node
+            .paths()
+            .into_iter()
+            .filter(|p| p.starts_with("/v2/ledger/sync/"))
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["/v2/ledger/sync/3", "/v2/ledger/sync/4"], syncs);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_advance_when_handler_fails() {
+        let node = follower_node(0, 0);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let algod = Algod::new(&node.url, "").unwrap();
+
+        let res = FollowerConductor::new(algod.clone(), store.clone(), 3)
+            .process_next_round(|_| async { Err(Error::Msg("handler failed".to_owned())) })
+            .await;
+
+        assert!(res.is_err());
+        assert_eq!(None, store.load().await.unwrap());
+
+        // resumes at the failed round, e.g. after a restart
+        let round = FollowerConductor::new(algod, store.clone(), 3)
+            .process_next_round(|_| async { Ok(()) })
+            .await
+            .unwrap();
+
+        assert_eq!(3, round);
+        assert_eq!(Some(4), store.load().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors() {
+        let node = follower_node(2, 503);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let conductor = FollowerConductor::new(Algod::new(&node.url, "").unwrap(), store, 3)
+            .max_retries(2)
+            .max_retry_delay_ms(1);
+
+        let round = conductor
+            .process_next_round(|_| async { Ok(()) })
+            .await
+            .unwrap();
+
+        assert_eq!(3, round);
+        assert_eq!(3, delta_requests(&node));
+    }
+
+    #[tokio::test]
+    async fn test_returns_non_transient_error_without_retrying() {
+        let node = follower_node(1, 400);
+        let store = Arc::new(InMemoryCursorStore::default());
+        let conductor = FollowerConductor::new(Algod::new(&node.url, "").unwrap(), store, 3)
+            .max_retry_delay_ms(1);
+
+        let err = conductor
+            .process_next_round(|_| async { Ok(()) })
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, Error::Algod(_)),
+            "not the original error: {:?}",
+            err
+        );
+        assert_eq!(1, delta_requests(&node));
+    }
+}
@@ -0,0 +1,142 @@
+use crate::Error;
+use algonaut_algod::{
+    ext::block_header::BlockHeader,
+    models::{LightBlockHeaderProof, StateProofMessage},
+};
+use algonaut_crypto::light_block_header::{self, LightBlockHeader};
+
+/// Verifies a proof returned by
+/// [Algod::light_block_header_proof](crate::algod::v2::Algod::light_block_header_proof):
+/// checks that `header` is attested to by `trusted_message`, the message of a state proof,
+/// e.g. returned by [Algod::state_proof](crate::algod::v2::Algod::state_proof).
+///
+/// The state proof attesting to the message isn't verified (see [algonaut_crypto::light_block_header]),
+/// so the message has to come from a trusted source, e.g. a node operated by the caller.
+/// A message returned by the same node as the proof doesn't make the proof trustless.
+///
+/// Messages attest to the round, seed, genesis hash and SHA256 transaction commitment of a header
+/// and, once the consensus protocol enables it, to the block hash (and so to the whole header).
+/// Both variants are accepted.
+pub fn verify_light_block_header(
+    trusted_message: &StateProofMessage,
+    header: &BlockHeader,
+    proof: &LightBlockHeaderProof,
+) -> Result<(), Error> {
+    let message = light_block_header::StateProofMessage {
+        ln_proven_weight: trusted_message.ln_proven_weight,
+        block_headers_commitment: trusted_message.block_headers_commitment.0.clone(),
+        first_attested_round: trusted_message.first_attested_round,
+        last_attested_round: trusted_message.last_attested_round,
+        voters_commitment: trusted_message.voters_commitment.0.clone(),
+    };
+    let mut light_header = LightBlockHeader {
+        seed: header.seed,
+        block_hash: Default::default(),
+        genesis_hash: header.genesis_hash,
+        round: header.round,
+        txn_commitment_sha256: header.txn_commitment_sha256,
+    };
+    let verify = |light_header: &LightBlockHeader| {
+        light_block_header::verify_light_block_header(
+            &message,
+            light_header,
+            proof.index,
+            proof.treedepth,
+            &proof.proof.0,
+        )
+    };
+
+    if let Err(e) = verify(&light_header) {
+        light_header.block_hash = header.hash()?;
+        verify(&light_header)
+            .map_err(|_| Error::Msg(format!("Invalid light block header proof: {e}")))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algonaut_crypto::{merkle, HashDigest, HashType};
+    use algonaut_encoding::Bytes;
+
+    fn header(round: u64) -> BlockHeader {
+        BlockHeader {
+            round,
+            seed: HashDigest([round as u8; 32]),
+            genesis_hash: HashDigest([1; 32]),
+            txn_commitment_sha256: HashDigest([2; 32]),
+            timestamp: 1736510450,
+            ..BlockHeader::default()
+        }
+    }
+
+    /// A message attesting to the 2 headers, and the proofs of the headers.
+    fn attest(
+        headers: &[BlockHeader; 2],
+        with_block_hash: bool,
+    ) -> (StateProofMessage, Vec<LightBlockHeaderProof>) {
+        let leaves: Vec<HashDigest> = headers
+            .iter()
+            .map(|h| {
+                LightBlockHeader {
+                    seed: h.seed,
+                    block_hash: if with_block_hash {
+                        h.hash().unwrap()
+                    } else {
+                        HashDigest::default()
+                    },
+                    genesis_hash: h.genesis_hash,
+                    round: h.round,
+                    txn_commitment_sha256: h.txn_commitment_sha256,
+                }
+                .leaf()
+                .unwrap()
+            })
+            .collect();
+        let root = merkle::hash(
+            HashType::Sha256,
+            &[b"MA".as_slice(), &leaves[0].0, &leaves[1].0].concat(),
+        )
+        .unwrap();
+        let message = StateProofMessage {
+            block_headers_commitment: Bytes(root.0.to_vec()),
+            first_attested_round: headers[0].round,
+            last_attested_round: headers[1].round,
+            ..StateProofMessage::default()
+        };
+        let proofs = (0..2)
+            .map(|i| LightBlockHeaderProof {
+                index: i,
+                proof: Bytes(leaves[1 - i as usize].0.to_vec()),
+                treedepth: 1,
+            })
+            .collect();
+        (message, proofs)
+    }
+
+    #[test]
+    fn test_verifies_light_block_headers() {
+        let headers = [header(257), header(258)];
+
+        for with_block_hash in [false, true] {
+            let (message, proofs) = attest(&headers, with_block_hash);
+
+            verify_light_block_header(&message, &headers[0], &proofs[0]).unwrap();
+            verify_light_block_header(&message, &headers[1], &proofs[1]).unwrap();
+            assert!(verify_light_block_header(&message, &headers[1], &proofs[0]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_block_hash_commits_to_whole_header() {
+        let headers = [header(257), header(258)];
+        let (message, proofs) = attest(&headers, true);
+        let mut tampered = headers[0].clone();
+        tampered.timestamp += 1;
+
+        let err = verify_light_block_header(&message, &tampered, &proofs[0]).unwrap_err();
+
+        assert!(err.to_string().contains("Invalid light block header proof"));
+    }
+}
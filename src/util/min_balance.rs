@@ -0,0 +1,180 @@
+use algonaut_algod::models::{Account, ApplicationStateSchema};
+use algonaut_core::{consensus::ConsensusParams, MicroAlgos};
+
+/// The resources of an account that raise its minimum balance requirement (MBR).
+///
+/// Can be read from an algod [Account] and modified with the "what-if" methods,
+/// e.g. to calculate how much an account has to be funded before opting into assets or creating boxes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountResources {
+    /// Number of assets the account holds, including the ones it created.
+    pub assets: u64,
+    /// Number of apps the account created.
+    pub created_apps: u64,
+    /// Number of apps the account opted into.
+    pub opted_in_apps: u64,
+    /// Total extra program pages of the apps the account created.
+    pub extra_app_pages: u64,
+    /// Total state schema of the account: the global schemas of the apps it created
+    /// and the local schemas of the apps it opted into.
+    pub schema: ApplicationStateSchema,
+    /// Number of boxes of the account. Only app accounts have boxes.
+    pub boxes: u64,
+    /// Total length of the names and contents of the account's boxes, in bytes.
+    pub box_bytes: u64,
+}
+
+/// The minimum balance requirement of an account, broken down by resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinBalance {
+    /// Min. balance of every account.
+    pub base: MicroAlgos,
+    pub assets: MicroAlgos,
+    pub created_apps: MicroAlgos,
+    pub opted_in_apps: MicroAlgos,
+    pub extra_app_pages: MicroAlgos,
+    pub schema: MicroAlgos,
+    pub boxes: MicroAlgos,
+}
+
+impl AccountResources {
+    /// Opts into `count` assets.
+    pub fn opt_in_assets(mut self, count: u64) -> Self {
+        self.assets += count;
+        self
+    }
+
+    /// Opts into an app with the given local state schema.
+    pub fn opt_in_app(mut self, local_schema: &ApplicationStateSchema) -> Self {
+        self.opted_in_apps += 1;
+        self.add_schema(local_schema);
+        self
+    }
+
+    /// Creates an app with the given global state schema and extra program pages.
+    pub fn create_app(mut self, global_schema: &ApplicationStateSchema, extra_pages: u64) -> Self {
+        self.created_apps += 1;
+        self.extra_app_pages += extra_pages;
+        self.add_schema(global_schema);
+        self
+    }
+
+    /// Creates a box with a name of `name_len` bytes and a content of `size` bytes.
+    pub fn create_box(mut self, name_len: u64, size: u64) -> Self {
+        self.boxes += 1;
+        self.box_bytes += name_len + size;
+        self
+    }
+
+    /// Calculates the minimum balance requirement of these resources.
+    pub fn min_balance(&self, params: &ConsensusParams) -> MinBalance {
+        MinBalance {
+            base: params.min_balance,
+            assets: params.min_balance * self.assets,
+            created_apps: params.app_flat_params_min_balance * self.created_apps,
+            opted_in_apps: params.app_flat_opt_in_min_balance * self.opted_in_apps,
+            extra_app_pages: params.app_flat_params_min_balance * self.extra_app_pages,
+            schema: (params.schema_min_balance_per_entry + params.schema_uint_min_balance)
+                * self.schema.num_uint
+                + (params.schema_min_balance_per_entry + params.schema_bytes_min_balance)
+                    * self.schema.num_byte_slice,
+            boxes: params.box_flat_min_balance * self.boxes
+                + params.box_byte_min_balance * self.box_bytes,
+        }
+    }
+
+    fn add_schema(&mut self, schema: &ApplicationStateSchema) {
+        self.schema.num_uint += schema.num_uint;
+        self.schema.num_byte_slice += schema.num_byte_slice;
+    }
+}
+
+impl From<&Account> for AccountResources {
+    fn from(account: &Account) -> Self {
+        AccountResources {
+            assets: account.total_assets_opted_in,
+            created_apps: account.total_created_apps,
+            opted_in_apps: account.total_apps_opted_in,
+            extra_app_pages: account.apps_total_extra_pages.unwrap_or_default(),
+            schema: account
+                .apps_total_schema
+                .as_deref()
+                .cloned()
+                .unwrap_or_default(),
+            boxes: account.total_boxes.unwrap_or_default(),
+            box_bytes: account.total_box_bytes.unwrap_or_default(),
+        }
+    }
+}
+
+impl MinBalance {
+    /// The total minimum balance requirement.
+    pub fn total(&self) -> MicroAlgos {
+        self.base
+            + self.assets
+            + self.created_apps
+            + self.opted_in_apps
+            + self.extra_app_pages
+            + self.schema
+            + self.boxes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(num_uint: u64, num_byte_slice: u64) -> ApplicationStateSchema {
+        ApplicationStateSchema {
+            num_uint,
+            num_byte_slice,
+        }
+    }
+
+    #[test]
+    fn test_empty_account() {
+        let min_balance = AccountResources::default().min_balance(&ConsensusParams::default());
+
+        assert_eq!(MicroAlgos(100_000), min_balance.total());
+    }
+
+    #[test]
+    fn test_account_resources() {
+        let account = Account {
+            total_assets_opted_in: 2,
+            total_created_apps: 1,
+            total_apps_opted_in: 1,
+            apps_total_extra_pages: Some(1),
+            apps_total_schema: Some(Box::new(schema(2, 1))),
+            ..Default::default()
+        };
+
+        let min_balance = AccountResources::from(&account).min_balance(&ConsensusParams::default());
+
+        assert_eq!(MicroAlgos(200_000), min_balance.assets);
+        assert_eq!(MicroAlgos(100_000), min_balance.created_apps);
+        assert_eq!(MicroAlgos(100_000), min_balance.opted_in_apps);
+        assert_eq!(MicroAlgos(100_000), min_balance.extra_app_pages);
+        assert_eq!(MicroAlgos(2 * 28_500 + 50_000), min_balance.schema);
+        assert_eq!(MicroAlgos(707_000), min_balance.total());
+    }
+
+    #[test]
+    fn test_what_if() {
+        let params = ConsensusParams::default();
+        let resources = AccountResources::default();
+
+        let with_assets = resources.clone().opt_in_assets(3).min_balance(&params);
+        let with_box = resources.clone().create_box(4, 1024).min_balance(&params);
+        let with_app = resources
+            .clone()
+            .create_app(&schema(1, 1), 0)
+            .opt_in_app(&schema(0, 2))
+            .min_balance(&params);
+
+        assert_eq!(MicroAlgos(400_000), with_assets.total());
+        assert_eq!(MicroAlgos(2_500 + 400 * 1028), with_box.boxes);
+        assert_eq!(MicroAlgos(28_500 + 3 * 50_000), with_app.schema);
+        assert_eq!(MicroAlgos(478_500), with_app.total());
+    }
+}
@@ -1,6 +1,25 @@
+pub mod app_call_resources;
+pub mod block_follower;
+pub mod chain_verifier;
 pub mod dryrun_printer;
+pub mod fee_planner;
+pub mod follower_conductor;
+pub mod light_block_header;
+pub mod min_balance;
+#[cfg(test)]
+pub(crate) mod test_fixtures;
+#[cfg(test)]
+pub(crate) mod test_server;
+pub mod transaction_subscriber;
+pub mod txn_proof;
 pub mod wait_for_pending_tx;
 
+use crate::{
+    algod::v2::simulate::SimulateBuilder, atomic_transaction_composer::error::ComposerError, Error,
+};
+use algonaut_algod::models::{SimulateTransaction200Response, SimulateTransactionGroupResult};
+use algonaut_transaction::{tx_group::TxGroup, Transaction};
+
 #[cfg(target_arch = "wasm32")]
 pub async fn sleep(ms: u32) {
     gloo_timers::future::TimeoutFuture::new(ms).await;
@@ -10,3 +29,62 @@ pub async fn sleep(ms: u32) {
 pub async fn sleep(ms: u32) {
     futures_timer::Delay::new(std::time::Duration::from_millis(ms as u64)).await;
 }
+
+/// Clears the group id of `txs` and, if `group` is set, assigns a new one.
+pub(crate) fn assign_group_id(txs: &mut [Transaction], group: bool) -> Result<(), Error> {
+    for tx in txs.iter_mut() {
+        tx.group = None;
+    }
+    if group {
+        TxGroup::assign_group_id(&mut txs.iter_mut().collect::<Vec<_>>())?;
+    }
+    Ok(())
+}
+
+/// Simulates `txs` without signatures with `simulate`, returning the result of the group.
+///
+/// A group has to have a group id to be simulated, so it's assigned if there are multiple transactions.
+/// Returns an error if the simulation failed.
+pub(crate) async fn simulate_unsigned_group(
+    simulate: SimulateBuilder<'_>,
+    txs: &[Transaction],
+) -> Result<SimulateTransactionGroupResult, Error> {
+    let mut txs = txs.to_vec();
+    let grouped = txs.len() > 1;
+    assign_group_id(&mut txs, grouped)?;
+
+    let response = simulate.unsigned_group(&txs)?.send().await?;
+    let group_result = single_group_result(&response, txs.len())?;
+    if let Some(failure_message) = &group_result.failure_message {
+        return Err(ComposerError::SimulationFailed(failure_message.clone()).into());
+    }
+    Ok(group_result.clone())
+}
+
+/// Returns the result of the only group of a simulate `response`,
+/// checking that it has a result for each of the `tx_count` transactions.
+pub(crate) fn single_group_result(
+    response: &SimulateTransaction200Response,
+    tx_count: usize,
+) -> Result<&SimulateTransactionGroupResult, ComposerError> {
+    let group_result = response
+        .txn_groups
+        .first()
+        .ok_or(ComposerError::MissingSimulateGroupResult)?;
+    check_txn_result_count(group_result, tx_count)?;
+    Ok(group_result)
+}
+
+/// Checks that a simulated group has a result for each of the `tx_count` transactions.
+pub(crate) fn check_txn_result_count(
+    group_result: &SimulateTransactionGroupResult,
+    tx_count: usize,
+) -> Result<(), ComposerError> {
+    if group_result.txn_results.len() != tx_count {
+        return Err(ComposerError::SimulateResultCount {
+            returned: group_result.txn_results.len(),
+            expected: tx_count,
+        });
+    }
+    Ok(())
+}
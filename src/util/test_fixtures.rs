@@ -0,0 +1,57 @@
+//! Transactions and parameters shared by unit tests.
+
+use algonaut_algod::models::TransactionParams200Response;
+use algonaut_core::{Address, MicroAlgos};
+use algonaut_crypto::HashDigest;
+use algonaut_transaction::{builder::CallApplication, Pay, Transaction, TxnBuilder};
+
+/// Suggested params with a zero genesis hash and the minimum fee of 1000.
+pub(crate) fn txn_params() -> TransactionParams200Response {
+    TransactionParams200Response {
+        consensus_version: "".to_owned(),
+        fee: 0,
+        genesis_hash: HashDigest([0; 32]),
+        genesis_id: "".to_owned(),
+        last_round: 0,
+        min_fee: 1000,
+    }
+}
+
+/// A payment of `amount` from `sender` to itself, with a fee of 1000.
+pub(crate) fn payment(sender: &Address, amount: u64) -> Transaction {
+    TxnBuilder::with_fee(
+        &txn_params(),
+        MicroAlgos(1000),
+        Pay::new(*sender, *sender, MicroAlgos(amount)).build(),
+    )
+    .build()
+    .unwrap()
+}
+
+/// A call of the app `app_id`, with a fee of 1000.
+pub(crate) fn app_call(sender: &Address, app_id: u64) -> Transaction {
+    TxnBuilder::with_fee(
+        &txn_params(),
+        MicroAlgos(1000),
+        CallApplication::new(*sender, app_id).build(),
+    )
+    .build()
+    .unwrap()
+}
+
+/// An algod `/v2/status` response body for a node at `round`.
+pub(crate) fn status(round: u64) -> String {
+    format!(
+        r#"{{
+            "catchup-time": 0,
+            "last-round": {round},
+            "last-version": "future",
+            "next-version": "future",
+            "next-version-round": {},
+            "next-version-supported": true,
+            "stopped-at-unsupported-round": false,
+            "time-since-last-round": 0
+        }}"#,
+        round + 1
+    )
+}
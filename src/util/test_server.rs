@@ -0,0 +1,164 @@
+//! A minimal HTTP server, to test clients against canned responses.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// A request received by a [TestServer].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestRequest {
+    pub method: String,
+    /// Path, including the query.
+    pub path: String,
+    /// Headers, with lowercase names.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl TestRequest {
+    /// The value of the header with the lowercase `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response returned by a [TestServer].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestResponse {
+    pub status: u16,
+    pub body: Vec<u8>,
+    /// Delay between sending the head and the body, to simulate a stalled response.
+    pub body_delay: Option<Duration>,
+}
+
+impl TestResponse {
+    pub fn json(body: &str) -> TestResponse {
+        TestResponse::bytes(body.as_bytes().to_vec())
+    }
+
+    pub fn bytes(body: Vec<u8>) -> TestResponse {
+        TestResponse {
+            status: 200,
+            body,
+            body_delay: None,
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> TestResponse {
+        TestResponse {
+            status,
+            body: format!(r#"{{"message":"{message}"}}"#).into_bytes(),
+            body_delay: None,
+        }
+    }
+
+    /// Sends the head right away and the body after `delay`.
+    pub fn delay_body(mut self, delay: Duration) -> TestResponse {
+        self.body_delay = Some(delay);
+        self
+    }
+}
+
+/// Serves each request with the response returned by its handler, on a background thread,
+/// and records the requests.
+pub(crate) struct TestServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<TestRequest>>>,
+}
+
+impl TestServer {
+    pub fn start<F>(handler: F) -> TestServer
+    where
+        F: Fn(&TestRequest) -> TestResponse + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let recorded = requests.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => return,
+                };
+                let request = match read_request(&mut BufReader::new(&stream)) {
+                    Some(request) => request,
+                    None => continue,
+                };
+                let response = handler(&request);
+                recorded.lock().unwrap().push(request);
+
+                let head = format!(
+                    "HTTP/1.1 {} Test\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                let _ = stream
+                    .write_all(head.as_bytes())
+                    .and_then(|_| stream.flush())
+                    .and_then(|_| {
+                        if let Some(delay) = response.body_delay {
+                            thread::sleep(delay);
+                        }
+                        stream.write_all(&response.body)
+                    });
+            }
+        });
+
+        TestServer { url, requests }
+    }
+
+    /// The requests received so far.
+    pub fn requests(&self) -> Vec<TestRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The paths of the requests received so far.
+    pub fn paths(&self) -> Vec<String> {
+        self.requests().into_iter().map(|r| r.path).collect()
+    }
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> Option<TestRequest> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_owned();
+    let path = parts.next()?.to_owned();
+
+    let mut headers = vec![];
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let (name, value) = (name.to_ascii_lowercase(), value.trim().to_owned());
+            if name == "content-length" {
+                content_length = value.parse().ok()?;
+            }
+            headers.push((name, value));
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(TestRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
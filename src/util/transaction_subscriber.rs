@@ -0,0 +1,924 @@
+use super::block_follower::{BlockFollower, CursorStore};
+use crate::{algod::v2::Algod, indexer::v2::Indexer, Error};
+use algonaut_algod::ext::msgpack::MsgpackBlock;
+use algonaut_core::{Address, TransactionTypeEnum};
+use algonaut_encoding::decode_base64;
+use algonaut_indexer::models::{transaction::TxType, Transaction as IndexerTransaction};
+use algonaut_transaction::{
+    apply_data::{ApplyData, InnerTransaction, SignedTransactionWithApplyData},
+    transaction::{to_tx_type_enum, TransactionType},
+    Transaction,
+};
+use futures::{future, Stream, StreamExt};
+use sha2::{Digest, Sha512_256};
+use std::sync::Arc;
+
+/// Selects transactions by their fields.
+///
+/// A transaction matches if it matches all the conditions that were set,
+/// so an empty filter matches every transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    sender: Option<String>,
+    receiver: Option<String>,
+    tx_type: Option<TransactionTypeEnum>,
+    app_id: Option<u64>,
+    asset_id: Option<u64>,
+    note_prefix: Option<Vec<u8>>,
+    arc28_event_selector: Option<[u8; 4]>,
+    method_selector: Option<[u8; 4]>,
+    min_amount: Option<u64>,
+}
+
+/// A transaction matched by a [TransactionSubscriber], with its fields relevant to filtering.
+#[derive(Debug, Clone)]
+pub struct SubscribedTransaction {
+    pub round: u64,
+    /// The index of the transaction in its block. For inner transactions, the index of the top level transaction.
+    pub intra_round_offset: u64,
+    /// The indexes of an inner transaction in the inner transactions of each level, starting at the top level transaction.
+    /// Empty for top level transactions.
+    pub inner_path: Vec<usize>,
+    pub tx_type: TransactionTypeEnum,
+    pub sender: String,
+    /// The receiver of payments and asset transfers.
+    pub receiver: Option<String>,
+    /// The amount of payments and asset transfers.
+    pub amount: Option<u64>,
+    pub asset_id: Option<u64>,
+    pub app_id: Option<u64>,
+    pub note: Vec<u8>,
+    pub app_args: Vec<Vec<u8>>,
+    pub logs: Vec<Vec<u8>>,
+    pub source: TransactionSource,
+}
+
+/// Where a [SubscribedTransaction] was read from.
+#[derive(Debug, Clone)]
+pub enum TransactionSource {
+    /// A block from algod.
+    Algod(Box<SignedTransactionWithApplyData>),
+    /// An inner transaction of a block from algod.
+    AlgodInner(Box<InnerTransaction>),
+    /// Indexer, because algod didn't have the block anymore.
+    Indexer(Box<IndexerTransaction>),
+}
+
+/// The transactions of a round matched by a [TransactionSubscriber].
+#[derive(Debug, Clone)]
+pub struct SubscribedRound {
+    pub round: u64,
+    pub transactions: Vec<SubscribedTransaction>,
+}
+
+/// Follows the chain and returns the transactions, including inner transactions, matching any of its filters.
+///
+/// Rounds behind the node's last round are fetched right away, to catch up,
+/// afterwards the subscriber waits for each new block with [Algod::status_after_block], see [BlockFollower].
+/// Blocks are requested as msgpack, so logs and app arguments are read losslessly.
+/// If algod doesn't have a block (e.g. a non archival node that doesn't have old rounds anymore),
+/// the transactions are searched with Indexer, if set.
+/// Requests failing with a transient error are retried with an exponential backoff.
+///
+/// The cursor is saved when the next round is requested from the stream,
+/// i.e. after the previous round was processed, so no transaction is skipped when resuming.
+/// Rounds without matching transactions are checkpointed along with the next round.
+#[derive(Debug, Clone)]
+pub struct TransactionSubscriber {
+    algod: Algod,
+    indexer: Option<Indexer>,
+    store: Arc<dyn CursorStore>,
+    start_round: u64,
+    filters: Vec<TransactionFilter>,
+    max_retries: u32,
+    max_retry_delay_ms: u32,
+}
+
+impl TransactionFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sender(mut self, sender: &Address) -> Self {
+        self.sender = Some(sender.to_string());
+        self
+    }
+
+    /// Matches the receiver of payments and asset transfers.
+    pub fn receiver(mut self, receiver: &Address) -> Self {
+        self.receiver = Some(receiver.to_string());
+        self
+    }
+
+    pub fn tx_type(mut self, tx_type: TransactionTypeEnum) -> Self {
+        self.tx_type = Some(tx_type);
+        self
+    }
+
+    /// Matches calls to and the creation of the app.
+    pub fn app_id(mut self, app_id: u64) -> Self {
+        self.app_id = Some(app_id);
+        self
+    }
+
+    /// Matches asset configurations, transfers and freezes of the asset.
+    pub fn asset_id(mut self, asset_id: u64) -> Self {
+        self.asset_id = Some(asset_id);
+        self
+    }
+
+    pub fn note_prefix(mut self, note_prefix: &[u8]) -> Self {
+        self.note_prefix = Some(note_prefix.to_vec());
+        self
+    }
+
+    /// Matches app calls that emitted the ARC-28 event with the given signature, e.g. `Swapped(uint64,uint64)`.
+    pub fn arc28_event(mut self, signature: &str) -> Self {
+        let mut selector = [0; 4];
+        selector.copy_from_slice(&Sha512_256::digest(signature.as_bytes())[..4]);
+        self.arc28_event_selector = Some(selector);
+        self
+    }
+
+    /// Matches calls of the ABI method with the given selector, see [AbiMethod::get_selector](algonaut_abi::abi_interactions::AbiMethod::get_selector).
+    pub fn method_selector(mut self, selector: [u8; 4]) -> Self {
+        self.method_selector = Some(selector);
+        self
+    }
+
+    /// Matches payments and asset transfers of at least the amount.
+    pub fn min_amount(mut self, min_amount: u64) -> Self {
+        self.min_amount = Some(min_amount);
+        self
+    }
+
+    /// Returns if the transaction matches this filter.
+    pub fn matches(&self, tx: &SubscribedTransaction) -> bool {
+        self.sender.as_ref().is_none_or(|s| *s == tx.sender)
+            && self
+                .receiver
+                .as_ref()
+                .is_none_or(|r| tx.receiver.as_ref() == Some(r))
+            && self.tx_type.as_ref().is_none_or(|t| *t == tx.tx_type)
+            && self.app_id.is_none_or(|id| tx.app_id == Some(id))
+            && self.asset_id.is_none_or(|id| tx.asset_id == Some(id))
+            && self
+                .note_prefix
+                .as_ref()
+                .is_none_or(|p| tx.note.starts_with(p))
+            && self
+                .arc28_event_selector
+                .is_none_or(|s| tx.logs.iter().any(|l| l.starts_with(&s)))
+            && self
+                .method_selector
+                .is_none_or(|s| tx.app_args.first().is_some_and(|a| *a == s))
+            && self
+                .min_amount
+                .is_none_or(|min| tx.amount.is_some_and(|a| a >= min))
+    }
+}
+
+impl TransactionSubscriber {
+    /// Creates a subscriber that starts at the saved cursor or, if none was saved, at `start_round`.
+    pub fn new(
+        algod: Algod,
+        store: Arc<dyn CursorStore>,
+        start_round: u64,
+    ) -> TransactionSubscriber {
+        TransactionSubscriber {
+            algod,
+            indexer: None,
+            store,
+            start_round,
+            filters: vec![],
+            max_retries: 10,
+            max_retry_delay_ms: 30_000,
+        }
+    }
+
+    /// Sets the Indexer to search the transactions of rounds algod doesn't have.
+    pub fn indexer(mut self, indexer: Indexer) -> Self {
+        self.indexer = Some(indexer);
+        self
+    }
+
+    /// Adds a filter. Transactions matching any of the filters are returned.
+    pub fn filter(mut self, filter: TransactionFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Sets how often a request failing with a transient error is retried before the stream
+    /// returns the error and ends.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the max. delay between retries, in milliseconds.
+    pub fn max_retry_delay_ms(mut self, max_retry_delay_ms: u32) -> Self {
+        self.max_retry_delay_ms = max_retry_delay_ms;
+        self
+    }
+
+    /// Returns a stream of the rounds with matching transactions.
+    ///
+    /// The stream only ends after returning an error.
+    pub fn stream(self) -> impl Stream<Item = Result<SubscribedRound, Error>> {
+        let follower = BlockFollower::new(self.algod.clone(), self.store.clone(), self.start_round)
+            .max_retries(self.max_retries)
+            .max_retry_delay_ms(self.max_retry_delay_ms);
+        let subscriber = Arc::new(self);
+        follower
+            .stream_with(move |round| {
+                let subscriber = subscriber.clone();
+                async move {
+                    let transactions = subscriber.round_transactions(round).await?;
+                    Ok(SubscribedRound {
+                        round,
+                        transactions,
+                    })
+                }
+            })
+            .filter(|res| future::ready(!matches!(res, Ok(round) if round.transactions.is_empty())))
+    }
+
+    /// Returns the transactions of the block matching any of the filters.
+    pub fn block_transactions(
+        &self,
+        block: &MsgpackBlock,
+    ) -> Result<Vec<SubscribedTransaction>, Error> {
+        let round = block.header.round;
+        let mut transactions = vec![];
+        let txns = block
+            .signed_transactions()
+            .map_err(|e| Error::Msg(e.to_string()))?;
+        for (offset, txn) in txns.into_iter().enumerate() {
+            let offset = offset as u64;
+            let tx = SubscribedTransaction::from_algod(
+                &txn.signed_transaction.transaction,
+                &txn.apply_data,
+                round,
+                offset,
+                vec![],
+                TransactionSource::Algod(Box::new(txn.clone())),
+            );
+            self.push_if_matches(tx, &mut transactions);
+            self.collect_inner(&txn.apply_data, round, offset, vec![], &mut transactions);
+        }
+        Ok(transactions)
+    }
+
+    async fn round_transactions(&self, round: u64) -> Result<Vec<SubscribedTransaction>, Error> {
+        match self.algod.block_msgpack(round).await {
+            Ok(block) => self.block_transactions(&block),
+            Err(e) if e.is_404() => match &self.indexer {
+                Some(indexer) => {
+                    log::debug!("Algod doesn't have block: {round}, searching with indexer: {e}");
+                    self.indexer_round_transactions(indexer, round).await
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn indexer_round_transactions(
+        &self,
+        indexer: &Indexer,
+        round: u64,
+    ) -> Result<Vec<SubscribedTransaction>, Error> {
+        let mut transactions = vec![];
+        let mut offset = 0;
+        let mut next = None;
+        loop {
+            let res = indexer
+                .search_for_transactions(
+                    None,
+                    next.as_deref(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(round),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+            for tx in &res.transactions {
+                let tx_offset = tx.intra_round_offset.unwrap_or(offset);
+                self.collect_indexer(tx, round, tx_offset, vec![], &mut transactions)?;
+                offset += 1;
+            }
+            match res.next_token {
+                Some(token) if !res.transactions.is_empty() => next = Some(token),
+                _ => return Ok(transactions),
+            }
+        }
+    }
+
+    fn collect_inner(
+        &self,
+        apply_data: &ApplyData,
+        round: u64,
+        offset: u64,
+        inner_path: Vec<usize>,
+        transactions: &mut Vec<SubscribedTransaction>,
+    ) {
+        for (i, inner) in apply_data.eval_delta.inner_txns.iter().enumerate() {
+            let mut path = inner_path.clone();
+            path.push(i);
+            let tx = SubscribedTransaction::from_algod(
+                &inner.transaction,
+                &inner.apply_data,
+                round,
+                offset,
+                path.clone(),
+                TransactionSource::AlgodInner(Box::new(inner.clone())),
+            );
+            self.push_if_matches(tx, transactions);
+            self.collect_inner(&inner.apply_data, round, offset, path, transactions);
+        }
+    }
+
+    fn collect_indexer(
+        &self,
+        indexer_tx: &IndexerTransaction,
+        round: u64,
+        offset: u64,
+        inner_path: Vec<usize>,
+        transactions: &mut Vec<SubscribedTransaction>,
+    ) -> Result<(), Error> {
+        let tx =
+            SubscribedTransaction::from_indexer(indexer_tx, round, offset, inner_path.clone())?;
+        self.push_if_matches(tx, transactions);
+        for (i, inner_tx) in indexer_tx.inner_txns.iter().flatten().enumerate() {
+            let mut path = inner_path.clone();
+            path.push(i);
+            self.collect_indexer(inner_tx, round, offset, path, transactions)?;
+        }
+        Ok(())
+    }
+
+    fn push_if_matches(
+        &self,
+        tx: SubscribedTransaction,
+        transactions: &mut Vec<SubscribedTransaction>,
+    ) {
+        if self.filters.iter().any(|f| f.matches(&tx)) {
+            transactions.push(tx);
+        }
+    }
+}
+
+impl SubscribedTransaction {
+    fn from_algod(
+        transaction: &Transaction,
+        apply_data: &ApplyData,
+        round: u64,
+        intra_round_offset: u64,
+        inner_path: Vec<usize>,
+        source: TransactionSource,
+    ) -> SubscribedTransaction {
+        let (receiver, amount, asset_id, app_id, app_args) = match &transaction.txn_type {
+            TransactionType::Payment(pay) => {
+                (Some(pay.receiver), Some(pay.amount.0), None, None, vec![])
+            }
+            TransactionType::AssetConfigurationTransaction(acfg) => (
+                None,
+                None,
+                acfg.config_asset
+                    .filter(|id| *id != 0)
+                    .or(apply_data.config_asset),
+                None,
+                vec![],
+            ),
+            TransactionType::AssetTransferTransaction(axfer) => (
+                Some(axfer.receiver),
+                Some(axfer.amount),
+                Some(axfer.xfer),
+                None,
+                vec![],
+            ),
+            TransactionType::AssetAcceptTransaction(accept) => (
+                Some(accept.sender),
+                Some(0),
+                Some(accept.xfer),
+                None,
+                vec![],
+            ),
+            TransactionType::AssetClawbackTransaction(clawback) => (
+                Some(clawback.asset_receiver),
+                Some(clawback.asset_amount),
+                Some(clawback.xfer),
+                None,
+                vec![],
+            ),
+            TransactionType::AssetFreezeTransaction(afrz) => {
+                (None, None, Some(afrz.asset_id), None, vec![])
+            }
+            TransactionType::ApplicationCallTransaction(appl) => (
+                None,
+                None,
+                None,
+                appl.app_id
+                    .filter(|id| *id != 0)
+                    .or(apply_data.application_id),
+                appl.app_arguments.clone().unwrap_or_default(),
+            ),
+            TransactionType::KeyRegistration(_)
+            | TransactionType::StateProofTransaction(_)
+            | TransactionType::Heartbeat(_) => (None, None, None, None, vec![]),
+        };
+
+        SubscribedTransaction {
+            round,
+            intra_round_offset,
+            inner_path,
+            tx_type: to_tx_type_enum(&transaction.txn_type),
+            sender: transaction.sender().to_string(),
+            receiver: receiver.map(|receiver| receiver.to_string()),
+            amount,
+            asset_id,
+            app_id,
+            note: transaction.note.clone().unwrap_or_default(),
+            app_args,
+            logs: apply_data.eval_delta.logs.clone(),
+            source,
+        }
+    }
+
+    fn from_indexer(
+        tx: &IndexerTransaction,
+        round: u64,
+        intra_round_offset: u64,
+        inner_path: Vec<usize>,
+    ) -> Result<SubscribedTransaction, Error> {
+        let tx_type = match tx.tx_type {
+            TxType::Pay => TransactionTypeEnum::Payment,
+            TxType::Keyreg => TransactionTypeEnum::KeyRegistration,
+            TxType::Acfg => TransactionTypeEnum::AssetConfiguration,
+            TxType::Axfer => TransactionTypeEnum::AssetTransfer,
+            TxType::Afrz => TransactionTypeEnum::AssetFreeze,
+            TxType::Appl => TransactionTypeEnum::ApplicationCall,
+            TxType::Stpf => TransactionTypeEnum::StateProof,
+        };
+
+        let (receiver, amount) = if let Some(pay) = &tx.payment_transaction {
+            (Some(pay.receiver.clone()), Some(pay.amount))
+        } else if let Some(axfer) = &tx.asset_transfer_transaction {
+            (Some(axfer.receiver.clone()), Some(axfer.amount))
+        } else {
+            (None, None)
+        };
+
+        let asset_id = if let Some(acfg) = &tx.asset_config_transaction {
+            acfg.asset_id
+                .filter(|id| *id != 0)
+                .or(tx.created_asset_index)
+        } else if let Some(axfer) = &tx.asset_transfer_transaction {
+            Some(axfer.asset_id)
+        } else {
+            tx.asset_freeze_transaction
+                .as_ref()
+                .map(|afrz| afrz.asset_id)
+        };
+
+        let (app_id, app_args) = match &tx.application_transaction {
+            Some(appl) => (
+                Some(appl.application_id)
+                    .filter(|id| *id != 0)
+                    .or(tx.created_application_index),
+                appl.application_args
+                    .iter()
+                    .flatten()
+                    .map(|arg| decode_bytes(arg))
+                    .collect::<Result<_, _>>()?,
+            ),
+            None => (None, vec![]),
+        };
+
+        Ok(SubscribedTransaction {
+            round: tx.confirmed_round.unwrap_or(round),
+            intra_round_offset,
+            inner_path,
+            tx_type,
+            sender: tx.sender.clone(),
+            receiver,
+            amount,
+            asset_id,
+            app_id,
+            note: tx.note.clone().map(|note| note.0).unwrap_or_default(),
+            app_args,
+            logs: tx.logs.iter().flatten().map(|log| log.0.clone()).collect(),
+            source: TransactionSource::Indexer(Box::new(tx.clone())),
+        })
+    }
+}
+
+fn decode_bytes(base64: &str) -> Result<Vec<u8>, Error> {
+    decode_base64(base64.as_bytes())
+        .map_err(|e| Error::Msg(format!("Couldn't decode base64: `{base64}`: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{
+        block_follower::InMemoryCursorStore,
+        test_fixtures::{status, txn_params},
+        test_server::{TestResponse, TestServer},
+    };
+    use algonaut_algod::ext::block_header::BlockHeader;
+    use algonaut_core::{MicroAlgos, ToMsgPack};
+    use algonaut_crypto::Signature;
+    use algonaut_model::transaction::{StateProof, StateProofMessage, StateProofType};
+    use algonaut_transaction::{
+        builder::CallApplication,
+        transaction::{StateProofTransaction, TransactionSignature, TransactionType},
+        Pay, SignedTransaction, TransferAsset, TxnBuilder,
+    };
+
+    const SENDER: &str = "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA";
+    const RECEIVER: &str = "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA";
+    /// The selector of `Swapped(uint64,uint64)`, which isn't valid UTF-8.
+    const SWAPPED: [u8; 4] = [0x1c, 0xcb, 0xd9, 0x25];
+
+    /// Encodes a string of arbitrary bytes, like go-algorand encodes logs.
+    fn raw_str(bytes: &[u8]) -> Vec<u8> {
+        [vec![0xa0 | bytes.len() as u8], bytes.to_vec()].concat()
+    }
+
+    fn array(items: &[Vec<u8>]) -> Vec<u8> {
+        [vec![0x90 | items.len() as u8], items.concat()].concat()
+    }
+
+    /// Adds an eval delta with the logs and inner transactions to the msgpack map `entry`.
+    fn with_delta(mut entry: Vec<u8>, logs: &[&[u8]], inner_txns: &[Vec<u8>]) -> Vec<u8> {
+        entry[0] += 1;
+        entry.extend(raw_str(b"dt"));
+        entry.push(0x82);
+        entry.extend(raw_str(b"itx"));
+        entry.extend(array(inner_txns));
+        entry.extend(raw_str(b"lg"));
+        entry.extend(array(
+            &logs.iter().map(|log| raw_str(log)).collect::<Vec<_>>(),
+        ));
+        entry
+    }
+
+    /// A block transaction, with an unchecked signature.
+    fn entry(transaction: Transaction) -> Vec<u8> {
+        SignedTransaction {
+            transaction,
+            transaction_id: String::new(),
+            sig: TransactionSignature::Single(Signature([0; 64])),
+            auth_address: None,
+        }
+        .to_msg_pack()
+        .unwrap()
+    }
+
+    fn inner_entry(transaction: Transaction) -> Vec<u8> {
+        [
+            vec![0x81],
+            raw_str(b"txn"),
+            transaction.to_msg_pack().unwrap(),
+        ]
+        .concat()
+    }
+
+    fn payment() -> Transaction {
+        TxnBuilder::with_fee(
+            &txn_params(),
+            MicroAlgos(1000),
+            Pay::new(
+                SENDER.parse().unwrap(),
+                RECEIVER.parse().unwrap(),
+                MicroAlgos(5000),
+            )
+            .build(),
+        )
+        .note(b"payment".to_vec())
+        .build()
+        .unwrap()
+    }
+
+    /// The transactions of a block with a payment and an app call issuing an asset transfer and emitting
+    /// a `Swapped` event.
+    fn txns() -> Vec<Vec<u8>> {
+        let asset_transfer = TxnBuilder::with_fee(
+            &txn_params(),
+            MicroAlgos(0),
+            TransferAsset::new(SENDER.parse().unwrap(), 7, 10, RECEIVER.parse().unwrap()).build(),
+        )
+        .build()
+        .unwrap();
+        let app_call = TxnBuilder::with_fee(
+            &txn_params(),
+            MicroAlgos(1000),
+            CallApplication::new(RECEIVER.parse().unwrap(), 42)
+                .app_arguments(vec![vec![1, 2, 3, 4]])
+                .build(),
+        )
+        .build()
+        .unwrap();
+        let log = [&SWAPPED[..], &[0; 16]].concat();
+        vec![
+            entry(payment()),
+            with_delta(entry(app_call), &[&log], &[inner_entry(asset_transfer)]),
+        ]
+    }
+
+    fn block() -> MsgpackBlock {
+        MsgpackBlock {
+            header: BlockHeader {
+                round: 100,
+                ..BlockHeader::default()
+            },
+            txns: txns(),
+        }
+    }
+
+    fn subscriber(filter: TransactionFilter) -> TransactionSubscriber {
+        let algod = Algod::new("http://localhost", "").unwrap();
+        TransactionSubscriber::new(algod, Arc::new(InMemoryCursorStore::default()), 0)
+            .filter(filter)
+    }
+
+    #[test]
+    fn test_empty_filter_matches_all_transactions() {
+        let txs = subscriber(TransactionFilter::new())
+            .block_transactions(&block())
+            .unwrap();
+
+        assert_eq!(3, txs.len());
+        assert_eq!(b"payment".to_vec(), txs[0].note);
+        assert!(txs[1].inner_path.is_empty());
+        assert_eq!(vec![0], txs[2].inner_path);
+        assert_eq!(1, txs[2].intra_round_offset);
+        assert_eq!(100, txs[2].round);
+    }
+
+    #[test]
+    fn test_filters() {
+        let sender = SENDER.parse().unwrap();
+        let receiver = RECEIVER.parse().unwrap();
+        let matched = |filter: TransactionFilter| {
+            subscriber(filter)
+                .block_transactions(&block())
+                .unwrap()
+                .iter()
+                .map(|tx| tx.tx_type.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![
+                TransactionTypeEnum::Payment,
+                TransactionTypeEnum::AssetTransfer
+            ],
+            matched(TransactionFilter::new().sender(&sender))
+        );
+        assert_eq!(
+            vec![TransactionTypeEnum::Payment],
+            matched(TransactionFilter::new().receiver(&receiver).min_amount(100))
+        );
+        assert_eq!(
+            vec![TransactionTypeEnum::AssetTransfer],
+            matched(TransactionFilter::new().asset_id(7))
+        );
+        assert_eq!(
+            vec![TransactionTypeEnum::ApplicationCall],
+            matched(
+                TransactionFilter::new()
+                    .app_id(42)
+                    .method_selector([1, 2, 3, 4])
+            )
+        );
+        assert_eq!(
+            vec![TransactionTypeEnum::Payment],
+            matched(TransactionFilter::new().note_prefix(b"pay"))
+        );
+        assert!(
+            matched(TransactionFilter::new().tx_type(TransactionTypeEnum::KeyRegistration))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_matches_state_proof_transactions() {
+        let state_proof = Transaction {
+            fee: MicroAlgos(0),
+            note: None,
+            txn_type: TransactionType::StateProofTransaction(StateProofTransaction {
+                sender: SENDER.parse().unwrap(),
+                state_proof_type: StateProofType::StateProofBasic,
+                state_proof: StateProof::default(),
+                message: StateProofMessage::default(),
+            }),
+            ..payment()
+        };
+        let unsigned = SignedTransaction {
+            transaction: state_proof,
+            transaction_id: String::new(),
+            sig: TransactionSignature::Unsigned,
+            auth_address: None,
+        }
+        .to_msg_pack()
+        .unwrap();
+        let block = MsgpackBlock {
+            txns: vec![unsigned, entry(payment())],
+            ..block()
+        };
+
+        let txs = subscriber(TransactionFilter::new().tx_type(TransactionTypeEnum::StateProof))
+            .block_transactions(&block)
+            .unwrap();
+
+        assert_eq!(1, txs.len());
+        assert_eq!(TransactionTypeEnum::StateProof, txs[0].tx_type);
+    }
+
+    #[test]
+    fn test_arc28_event_filter_matches_binary_logs() {
+        let filter = TransactionFilter::new().arc28_event("Swapped(uint64,uint64)");
+        assert_eq!(Some(SWAPPED), filter.arc28_event_selector);
+
+        let txs = subscriber(filter).block_transactions(&block()).unwrap();
+
+        assert_eq!(1, txs.len());
+        assert_eq!(TransactionTypeEnum::ApplicationCall, txs[0].tx_type);
+        assert_eq!(vec![[&SWAPPED[..], &[0; 16]].concat()], txs[0].logs);
+        assert!(
+            subscriber(TransactionFilter::new().arc28_event("Minted(uint64)"))
+                .block_transactions(&block())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_indexer_transaction() {
+        let json = r#"{
+            "confirmed-round": 100,
+            "fee": 1000,
+            "first-valid": 90,
+            "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+            "last-valid": 1090,
+            "intra-round-offset": 3,
+            "sender": "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA",
+            "tx-type": "axfer",
+            "asset-transfer-transaction": {
+                "amount": 10,
+                "asset-id": 7,
+                "receiver": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA"
+            }
+        }"#;
+        let indexer_tx: IndexerTransaction = serde_json::from_str(json).unwrap();
+
+        let tx = SubscribedTransaction::from_indexer(&indexer_tx, 100, 0, vec![]).unwrap();
+
+        assert_eq!(TransactionTypeEnum::AssetTransfer, tx.tx_type);
+        assert_eq!(Some(7), tx.asset_id);
+        assert_eq!(Some(10), tx.amount);
+        assert_eq!(Some(RECEIVER.to_owned()), tx.receiver);
+    }
+
+    /// A node at round 3, with a payment in block 2, failing block requests with `block_status` if it isn't 200.
+    fn node(block_status: u16) -> TestServer {
+        TestServer::start(move |request| {
+            let path = request.path.as_str();
+            if path.starts_with("/v2/status/wait-for-block-after/") {
+                TestResponse::json(&status(3))
+            } else if let Some(round) = path.strip_prefix("/v2/blocks/") {
+                if block_status != 200 {
+                    return TestResponse::error(block_status, "failed");
+                }
+                let round: u64 = round.split('?').next().unwrap().parse().unwrap();
+                let txns = if round == 2 {
+                    vec![entry(payment())]
+                } else {
+                    vec![]
+                };
+                TestResponse::bytes(
+                    [
+                        vec![0x81],
+                        raw_str(b"block"),
+                        vec![0x82],
+                        raw_str(b"rnd"),
+                        rmp_serde::to_vec(&round).unwrap(),
+                        raw_str(b"txns"),
+                        array(&txns),
+                    ]
+                    .concat(),
+                )
+            } else {
+                TestResponse::error(404, "not found")
+            }
+        })
+    }
+
+    fn indexer_node() -> TestServer {
+        TestServer::start(|_| {
+            TestResponse::json(
+                r#"{
+                    "current-round": 3,
+                    "transactions": [{
+                        "confirmed-round": 1,
+                        "fee": 1000,
+                        "genesis-hash": "wGHE2Pwdvd7S12BL5FaOP20EGYesN73ktiC1qzkkit8=",
+                        "first-valid": 1,
+                        "last-valid": 1001,
+                        "intra-round-offset": 0,
+                        "sender": "DPLD3RTSWC5STVBPZL5DIIVE2OC4BSAWTOYBLFN2X6EFLT2ZNF4SMX64UA",
+                        "tx-type": "pay",
+                        "payment-transaction": {
+                            "amount": 5000,
+                            "receiver": "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA"
+                        }
+                    }]
+                }"#,
+            )
+        })
+    }
+
+    fn stream_subscriber(
+        node: &TestServer,
+        indexer: &TestServer,
+        store: Arc<dyn CursorStore>,
+    ) -> TransactionSubscriber {
+        TransactionSubscriber::new(Algod::new(&node.url, "").unwrap(), store, 1)
+            .indexer(Indexer::new(&indexer.url, "").unwrap())
+            .filter(TransactionFilter::new().sender(&SENDER.parse().unwrap()))
+            .max_retries(1)
+            .max_retry_delay_ms(1)
+    }
+
+    #[tokio::test]
+    async fn test_streams_rounds_with_matching_transactions() {
+        let node = node(200);
+        let indexer = indexer_node();
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(stream_subscriber(&node, &indexer, store.clone()).stream());
+
+        let round = stream.next().await.unwrap().unwrap();
+
+        assert_eq!(2, round.round);
+        assert_eq!(1, round.transactions.len());
+        assert!(matches!(
+            round.transactions[0].source,
+            TransactionSource::Algod(_)
+        ));
+        assert_eq!(
+            vec![
+                "/v2/status/wait-for-block-after/0",
+                "/v2/blocks/1?format=msgpack",
+                "/v2/blocks/2?format=msgpack",
+            ],
+            node.paths()
+        );
+        // round 1 is checkpointed, round 2 hasn't been processed yet
+        assert_eq!(Some(2), store.load().await.unwrap());
+        assert!(indexer.paths().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_searches_indexer_if_algod_does_not_have_block() {
+        let node = node(404);
+        let indexer = indexer_node();
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(stream_subscriber(&node, &indexer, store).stream());
+
+        let round = stream.next().await.unwrap().unwrap();
+
+        assert_eq!(1, round.round);
+        assert!(matches!(
+            round.transactions[0].source,
+            TransactionSource::Indexer(_)
+        ));
+        assert_eq!(1, indexer.paths().len());
+    }
+
+    #[tokio::test]
+    async fn test_returns_other_algod_errors_without_searching_indexer() {
+        let node = node(400);
+        let indexer = indexer_node();
+        let store = Arc::new(InMemoryCursorStore::default());
+        let mut stream = Box::pin(stream_subscriber(&node, &indexer, store).stream());
+
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.is_none());
+        assert!(indexer.paths().is_empty());
+    }
+}
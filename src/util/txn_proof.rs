@@ -0,0 +1,234 @@
+use crate::Error;
+use algonaut_algod::{
+    ext::block::Block,
+    models::{get_transaction_proof_200_response::Hashtype, GetTransactionProof200Response},
+};
+use algonaut_crypto::{merkle, HashDigest, HashType};
+use algonaut_transaction::SignedTransaction;
+use std::convert::TryInto;
+
+/// Domain separation prefix of the transaction commitment's leaves.
+const LEAF_PREFIX: &[u8] = b"TL";
+
+/// Verifies a proof returned by [Algod::txn_proof](crate::algod::v2::Algod::txn_proof),
+/// without trusting the node that served it.
+///
+/// Recomputes the transaction's leaf, the hash of its id and the hash of the transaction
+/// as stored in the block (`stibhash`), and checks that the proof leads from it to `txn_commitment`,
+/// the transaction commitment of a trusted block header matching the proof's hash type,
+/// see [txn_commitment].
+///
+/// `signed_transaction` must have its genesis id and hash set, even if the block omits them.
+pub fn verify_txn_proof(
+    signed_transaction: &SignedTransaction,
+    proof: &GetTransactionProof200Response,
+    txn_commitment: &HashDigest,
+) -> Result<(), Error> {
+    let hash_type = hash_type(proof.hashtype);
+    let leaf = txn_leaf(hash_type, signed_transaction, &proof.stibhash.0)?;
+
+    merkle::verify_vector_commitment(
+        hash_type,
+        txn_commitment,
+        &leaf,
+        proof.idx,
+        proof.treedepth,
+        &proof.proof.0,
+    )
+    .map_err(|e| Error::Msg(format!("Invalid transaction proof: {e}")))
+}
+
+/// Returns the transaction commitment of `block` for proofs with the hash type.
+pub fn txn_commitment(block: &Block, hash_type: Hashtype) -> Result<HashDigest, Error> {
+    let commitment = match hash_type {
+        Hashtype::Sha512256 => &block.txn_commitment_sha512_256,
+        Hashtype::Sha256 => &block.txn_commitment,
+    };
+    let commitment = commitment
+        .as_ref()
+        .ok_or_else(|| Error::Msg(format!("Block has no {hash_type:?} transaction commitment")))?;
+    let bytes = algonaut_encoding::decode_base64(commitment.as_bytes())?;
+    Ok(HashDigest(bytes.try_into().map_err(|_| {
+        Error::Msg(format!(
+            "Invalid transaction commitment length: {commitment}"
+        ))
+    })?))
+}
+
+/// The leaf is the hash of the transaction id (hashed with the commitment's hash type)
+/// followed by the hash of the transaction in the block.
+fn txn_leaf(
+    hash_type: HashType,
+    signed_transaction: &SignedTransaction,
+    stib_hash: &[u8],
+) -> Result<HashDigest, Error> {
+    if stib_hash.len() != 32 {
+        return Err(Error::Msg(format!(
+            "Invalid stibhash length: {}",
+            stib_hash.len()
+        )));
+    }
+    let tx_id = merkle::hash(hash_type, &signed_transaction.transaction.bytes_to_sign()?)
+        .map_err(|e| Error::Msg(e.to_string()))?;
+    merkle::hash(hash_type, &[LEAF_PREFIX, &tx_id.0, stib_hash].concat())
+        .map_err(|e| Error::Msg(e.to_string()))
+}
+
+fn hash_type(hash_type: Hashtype) -> HashType {
+    match hash_type {
+        Hashtype::Sha512256 => HashType::Sha512_256,
+        Hashtype::Sha256 => HashType::Sha256,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_fixtures::txn_params;
+    use algonaut_algod::models::TransactionParams200Response;
+    use algonaut_core::MicroAlgos;
+    use algonaut_encoding::Bytes;
+    use algonaut_transaction::{account::Account, Pay, TxnBuilder};
+
+    fn signed_txs(account: &Account) -> Vec<SignedTransaction> {
+        let params = TransactionParams200Response {
+            genesis_hash: HashDigest([1; 32]),
+            genesis_id: "testnet-v1.0".to_owned(),
+            ..txn_params()
+        };
+        (1..=3)
+            .map(|amount| {
+                let tx = TxnBuilder::with(
+                    &params,
+                    Pay::new(account.address(), account.address(), MicroAlgos(amount)).build(),
+                )
+                .build()
+                .unwrap();
+                account.sign_transaction(tx).unwrap()
+            })
+            .collect()
+    }
+
+    fn node(hash_type: HashType, left: &HashDigest, right: &HashDigest) -> HashDigest {
+        merkle::hash(hash_type, &[b"MA".as_slice(), &left.0, &right.0].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_verifies_txn_proofs() {
+        let account = Account::generate();
+        let txs = signed_txs(&account);
+        let stib_hashes: Vec<[u8; 32]> = (0..3).map(|i| [i; 32]).collect();
+
+        for (hashtype, hash_type) in [
+            (Hashtype::Sha512256, HashType::Sha512_256),
+            (Hashtype::Sha256, HashType::Sha256),
+        ] {
+            let leaves: Vec<HashDigest> = txs
+                .iter()
+                .zip(&stib_hashes)
+                .map(|(tx, stib_hash)| txn_leaf(hash_type, tx, stib_hash).unwrap())
+                .collect();
+            // leaf i is at the position with the 2 lowest bits of i reversed
+            let zero = HashDigest::default();
+            let left = node(hash_type, &leaves[0], &leaves[2]);
+            let right = node(hash_type, &leaves[1], &zero);
+            let root = node(hash_type, &left, &right);
+
+            let proof = |idx: u64, siblings: [&HashDigest; 2]| GetTransactionProof200Response {
+                hashtype,
+                idx,
+                proof: Bytes([siblings[0].0, siblings[1].0].concat()),
+                stibhash: Bytes(stib_hashes[idx as usize].to_vec()),
+                treedepth: 2,
+            };
+
+            verify_txn_proof(&txs[0], &proof(0, [&leaves[2], &right]), &root).unwrap();
+            verify_txn_proof(&txs[1], &proof(1, [&zero, &left]), &root).unwrap();
+            verify_txn_proof(&txs[2], &proof(2, [&leaves[0], &right]), &root).unwrap();
+
+            // another transaction, or a tampered stibhash, isn't in the block
+            assert!(verify_txn_proof(&txs[1], &proof(0, [&leaves[2], &right]), &root).is_err());
+            let mut tampered = proof(0, [&leaves[2], &right]);
+            tampered.stibhash = Bytes(vec![9; 32]);
+            assert!(verify_txn_proof(&txs[0], &tampered, &root).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verifies_known_txn_proofs() {
+        // Proofs as algod returns them as JSON, and the commitments they lead to. They're
+        // synthetic: the id and roots were computed without this crate, by encoding the
+        // transaction by hand with go-algorand's canonical msgpack rules and hashing the leaf
+        // and node with Python's hashlib.
+        use algonaut_core::{Address, Round};
+        use algonaut_crypto::Signature;
+        use algonaut_transaction::{transaction::TransactionSignature, Transaction};
+
+        let transaction = Transaction {
+            fee: MicroAlgos(1000),
+            first_valid: Round(100),
+            genesis_hash: HashDigest([1; 32]),
+            last_valid: Round(1100),
+            txn_type: Pay::new(Address([2; 32]), Address([3; 32]), MicroAlgos(5)).build(),
+            genesis_id: Some("testnet-v1.0".to_owned()),
+            group: None,
+            lease: None,
+            note: None,
+            rekey_to: None,
+        };
+        let signed = SignedTransaction {
+            transaction_id: transaction.id().unwrap(),
+            transaction,
+            sig: TransactionSignature::Single(Signature([0; 64])),
+            auth_address: None,
+        };
+        assert_eq!(
+            "LCFG3DI3JLVOILF6FWJ6QVNKFSVYFFC324CXHSCCIDPGGSOZ42UA",
+            signed.transaction_id
+        );
+
+        for (hashtype, root) in [
+            ("sha512_256", "O1aYN0BL3GgLZIUChZhkB4ZgHiZ6ohUZWyye9fHdS6g="),
+            ("sha256", "AU7iHZvpu5RTJMWlHcG6ozC7UGiamF5pRVEouA5kZeo="),
+        ] {
+            let proof: GetTransactionProof200Response = serde_json::from_str(&format!(
+                r#"{{
+                    "hashtype": "{hashtype}",
+                    "idx": 1,
+                    "proof": "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=",
+                    "stibhash": "BAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQ=",
+                    "treedepth": 1
+                }}"#
+            ))
+            .unwrap();
+            let root: HashDigest = root.parse().unwrap();
+
+            verify_txn_proof(&signed, &proof, &root).unwrap();
+            assert!(verify_txn_proof(&signed, &proof, &HashDigest([7; 32])).is_err());
+        }
+    }
+
+    #[test]
+    fn test_reads_txn_commitments_from_block() {
+        let block: Block = serde_json::from_str(
+            r#"{
+                "txn": "O2+OcTdQ1xaV02zKP7OFGFjzRxzDBmwwLGXNBdBq1/Y=",
+                "txn256": "4Wc2EpwiEdNcflxBRoUEaqJxGAjIOkAW2mBmbZX6Ptk="
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "O2+OcTdQ1xaV02zKP7OFGFjzRxzDBmwwLGXNBdBq1/Y=",
+            txn_commitment(&block, Hashtype::Sha512256)
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(
+            "4Wc2EpwiEdNcflxBRoUEaqJxGAjIOkAW2mBmbZX6Ptk=",
+            txn_commitment(&block, Hashtype::Sha256)
+                .unwrap()
+                .to_string()
+        );
+    }
+}
@@ -1,10 +1,16 @@
 use super::sleep;
-use crate::{algod::v2::Algod, Error};
+use crate::{
+    algod::v2::{rejection::AlgodRejection, Algod},
+    Error,
+};
 use algonaut_algod::models::PendingTransactionResponse;
 use instant::Instant;
 use std::time::Duration;
 
 /// Utility to wait for a transaction to be confirmed
+///
+/// Fails if the transaction was removed from the node's pool. See also [wait_for_confirmation],
+/// which waits for a number of rounds instead of a fixed time.
 pub async fn wait_for_pending_transaction(
     algod: &Algod,
     tx_id: &str,
@@ -16,6 +22,8 @@ pub async fn wait_for_pending_transaction(
         // If the transaction has been confirmed or we time out, exit.
         if pending_transaction.confirmed_round.is_some() {
             return Ok(pending_transaction);
+        } else if !pending_transaction.pool_error.is_empty() {
+            return Err(pool_error(pending_transaction));
         } else if start.elapsed() >= timeout {
             return Err(Error::Msg(format!(
                 "Pending transaction timed out ({timeout:?})"
@@ -24,3 +32,223 @@ pub async fn wait_for_pending_transaction(
         sleep(250).await;
     }
 }
+
+/// Waits until the transaction is confirmed, for at most `max_rounds` rounds after the current one.
+///
+/// Instead of polling, waits for each new block with
+/// [Algod::status_after_block](crate::algod::v2::Algod::status_after_block).
+///
+/// Fails immediately if the node removed the transaction from its pool (e.g. because it was rejected
+/// when evaluated in a new block), with an [Error::Algod] whose cause is parsed from the pool error,
+/// and once the transaction's last valid round has passed.
+pub async fn wait_for_confirmation(
+    algod: &Algod,
+    tx_id: &str,
+    max_rounds: u64,
+) -> Result<PendingTransactionResponse, Error> {
+    let mut confirmed = wait_for_group_confirmation(algod, &[tx_id.to_owned()], max_rounds).await?;
+    Ok(confirmed.remove(0))
+}
+
+/// Waits until all transactions of a group are confirmed, see [wait_for_confirmation].
+///
+/// Returns the pending transaction responses in the order of `tx_ids`.
+pub async fn wait_for_group_confirmation(
+    algod: &Algod,
+    tx_ids: &[String],
+    max_rounds: u64,
+) -> Result<Vec<PendingTransactionResponse>, Error> {
+    let mut round = algod.status().await?.last_round;
+    let max_round = round.saturating_add(max_rounds);
+    let mut confirmed: Vec<Option<PendingTransactionResponse>> = vec![None; tx_ids.len()];
+
+    loop {
+        for (tx_id, confirmed) in tx_ids.iter().zip(confirmed.iter_mut()) {
+            if confirmed.is_some() {
+                continue;
+            }
+            let pending_transaction = algod.pending_txn(tx_id).await?;
+            if pending_transaction.confirmed_round.is_some() {
+                *confirmed = Some(pending_transaction);
+                continue;
+            }
+            if !pending_transaction.pool_error.is_empty() {
+                return Err(pool_error(pending_transaction));
+            }
+            // the transaction can't be committed after its last valid round
+            if let Some(last_valid) = last_valid(&pending_transaction) {
+                if round >= last_valid {
+                    return Err(Error::Msg(format!(
+                        "Transaction: {tx_id} expired, its last valid round: {last_valid} has passed"
+                    )));
+                }
+            }
+        }
+
+        if confirmed.iter().all(Option::is_some) {
+            return Ok(confirmed.into_iter().flatten().collect());
+        }
+        if round >= max_round {
+            return Err(Error::Msg(format!(
+                "Transaction not confirmed after {max_rounds} rounds"
+            )));
+        }
+        // returns when the next block is available or after a server side timeout
+        round = algod.status_after_block(round).await?.last_round;
+    }
+}
+
+/// Pool errors are reported like rejections of the transaction, with the status of the response.
+fn pool_error(pending_transaction: PendingTransactionResponse) -> Error {
+    Error::Algod(Box::new(AlgodRejection::new(
+        200,
+        pending_transaction.pool_error,
+    )))
+}
+
+fn last_valid(pending_transaction: &PendingTransactionResponse) -> Option<u64> {
+    pending_transaction.txn.pointer("/txn/lv")?.as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        algod::v2::rejection::AlgodErrorCause,
+        util::{
+            test_fixtures::status,
+            test_server::{TestResponse, TestServer},
+        },
+    };
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    };
+
+    const LAST_VALID: u64 = 1010;
+
+    fn pending(confirmed_round: Option<u64>, pool_error: &str) -> String {
+        let mut pending = serde_json::json!({
+            "pool-error": pool_error,
+            "txn": { "sig": "", "txn": { "fv": 990, "lv": LAST_VALID, "type": "pay" } }
+        });
+        if let Some(round) = confirmed_round {
+            pending["confirmed-round"] = round.into();
+        }
+        pending.to_string()
+    }
+
+    /// A node at `start_round`, advancing a round per `wait-for-block-after` request.
+    /// Transactions are confirmed in their round in `confirmations`, keyed by id.
+    fn node(start_round: u64, confirmations: Vec<(&'static str, u64)>) -> TestServer {
+        let round = Arc::new(AtomicU64::new(start_round));
+        TestServer::start(move |request| {
+            let path = request.path.as_str();
+            if path.starts_with("/v2/status/wait-for-block-after/") {
+                TestResponse::json(&status(round.fetch_add(1, Ordering::SeqCst) + 1))
+            } else if path.starts_with("/v2/status") {
+                TestResponse::json(&status(round.load(Ordering::SeqCst)))
+            } else if let Some(tx_id) = path.strip_prefix("/v2/transactions/pending/") {
+                let tx_id = tx_id.split('?').next().unwrap();
+                let current = round.load(Ordering::SeqCst);
+                match confirmations.iter().find(|(id, _)| *id == tx_id) {
+                    Some((_, confirmed)) if *confirmed <= current => {
+                        TestResponse::json(&pending(Some(*confirmed), ""))
+                    }
+                    Some(_) => TestResponse::json(&pending(None, "")),
+                    None => TestResponse::json(&pending(
+                        None,
+                        &format!("transaction {tx_id}: overspend (account A, data {{}}, tried to spend {{5}})"),
+                    )),
+                }
+            } else {
+                TestResponse::error(404, "not found")
+            }
+        })
+    }
+
+    fn algod(node: &TestServer) -> Algod {
+        Algod::new(&node.url, "").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_confirmation_round() {
+        let node = node(1000, vec![("TX1", 1002)]);
+
+        let pending = wait_for_confirmation(&algod(&node), "TX1", 5)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(1002), pending.confirmed_round);
+        let waits = node
+            .paths()
+            .into_iter()
+            .filter(|p| p.starts_with("/v2/status/wait-for-block-after/"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "/v2/status/wait-for-block-after/1000",
+                "/v2/status/wait-for-block-after/1001"
+            ],
+            waits
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fails_with_pool_error() {
+        let node = node(1000, vec![]);
+
+        let err = wait_for_confirmation(&algod(&node), "TX1", 5)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.algod_cause(),
+            Some(AlgodErrorCause::Overspend {
+                tried_to_spend: Some(5),
+                ..
+            })
+        ));
+        assert!(!node
+            .paths()
+            .iter()
+            .any(|p| p.starts_with("/v2/status/wait-for-block-after/")));
+    }
+
+    #[tokio::test]
+    async fn test_stops_after_max_rounds_or_last_valid() {
+        let node = node(1000, vec![("TX1", 2000)]);
+
+        let err = wait_for_confirmation(&algod(&node), "TX1", 3)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not confirmed after 3 rounds"));
+
+        let node = self::node(LAST_VALID - 1, vec![("TX1", 2000)]);
+
+        let err = wait_for_confirmation(&algod(&node), "TX1", 100)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("expired"));
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_every_txn_of_group() {
+        let node = node(1000, vec![("TX1", 1001), ("TX2", 1003)]);
+
+        let confirmed =
+            wait_for_group_confirmation(&algod(&node), &["TX1".to_owned(), "TX2".to_owned()], 5)
+                .await
+                .unwrap();
+
+        let rounds: Vec<_> = confirmed.iter().map(|p| p.confirmed_round).collect();
+        assert_eq!(vec![Some(1001), Some(1003)], rounds);
+        // confirmed transactions aren't requested again
+        let tx1_requests = node
+            .paths()
+            .iter()
+            .filter(|p| p.starts_with("/v2/transactions/pending/TX1"))
+            .count();
+        assert_eq!(2, tx1_requests);
+    }
+}
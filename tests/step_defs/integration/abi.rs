@@ -3,9 +3,9 @@ use crate::step_defs::{
     util::{read_teal, wait_for_pending_transaction},
 };
 use algonaut::atomic_transaction_composer::{
-    transaction_signer::TransactionSigner, AbiArgValue, AbiMethodReturnValue, AbiReturnDecodeError,
-    AddMethodCallParams, AtomicTransactionComposer, AtomicTransactionComposerStatus,
-    TransactionWithSigner,
+    error::ComposerError, transaction_signer::BasicAccountSigner, AbiArgValue,
+    AbiMethodReturnValue, AbiReturnDecodeError, AddMethodCallParams, AtomicTransactionComposer,
+    AtomicTransactionComposerStatus, TransactionWithSigner,
 };
 use algonaut_abi::{
     abi_interactions::{AbiArgType, AbiMethod, AbiReturn, AbiReturnType, ReferenceArgType},
@@ -23,16 +23,17 @@ use num_traits::ToPrimitive;
 use sha2::Digest;
 use std::convert::TryInto;
 use std::error::Error;
+use std::sync::Arc;
 
 #[given(regex = r#"^I make a transaction signer for the ([^"]*) account\.$"#)]
 #[when(regex = r#"^I make a transaction signer for the ([^"]*) account\.$"#)]
 async fn i_make_a_transaction_signer_for_the_account(w: &mut World, account_str: String) {
-    let signer = TransactionSigner::BasicAccount(match account_str.as_ref() {
+    let signer = BasicAccountSigner(match account_str.as_ref() {
         "transient" => w.transient_account.clone().unwrap(),
         _ => panic!("Not handled account string: {}", account_str),
     });
 
-    w.tx_signer = Some(signer);
+    w.tx_signer = Some(Arc::new(signer));
 }
 
 #[given(expr = "a new AtomicTransactionComposer")]
@@ -108,7 +109,7 @@ async fn i_add_the_current_transaction_with_signer_tothecomposer(w: &mut World)
 async fn i_gather_signatures_with_the_composer(w: &mut World) {
     let tx_composer = w.tx_composer.as_mut().unwrap();
 
-    w.signed_txs = Some(tx_composer.gather_signatures().unwrap());
+    w.signed_txs = Some(tx_composer.gather_signatures().await.unwrap());
 }
 
 #[then(regex = r#"^The composer should have a status of "([^"]*)"\.$"#)]
@@ -464,20 +465,10 @@ fn i_build_the_transaction_group_with_the_composer(w: &mut World, error_type: St
             // no error expected
             build_res.unwrap();
         }
-        "zero group size error" => {
-            let message = match build_res {
-                Ok(_) => None,
-                Err(e) => match e {
-                    algonaut::Error::Msg(m) => Some(m),
-                    _ => None,
-                },
-            };
-
-            match message.as_deref() {
-                Some("attempting to build group with zero transactions") => {}
-                _ => panic!("expected error, but got: {:?}", message),
-            }
-        }
+        "zero group size error" => match build_res {
+            Err(algonaut::Error::Composer(ComposerError::EmptyGroup)) => {}
+            res => panic!("expected zero group size error, but got: {:?}", res),
+        },
         _ => panic!("Unknown error type: {}", error_type),
     }
 }
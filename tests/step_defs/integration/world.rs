@@ -11,6 +11,7 @@ use algonaut_algod::models::TransactionParams200Response;
 use algonaut_core::Address;
 use algonaut_transaction::{account::Account, SignedTransaction, Transaction};
 use cucumber;
+use std::sync::Arc;
 
 #[derive(Default, Debug, cucumber::World)]
 pub struct World {
@@ -33,7 +34,7 @@ pub struct World {
 
     pub note: Option<Vec<u8>>,
 
-    pub tx_signer: Option<TransactionSigner>,
+    pub tx_signer: Option<Arc<dyn TransactionSigner>>,
     pub tx_with_signer: Option<TransactionWithSigner>,
     pub tx_composer: Option<AtomicTransactionComposer>,
     pub tx_composer_methods: Option<Vec<AbiMethod>>,